@@ -1,10 +1,12 @@
 mod main_menu;
 mod mob;
 mod player;
+mod resource_pack;
 mod world;
 
 use crate::main_menu::MainMenuPlugin;
 use crate::player::PlayerPlugin;
+use crate::resource_pack::ResourcePackPlugin;
 use crate::world::WorldPlugin;
 use bevy::prelude::*;
 use bevy::window::{CursorGrabMode, CursorOptions, PresentMode};
@@ -12,6 +14,8 @@ use bevy_rapier3d::prelude::*;
 
 fn main() {
     App::new()
+        // Registers an asset source, which must happen before the AssetPlugin
+        .add_plugins(ResourcePackPlugin)
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "EXPLR".into(),
@@ -51,6 +55,8 @@ fn grab_cursor(
     mouse_input: Res<ButtonInput<MouseButton>>,
     key_input: Res<ButtonInput<KeyCode>>,
     settings_menu: Query<&Visibility, With<crate::player::settings_menu::SettingsMenu>>,
+    open_container: Res<crate::player::container_ui::OpenContainer>,
+    dead_query: Query<(), With<crate::player::components::Dead>>,
     app_state: Res<State<crate::main_menu::AppState>>,
 ) {
     if *app_state.get() != crate::main_menu::AppState::InGame {
@@ -65,7 +71,9 @@ fn grab_cursor(
         let Ok(menu_visibility) = settings_menu.single() else {
             return;
         };
-        let menu_visible = *menu_visibility != Visibility::Hidden;
+        let menu_visible = *menu_visibility != Visibility::Hidden
+            || open_container.is_open()
+            || !dead_query.is_empty();
 
         if menu_visible {
             cursor.grab_mode = CursorGrabMode::None;
@@ -18,6 +18,20 @@ pub struct WorldSettings {
     pub seed: u64,
     pub player_position: Option<Vec3>,
     pub inventory: Option<crate::player::components::Inventory>,
+    /// Seconds the world has been played for, which block entities measure
+    /// the time they spent unloaded against.
+    #[serde(default)]
+    pub play_time: f64,
+    /// Where new players appear, and where players respawn without a
+    /// respawn point of their own.
+    #[serde(default)]
+    pub spawn_point: Option<Vec3>,
+    /// Where the player respawns after dying, set with `/spawnpoint`.
+    #[serde(default)]
+    pub respawn_point: Option<Vec3>,
+    /// World rule: whether dying keeps the inventory instead of scattering it.
+    #[serde(default)]
+    pub keep_inventory: bool,
 }
 
 #[derive(Component)]
@@ -51,13 +65,18 @@ impl Plugin for MainMenuPlugin {
 
 fn save_inventory_on_exit(
     player_query: Query<
-        (&Transform, &crate::player::components::Inventory),
+        (
+            &Transform,
+            &crate::player::components::Inventory,
+            Has<crate::player::components::Dead>,
+        ),
         With<crate::player::components::Player>,
     >,
     mut world_settings: ResMut<WorldSettings>,
 ) {
-    if let Ok((transform, inventory)) = player_query.single() {
-        world_settings.player_position = Some(transform.translation);
+    if let Ok((transform, inventory, dead)) = player_query.single() {
+        // A dead player comes back at their respawn point
+        world_settings.player_position = (!dead).then_some(transform.translation);
         world_settings.inventory = Some(inventory.clone());
         save_world_settings(&world_settings);
     }
@@ -474,7 +493,7 @@ fn handle_input(
     }
 }
 
-fn get_worlds_dir() -> PathBuf {
+pub fn get_worlds_dir() -> PathBuf {
     let mut path = home::home_dir().unwrap_or_else(|| PathBuf::from("."));
     path.push(".explr");
     path.push("worlds");
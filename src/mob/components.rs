@@ -6,6 +6,8 @@ pub struct Mob {
     pub max_speed: f32,
     pub wander_timer: f32,
     pub attack_cooldown: f32,
+    /// Seconds left of being knocked back, during which it doesn't steer.
+    pub knockback_timer: f32,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -17,6 +17,7 @@ impl Plugin for MobPlugin {
                 mob_behavior_system,
                 mob_movement_system,
                 handle_kill_events,
+                despawn_dead_mobs,
             )
                 .chain()
                 .run_if(in_state(crate::main_menu::AppState::InGame)),
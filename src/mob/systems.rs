@@ -1,7 +1,10 @@
 use crate::mob::components::{Mob, MobBehavior, MobSpawner, MobState, MobType};
-use crate::player::components::{Health, Player};
+use crate::player::components::{DamageCause, Health, Player};
 use crate::player::inventory_ui::KillEvent;
+use crate::player::systems::spawn_drop_item;
 use crate::world::components::{GameTime, InGameEntity};
+use crate::world::items::ItemRegistry;
+use crate::world::systems::BlockAssets;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use rand::{Rng, thread_rng};
@@ -44,11 +47,9 @@ pub fn spawn_mob_typed(
             },
             wander_timer: 0.0,
             attack_cooldown: 0.0,
+            knockback_timer: 0.0,
         },
-        Health {
-            current: health,
-            max: health,
-        },
+        Health::new(health),
         MobState::default(),
         RigidBody::Dynamic,
         Collider::cuboid(size.x, size.y, size.z),
@@ -150,6 +151,7 @@ pub fn mob_behavior_system(
 
     for (entity, mut mob, mut state, transform, material_handle) in mob_query.iter_mut() {
         state.timer -= time.delta_secs();
+        mob.knockback_timer = (mob.knockback_timer - time.delta_secs()).max(0.0);
 
         match state.state {
             MobBehavior::Idle => {
@@ -194,7 +196,7 @@ pub fn mob_behavior_system(
                     && dist < 1.2
                     && mob.attack_cooldown <= 0.0
                 {
-                    player_health.current = player_health.current.saturating_sub(1);
+                    player_health.damage(1, DamageCause::Mob(mob.mob_type));
                     mob.attack_cooldown = 1.0; // 1 second cooldown
                     println!("Slime attacked player! Health: {}", player_health.current);
                 }
@@ -313,6 +315,10 @@ pub fn mob_movement_system(
                 continue;
             }
         }
+        // Let a knockback play out before steering again
+        if mob.knockback_timer > 0.0 {
+            continue;
+        }
 
         let mut move_dir = Vec3::ZERO;
         let mut should_jump = false;
@@ -471,3 +477,27 @@ pub fn handle_kill_events(
         }
     }
 }
+
+/// Removes mobs whose health ran out. Cows leave beef behind.
+pub fn despawn_dead_mobs(
+    mut commands: Commands,
+    mob_query: Query<(Entity, &Mob, &Health, &Transform)>,
+    items: Res<ItemRegistry>,
+    block_assets: Res<BlockAssets>,
+) {
+    let mut rng = thread_rng();
+    for (entity, mob, health, transform) in mob_query.iter() {
+        if health.current > 0 {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        if mob.mob_type == MobType::Cow
+            && let Some(beef) = items.by_name("beef")
+        {
+            let pos = transform.translation.floor().as_ivec3();
+            for _ in 0..rng.gen_range(1..=3) {
+                spawn_drop_item(&mut commands, &block_assets, pos, beef);
+            }
+        }
+    }
+}
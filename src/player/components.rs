@@ -1,6 +1,10 @@
+use crate::mob::components::MobType;
+use crate::world::block_entity::EMPTY_SLOT;
 use crate::world::components::ItemType;
+use crate::world::crafting::MAX_GRID_SIZE;
+use crate::world::items::Food;
 use bevy::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Component, Default)]
 pub struct MiningProgress {
@@ -12,6 +16,10 @@ pub struct MiningProgress {
 #[derive(Component)]
 pub struct DespawnMiningEffect;
 
+/// Seconds until the player can attack again.
+#[derive(Component, Default)]
+pub struct AttackCooldown(pub f32);
+
 #[derive(Component)]
 pub struct Player;
 
@@ -19,16 +27,91 @@ pub struct Player;
 pub struct Health {
     pub current: i32,
     pub max: i32,
+    /// What last hurt it, shown on the death screen.
+    pub last_damage: Option<DamageCause>,
+}
+
+impl Health {
+    pub fn new(max: i32) -> Self {
+        Self {
+            current: max,
+            max,
+            last_damage: None,
+        }
+    }
+
+    pub fn damage(&mut self, amount: i32, cause: DamageCause) {
+        self.current = (self.current - amount).max(0);
+        self.last_damage = Some(cause);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DamageCause {
+    Fall,
+    Starvation,
+    Explosion,
+    Player,
+    Mob(MobType),
+}
+
+impl DamageCause {
+    pub fn death_message(self) -> &'static str {
+        match self {
+            DamageCause::Fall => "You hit the ground too hard",
+            DamageCause::Starvation => "You starved to death",
+            DamageCause::Explosion => "You blew up",
+            DamageCause::Player => "You were slain",
+            DamageCause::Mob(MobType::Cow) => "You were slain by a cow",
+            DamageCause::Mob(MobType::Slime) => "You were slain by a slime",
+        }
+    }
 }
 
+/// Marks a player whose health ran out, until they respawn from the death
+/// screen.
+#[derive(Component)]
+pub struct Dead;
+
 #[derive(Component)]
 pub struct Hunger {
     pub current: i32,
     pub max: i32,
-    pub timer: f32,
+    /// Fullness used up before `current` is, never more than `current`.
+    pub saturation: f32,
+    /// Effort from sprinting, jumping, mining and healing. Each
+    /// `EXHAUSTION_PER_POINT` of it costs a point of saturation or hunger.
+    pub exhaustion: f32,
+    /// Seconds toward regenerating the next point of health.
+    pub regen_timer: f32,
     pub damage_timer: f32,
 }
 
+/// Exhaustion that costs a point of saturation, or of hunger without any.
+pub const EXHAUSTION_PER_POINT: f32 = 4.0;
+
+impl Hunger {
+    pub fn exhaust(&mut self, amount: f32) {
+        self.exhaustion += amount;
+    }
+
+    /// Restores hunger and saturation from eating `food`.
+    pub fn eat(&mut self, food: Food) {
+        self.current = (self.current + food.hunger).min(self.max);
+        self.saturation = (self.saturation + food.saturation).min(self.current as f32);
+    }
+}
+
+/// Eating the selected item while right click is held.
+#[derive(Component, Default)]
+pub struct EatingProgress {
+    /// Hotbar slot being eaten from; eating stops if the selection changes.
+    pub slot: Option<usize>,
+    pub timer: f32,
+    /// Seconds until the next chewing sound.
+    pub sound_timer: f32,
+}
+
 #[derive(Component)]
 pub struct FootstepTimer {
     pub timer: Timer,
@@ -75,12 +158,51 @@ impl Default for CharacterController {
 pub struct InventorySlot {
     pub item_type: ItemType,
     pub count: u32,
+    /// Uses worn off a tool, which breaks once they reach its durability.
+    #[serde(default)]
+    pub damage: u32,
+}
+
+/// Slots in the hotbar, which come first in `Inventory::slots`.
+pub const HOTBAR_SLOTS: usize = 10;
+/// Slots in the storage rows of the inventory screen, after the hotbar.
+pub const STORAGE_SLOTS: usize = 27;
+pub const INVENTORY_SLOTS: usize = HOTBAR_SLOTS + STORAGE_SLOTS;
+/// Cells of the largest crafting grid, the crafting table's.
+pub const CRAFTING_SLOTS: usize = MAX_GRID_SIZE * MAX_GRID_SIZE;
+
+fn empty_slot() -> InventorySlot {
+    EMPTY_SLOT
+}
+
+fn empty_crafting_grid() -> Vec<InventorySlot> {
+    vec![EMPTY_SLOT; CRAFTING_SLOTS]
+}
+
+/// Worlds saved before the storage rows only have the hotbar's slots.
+fn deserialize_slots<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<InventorySlot>, D::Error> {
+    let mut slots = Vec::<InventorySlot>::deserialize(deserializer)?;
+    slots.resize(INVENTORY_SLOTS, EMPTY_SLOT);
+    Ok(slots)
 }
 
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct Inventory {
-    pub slots: [InventorySlot; 10],
+    /// The hotbar, then the storage rows.
+    #[serde(deserialize_with = "deserialize_slots")]
+    pub slots: Vec<InventorySlot>,
     pub selected_slot: usize,
+    #[serde(default = "empty_slot")]
+    pub offhand: InventorySlot,
+    /// The stack picked up with the cursor while a menu is open.
+    #[serde(default = "empty_slot")]
+    pub held: InventorySlot,
+    /// The open crafting grid's cells, row by row as wide as the grid.
+    /// Like `held`, emptied back into the inventory when the menu closes.
+    #[serde(default = "empty_crafting_grid")]
+    pub crafting: Vec<InventorySlot>,
 }
 
 #[derive(Component)]
@@ -88,23 +210,19 @@ pub struct InventorySlotIcon(pub usize);
 
 impl Default for Inventory {
     fn default() -> Self {
-        let slots = [InventorySlot {
-            item_type: ItemType::None,
-            count: 0,
-        }; 10];
         Self {
-            slots,
+            slots: vec![EMPTY_SLOT; INVENTORY_SLOTS],
             selected_slot: 0,
+            offhand: EMPTY_SLOT,
+            held: EMPTY_SLOT,
+            crafting: empty_crafting_grid(),
         }
     }
 }
 
 impl Default for Health {
     fn default() -> Self {
-        Self {
-            current: 20,
-            max: 20,
-        }
+        Self::new(20)
     }
 }
 
@@ -113,7 +231,9 @@ impl Default for Hunger {
         Self {
             current: 20,
             max: 20,
-            timer: 0.0,
+            saturation: 5.0,
+            exhaustion: 0.0,
+            regen_timer: 0.0,
             damage_timer: 0.0,
         }
     }
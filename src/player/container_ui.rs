@@ -0,0 +1,1252 @@
+use bevy::ecs::system::SystemParam;
+use bevy::image::TRANSPARENT_IMAGE_HANDLE;
+use bevy::prelude::*;
+use bevy::ui::{FocusPolicy, RelativeCursorPosition};
+use bevy::window::PrimaryWindow;
+
+use crate::player::components::{
+    CameraController, Dead, HOTBAR_SLOTS, INVENTORY_SLOTS, Inventory, InventorySlot, Player,
+};
+use crate::player::inventory_ui::{CommandState, InventoryIconAssets};
+use crate::player::resources::SoundAssets;
+use crate::player::settings_menu::{Settings, SettingsMenu};
+use crate::player::systems::{play_sound, throw_items};
+use crate::resource_pack::ResourcePacks;
+use crate::world::block_entity::{BlockEntity, CHEST_SLOTS, EMPTY_SLOT, chunk_for};
+use crate::world::blocks::{BlockEntityKind, BlockRegistry, UseBehavior};
+use crate::world::components::{Chunk, InGameEntity, ItemType};
+use crate::world::crafting::{MAX_GRID_SIZE, Recipe, RecipeRegistry};
+use crate::world::items::ItemRegistry;
+use crate::world::resources::VoxelWorld;
+use crate::world::smelting::{FURNACE_FUEL, FURNACE_INPUT, FURNACE_OUTPUT, SmeltingRegistry};
+use crate::world::systems::BlockAssets;
+
+/// What the container menu is laid out for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MenuLayout {
+    /// The player's own inventory screen, with a 2x2 crafting grid.
+    Inventory,
+    CraftingTable,
+    Container(BlockEntityKind),
+}
+
+impl MenuLayout {
+    /// Width of its crafting grid, or 0 if it has none.
+    pub fn grid_size(self) -> usize {
+        match self {
+            MenuLayout::Inventory => 2,
+            MenuLayout::CraftingTable => MAX_GRID_SIZE,
+            MenuLayout::Container(_) => 0,
+        }
+    }
+}
+
+/// What the container menu shows, if it's open, and the block it was
+/// opened from, if any.
+#[derive(Resource, Default)]
+pub struct OpenContainer {
+    pub pos: Option<IVec3>,
+    pub layout: Option<MenuLayout>,
+    /// Whether the recipe book is shown beside crafting grids. It stays
+    /// open or closed between menus.
+    pub recipe_book: bool,
+}
+
+impl OpenContainer {
+    pub fn is_open(&self) -> bool {
+        self.layout.is_some()
+    }
+
+    pub fn open_block(&mut self, pos: IVec3, layout: MenuLayout) {
+        self.pos = Some(pos);
+        self.layout = Some(layout);
+    }
+
+    pub fn close(&mut self) {
+        self.pos = None;
+        self.layout = None;
+    }
+}
+
+/// The slots a held stack is being dragged across, and with which button.
+#[derive(Resource, Default)]
+pub struct SlotDrag {
+    button: Option<MouseButton>,
+    slots: Vec<ContainerSlot>,
+}
+
+#[derive(Component)]
+pub struct ContainerMenu;
+
+/// The menu's window. Clicking outside it with a held stack throws it.
+#[derive(Component)]
+pub struct ContainerWindow;
+
+#[derive(Component)]
+pub struct ContainerTitle;
+
+/// The label over the player's slots, left out on the inventory screen
+/// where the title says it.
+#[derive(Component)]
+pub struct InventoryLabel;
+
+/// The held stack, drawn at the cursor.
+#[derive(Component)]
+pub struct HeldStack;
+
+/// The slots shown for one layout of the menu.
+#[derive(Component)]
+pub struct ContainerPanel(pub MenuLayout);
+
+#[derive(Component)]
+pub struct RecipeBookButton;
+
+/// The recipe book's page, listing every recipe the open grid fits.
+#[derive(Component)]
+pub struct RecipeBook;
+
+/// A recipe's button in the recipe book, by index in the registry.
+#[derive(Component)]
+pub struct RecipeEntry(pub usize);
+
+/// The result icon on a recipe's button.
+#[derive(Component)]
+pub struct RecipeIcon(pub usize);
+
+/// The fill of one of the furnace's progress bars.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum FurnaceBar {
+    Burn,
+    Cook,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SlotOwner {
+    Container,
+    /// `Inventory::slots`: the hotbar, then storage.
+    Player,
+    Offhand,
+    /// The stack held at the cursor.
+    Held,
+    /// `Inventory::crafting`.
+    Crafting,
+    /// What the crafting grid makes.
+    CraftResult,
+}
+
+/// Marks a slot's button, icon and count in the container menu.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ContainerSlot {
+    pub owner: SlotOwner,
+    pub index: usize,
+}
+
+/// Moves as much of `from` as fits into `into`, topping up stacks of the
+/// same item before filling empty slots.
+pub fn transfer_stack(from: &mut InventorySlot, into: &mut [InventorySlot], items: &ItemRegistry) {
+    if from.item_type == ItemType::NONE {
+        return;
+    }
+    let max_stack = items.max_stack(from.item_type);
+
+    for slot in into
+        .iter_mut()
+        .filter(|slot| slot.item_type == from.item_type)
+    {
+        let moved = from.count.min(max_stack.saturating_sub(slot.count));
+        slot.count += moved;
+        from.count -= moved;
+    }
+    for slot in into.iter_mut() {
+        if from.count == 0 {
+            break;
+        }
+        if slot.item_type == ItemType::NONE {
+            let moved = from.count.min(max_stack);
+            *slot = InventorySlot {
+                item_type: from.item_type,
+                count: moved,
+                damage: from.damage,
+            };
+            from.count -= moved;
+        }
+    }
+
+    if from.count == 0 {
+        from.item_type = ItemType::NONE;
+    }
+}
+
+fn spawn_slot(parent: &mut ChildSpawnerCommands, slot: ContainerSlot) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(40.0),
+                height: Val::Px(40.0),
+                margin: UiRect::all(Val::Px(4.5)),
+                border: UiRect::all(Val::Px(2.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.3, 0.3, 0.3, 1.0)),
+            BorderColor::all(Color::WHITE),
+            RelativeCursorPosition::default(),
+            slot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    width: Val::Px(20.0),
+                    height: Val::Px(20.0),
+                    ..default()
+                },
+                ImageNode::new(TRANSPARENT_IMAGE_HANDLE),
+                slot,
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 15.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                slot,
+            ));
+        });
+}
+
+fn spawn_slot_row(
+    parent: &mut ChildSpawnerCommands,
+    owner: SlotOwner,
+    indices: std::ops::Range<usize>,
+) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            ..default()
+        })
+        .with_children(|parent| {
+            for index in indices {
+                spawn_slot(parent, ContainerSlot { owner, index });
+            }
+        });
+}
+
+fn spawn_labeled_slot(parent: &mut ChildSpawnerCommands, label: &str, slot: ContainerSlot) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    width: Val::Px(50.0),
+                    ..default()
+                },
+            ));
+            spawn_slot(parent, slot);
+        });
+}
+
+fn spawn_progress_bar(parent: &mut ChildSpawnerCommands, bar: FurnaceBar, color: Color) {
+    parent
+        .spawn((
+            Node {
+                width: Val::Px(80.0),
+                height: Val::Px(10.0),
+                margin: UiRect::all(Val::Px(4.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 1.0)),
+            BorderColor::all(Color::WHITE),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(color),
+                bar,
+            ));
+        });
+}
+
+fn spawn_furnace_panel(parent: &mut ChildSpawnerCommands) {
+    parent
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(10.0),
+                display: Display::None,
+                ..default()
+            },
+            ContainerPanel(MenuLayout::Container(BlockEntityKind::Furnace)),
+        ))
+        .with_children(|parent| {
+            let furnace_slot = |index| ContainerSlot {
+                owner: SlotOwner::Container,
+                index,
+            };
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_labeled_slot(parent, "Input", furnace_slot(FURNACE_INPUT));
+                    spawn_progress_bar(parent, FurnaceBar::Burn, Color::srgb(1.0, 0.5, 0.1));
+                    spawn_labeled_slot(parent, "Fuel", furnace_slot(FURNACE_FUEL));
+                });
+            spawn_progress_bar(parent, FurnaceBar::Cook, Color::WHITE);
+            spawn_slot(
+                parent,
+                ContainerSlot {
+                    owner: SlotOwner::Container,
+                    index: FURNACE_OUTPUT,
+                },
+            );
+        });
+}
+
+fn spawn_held_stack(parent: &mut ChildSpawnerCommands) {
+    let slot = ContainerSlot {
+        owner: SlotOwner::Held,
+        index: 0,
+    };
+    parent
+        .spawn((
+            Node {
+                width: Val::Px(40.0),
+                height: Val::Px(40.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                ..default()
+            },
+            HeldStack,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    width: Val::Px(20.0),
+                    height: Val::Px(20.0),
+                    ..default()
+                },
+                ImageNode::new(TRANSPARENT_IMAGE_HANDLE),
+                slot,
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 15.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                slot,
+            ));
+        });
+}
+
+/// A `size` wide crafting grid, its result and the recipe book's button.
+fn spawn_crafting_grid(parent: &mut ChildSpawnerCommands, size: usize, book_icon: Handle<Image>) {
+    parent
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for row in 0..size {
+                        let cells = row * size..row * size + size;
+                        spawn_slot_row(parent, SlotOwner::Crafting, cells);
+                    }
+                });
+            parent.spawn((
+                Text::new("->"),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            spawn_slot(
+                parent,
+                ContainerSlot {
+                    owner: SlotOwner::CraftResult,
+                    index: 0,
+                },
+            );
+            parent.spawn((
+                Button,
+                Node {
+                    width: Val::Px(40.0),
+                    height: Val::Px(36.0),
+                    ..default()
+                },
+                ImageNode::new(book_icon),
+                RecipeBookButton,
+            ));
+        });
+}
+
+fn spawn_recipe_book(
+    parent: &mut ChildSpawnerCommands,
+    background: Handle<Image>,
+    recipes: &RecipeRegistry,
+) {
+    parent
+        .spawn((
+            Node {
+                width: Val::Px(260.0),
+                height: Val::Px(300.0),
+                margin: UiRect::right(Val::Px(10.0)),
+                padding: UiRect::all(Val::Px(16.0)),
+                flex_wrap: FlexWrap::Wrap,
+                align_content: AlignContent::FlexStart,
+                display: Display::None,
+                ..default()
+            },
+            // The page, without the tabs and buttons around it
+            ImageNode::new(background).with_rect(Rect::new(1.0, 1.0, 148.0, 167.0)),
+            RelativeCursorPosition::default(),
+            ContainerWindow,
+            RecipeBook,
+        ))
+        .with_children(|parent| {
+            for index in 0..recipes.iter().count() {
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(28.0),
+                            height: Val::Px(28.0),
+                            margin: UiRect::all(Val::Px(2.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.6, 0.6, 0.6, 1.0)),
+                        RecipeEntry(index),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Node {
+                                width: Val::Px(20.0),
+                                height: Val::Px(20.0),
+                                ..default()
+                            },
+                            ImageNode::new(TRANSPARENT_IMAGE_HANDLE),
+                            // Let clicks through to the entry's button
+                            FocusPolicy::Pass,
+                            RecipeIcon(index),
+                        ));
+                    });
+            }
+        });
+}
+
+pub fn setup_container_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    packs: Res<ResourcePacks>,
+    recipes: Res<RecipeRegistry>,
+) {
+    commands.insert_resource(OpenContainer::default());
+    commands.insert_resource(SlotDrag::default());
+    let book_icon = packs.load_image(&asset_server, "textures/gui/sprites/recipe_book/button.png");
+    let book_page = packs.load_image(&asset_server, "textures/gui/recipe_book.png");
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Vw(100.0),
+                height: Val::Vh(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ContainerMenu,
+            InGameEntity,
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            spawn_recipe_book(parent, book_page, &recipes);
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        border: UiRect::all(Val::Px(2.0)),
+                        row_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.95)),
+                    BorderColor::all(Color::WHITE),
+                    RelativeCursorPosition::default(),
+                    ContainerWindow,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(""),
+                        TextFont {
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        ContainerTitle,
+                    ));
+                    parent
+                        .spawn((
+                            Node {
+                                flex_direction: FlexDirection::Column,
+                                display: Display::None,
+                                ..default()
+                            },
+                            ContainerPanel(MenuLayout::Container(BlockEntityKind::Chest)),
+                        ))
+                        .with_children(|parent| {
+                            for row in 0..CHEST_SLOTS / 9 {
+                                spawn_slot_row(parent, SlotOwner::Container, row * 9..row * 9 + 9);
+                            }
+                        });
+                    spawn_furnace_panel(parent);
+                    parent
+                        .spawn((
+                            Node {
+                                align_items: AlignItems::Center,
+                                column_gap: Val::Px(20.0),
+                                display: Display::None,
+                                ..default()
+                            },
+                            ContainerPanel(MenuLayout::Inventory),
+                        ))
+                        .with_children(|parent| {
+                            let offhand = ContainerSlot {
+                                owner: SlotOwner::Offhand,
+                                index: 0,
+                            };
+                            spawn_labeled_slot(parent, "Offhand", offhand);
+                            spawn_crafting_grid(parent, 2, book_icon.clone());
+                        });
+                    parent
+                        .spawn((
+                            Node {
+                                display: Display::None,
+                                ..default()
+                            },
+                            ContainerPanel(MenuLayout::CraftingTable),
+                        ))
+                        .with_children(|parent| {
+                            spawn_crafting_grid(parent, MAX_GRID_SIZE, book_icon.clone());
+                        });
+
+                    parent.spawn((
+                        Text::new("Inventory"),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        Node::default(),
+                        InventoryLabel,
+                    ));
+                    for row in (HOTBAR_SLOTS..INVENTORY_SLOTS).step_by(9) {
+                        spawn_slot_row(parent, SlotOwner::Player, row..row + 9);
+                    }
+                    spawn_slot_row(parent, SlotOwner::Player, 0..HOTBAR_SLOTS);
+                });
+            spawn_held_stack(parent);
+        });
+}
+
+/// Opens the inventory screen on E while alive, and closes whatever the menu
+/// shows on E or Escape. Once the menu is closed, a stack still held and
+/// whatever is left in the crafting grid go back into the inventory, and
+/// whatever doesn't fit is thrown.
+#[allow(clippy::too_many_arguments)]
+pub fn toggle_container_menu(
+    mut commands: Commands,
+    key: Res<ButtonInput<KeyCode>>,
+    mut open_container: ResMut<OpenContainer>,
+    command_state: Res<CommandState>,
+    settings_menu: Query<&Visibility, With<SettingsMenu>>,
+    mut inventory_query: Query<&mut Inventory, With<Player>>,
+    dead_query: Query<(), (With<Player>, With<Dead>)>,
+    eye_query: Query<&GlobalTransform, With<CameraController>>,
+    items: Res<ItemRegistry>,
+    block_assets: Res<BlockAssets>,
+    sound_assets: Res<SoundAssets>,
+    settings: Res<Settings>,
+) {
+    if open_container.is_open() {
+        if key.just_pressed(KeyCode::KeyE) || key.just_pressed(KeyCode::Escape) {
+            if let Some(MenuLayout::Container(_)) = open_container.layout {
+                play_sound(
+                    &mut commands,
+                    sound_assets.chest_close.clone(),
+                    settings.master_volume,
+                );
+            }
+            open_container.close();
+        }
+    } else if key.just_pressed(KeyCode::KeyE)
+        && !command_state.open
+        && dead_query.is_empty()
+        && settings_menu
+            .single()
+            .is_ok_and(|visibility| *visibility == Visibility::Hidden)
+    {
+        open_container.layout = Some(MenuLayout::Inventory);
+    }
+
+    if open_container.is_open() {
+        return;
+    }
+    let Ok(mut inventory) = inventory_query.single_mut() else {
+        return;
+    };
+    let loose = |slot: &InventorySlot| slot.item_type != ItemType::NONE;
+    if !loose(&inventory.held) && !inventory.crafting.iter().any(loose) {
+        return;
+    }
+    let Inventory {
+        slots,
+        held,
+        crafting,
+        ..
+    } = &mut *inventory;
+    for stack in crafting.iter_mut().chain(std::iter::once(held)) {
+        transfer_stack(stack, slots, &items);
+        if let Ok(eye) = eye_query.single() {
+            let count = stack.count;
+            throw_items(&mut commands, &block_assets, eye, stack, count);
+        }
+    }
+}
+
+/// What the first `size` by `size` cells of the crafting grid make.
+fn crafting_result(
+    inventory: &Inventory,
+    size: usize,
+    recipes: &RecipeRegistry,
+) -> Option<InventorySlot> {
+    let grid: Vec<ItemType> = inventory.crafting[..size * size]
+        .iter()
+        .map(|cell| cell.item_type)
+        .collect();
+    let recipe = recipes.find(&grid, size)?;
+    Some(InventorySlot {
+        item_type: recipe.result,
+        count: recipe.count,
+        damage: 0,
+    })
+}
+
+/// Uses up one item from each cell of the crafting grid.
+fn use_up_grid(inventory: &mut Inventory) {
+    for cell in inventory.crafting.iter_mut() {
+        cell.count = cell.count.saturating_sub(1);
+        if cell.count == 0 {
+            cell.item_type = ItemType::NONE;
+        }
+    }
+}
+
+/// Whether the inventory and grid hold everything `recipe` takes.
+fn can_make(inventory: &Inventory, recipe: &Recipe) -> bool {
+    recipe.item_counts().into_iter().all(|(item, needed)| {
+        let have: u32 = inventory
+            .slots
+            .iter()
+            .chain(&inventory.crafting)
+            .filter(|slot| slot.item_type == item)
+            .map(|slot| slot.count)
+            .sum();
+        have >= needed
+    })
+}
+
+/// Lays `recipe` out in a `size` wide crafting grid with items from the
+/// inventory, after putting back what the grid held. Storage is drawn on
+/// before the hotbar.
+fn fill_grid(inventory: &mut Inventory, recipe: &Recipe, size: usize, items: &ItemRegistry) {
+    let Inventory {
+        slots, crafting, ..
+    } = inventory;
+    for cell in crafting.iter_mut() {
+        transfer_stack(cell, slots, items);
+    }
+    if crafting.iter().any(|cell| cell.item_type != ItemType::NONE) {
+        return;
+    }
+    if !can_make(inventory, recipe) {
+        return;
+    }
+
+    for (cell, item) in recipe.placement(size) {
+        if let Some(from) = inventory
+            .slots
+            .iter_mut()
+            .rev()
+            .find(|slot| slot.item_type == item)
+        {
+            move_onto(&mut inventory.crafting[cell], from, 1, items);
+        }
+    }
+}
+
+/// The slot `slot` stands for, in the player's inventory or the open block.
+fn slot_mut<'a>(
+    slot: ContainerSlot,
+    inventory: &'a mut Inventory,
+    block_entity: Option<&'a mut BlockEntity>,
+) -> Option<&'a mut InventorySlot> {
+    match slot.owner {
+        SlotOwner::Container => block_entity?.slots_mut().get_mut(slot.index),
+        SlotOwner::Player => inventory.slots.get_mut(slot.index),
+        SlotOwner::Offhand => Some(&mut inventory.offhand),
+        SlotOwner::Held => Some(&mut inventory.held),
+        SlotOwner::Crafting => inventory.crafting.get_mut(slot.index),
+        SlotOwner::CraftResult => None,
+    }
+}
+
+/// Whether `item` may be put into `slot` by hand.
+fn accepts(
+    slot: ContainerSlot,
+    item: ItemType,
+    block_entity: Option<&BlockEntity>,
+    smelting: &SmeltingRegistry,
+) -> bool {
+    match slot.owner {
+        SlotOwner::Container => block_entity.is_some_and(|block_entity| {
+            block_entity
+                .slots_accepting(item, smelting)
+                .contains(&slot.index)
+        }),
+        SlotOwner::Player | SlotOwner::Offhand | SlotOwner::Crafting => true,
+        SlotOwner::Held | SlotOwner::CraftResult => false,
+    }
+}
+
+/// Moves up to `count` of `from` onto `into`, if it's empty or holds the
+/// same item.
+fn move_onto(into: &mut InventorySlot, from: &mut InventorySlot, count: u32, items: &ItemRegistry) {
+    if from.item_type == ItemType::NONE
+        || (into.item_type != ItemType::NONE && into.item_type != from.item_type)
+    {
+        return;
+    }
+    let room = items.max_stack(from.item_type).saturating_sub(into.count);
+    let moved = count.min(from.count).min(room);
+    if moved == 0 {
+        return;
+    }
+    if into.count == 0 {
+        into.damage = from.damage;
+    }
+    into.item_type = from.item_type;
+    into.count += moved;
+    from.count -= moved;
+    if from.count == 0 {
+        from.item_type = ItemType::NONE;
+    }
+}
+
+/// Shift-click: sends a stack between the open block and the inventory, or
+/// on the inventory screen, between the hotbar and storage. Stacks in the
+/// crafting grid go back into the inventory.
+fn quick_move(
+    slot: ContainerSlot,
+    inventory: &mut Inventory,
+    block_entity: Option<&mut BlockEntity>,
+    items: &ItemRegistry,
+    smelting: &SmeltingRegistry,
+) {
+    let Inventory {
+        slots,
+        offhand,
+        crafting,
+        ..
+    } = inventory;
+    match (slot.owner, block_entity) {
+        (SlotOwner::Crafting, _) => {
+            if let Some(from) = crafting.get_mut(slot.index) {
+                transfer_stack(from, slots, items);
+            }
+        }
+        (SlotOwner::Container, Some(block_entity)) => {
+            if let Some(from) = block_entity.slots_mut().get_mut(slot.index) {
+                transfer_stack(from, slots, items);
+            }
+        }
+        (SlotOwner::Player | SlotOwner::Offhand, Some(block_entity)) => {
+            let from = match slot.owner {
+                SlotOwner::Offhand => offhand,
+                _ => match slots.get_mut(slot.index) {
+                    Some(from) => from,
+                    None => return,
+                },
+            };
+            let accepting = block_entity.slots_accepting(from.item_type, smelting);
+            transfer_stack(from, &mut block_entity.slots_mut()[accepting], items);
+        }
+        (SlotOwner::Player, None) => {
+            let (hotbar, storage) = slots.split_at_mut(HOTBAR_SLOTS);
+            if let Some(from) = hotbar.get_mut(slot.index) {
+                transfer_stack(from, storage, items);
+            } else if let Some(from) = storage.get_mut(slot.index - HOTBAR_SLOTS) {
+                transfer_stack(from, hotbar, items);
+            }
+        }
+        (SlotOwner::Offhand, None) => transfer_stack(offhand, slots, items),
+        _ => {}
+    }
+}
+
+#[derive(SystemParam)]
+pub struct SlotClickParams<'w, 's> {
+    pub commands: Commands<'w, 's>,
+    pub mouse: Res<'w, ButtonInput<MouseButton>>,
+    pub key: Res<'w, ButtonInput<KeyCode>>,
+    pub slot_query:
+        Query<'w, 's, (&'static RelativeCursorPosition, &'static ContainerSlot), With<Button>>,
+    pub window_query: Query<'w, 's, &'static RelativeCursorPosition, With<ContainerWindow>>,
+    pub open_container: Res<'w, OpenContainer>,
+    pub drag: ResMut<'w, SlotDrag>,
+    pub voxel_world: Res<'w, VoxelWorld>,
+    pub chunk_query: Query<'w, 's, &'static mut Chunk>,
+    pub inventory_query: Query<'w, 's, &'static mut Inventory, With<Player>>,
+    pub eye_query: Query<'w, 's, &'static GlobalTransform, With<CameraController>>,
+    pub items: Res<'w, ItemRegistry>,
+    pub smelting: Res<'w, SmeltingRegistry>,
+    pub recipes: Res<'w, RecipeRegistry>,
+    pub block_assets: Res<'w, BlockAssets>,
+}
+
+/// Moves items between the menu's slots and the stack held at the cursor.
+/// Left-click picks up a whole stack, or puts the held one down, swapping
+/// if the slot holds something else; right-click picks up half, or puts
+/// one down. Dragging a held stack across slots spreads it evenly over them
+/// with the left button, or one each with the right. Shift-click sends a
+/// stack straight across, and clicking outside the window throws the held
+/// stack, or one of it. Clicking the crafting result takes one craft's
+/// worth onto the held stack; shift-clicking it crafts as many as fit in
+/// the inventory.
+pub fn handle_slot_clicks(mut params: SlotClickParams) {
+    let drag = &mut params.drag;
+    if !params.open_container.is_open() {
+        drag.button = None;
+        drag.slots.clear();
+        return;
+    }
+
+    let hovered = params
+        .slot_query
+        .iter()
+        .find(|(cursor, _)| cursor.cursor_over())
+        .map(|(_, slot)| *slot);
+    let pressed = [MouseButton::Left, MouseButton::Right]
+        .into_iter()
+        .find(|button| params.mouse.just_pressed(*button));
+    let released = drag
+        .button
+        .is_some_and(|button| params.mouse.just_released(button));
+    let entered = drag.button.is_some() && hovered.is_some_and(|slot| !drag.slots.contains(&slot));
+    if pressed.is_none() && !released && !entered {
+        return;
+    }
+
+    let Ok(mut inventory) = params.inventory_query.single_mut() else {
+        return;
+    };
+    let layout = params.open_container.layout;
+    let mut container = params
+        .open_container
+        .pos
+        .filter(|_| matches!(layout, Some(MenuLayout::Container(_))))
+        .and_then(|pos| chunk_for(&params.voxel_world, &mut params.chunk_query, pos));
+    let mut block_entity = container
+        .as_mut()
+        .and_then(|(chunk, local)| chunk.block_entities.get_mut(&*local));
+    let items = &params.items;
+    let smelting = &params.smelting;
+    let recipes = &params.recipes;
+    let grid_size = layout.map_or(0, MenuLayout::grid_size);
+    let mut held = std::mem::replace(&mut inventory.held, EMPTY_SLOT);
+
+    if let Some(button) = pressed {
+        let shift =
+            params.key.pressed(KeyCode::ShiftLeft) || params.key.pressed(KeyCode::ShiftRight);
+        match hovered {
+            Some(slot) if slot.owner == SlotOwner::CraftResult && shift => {
+                while let Some(mut result) = crafting_result(&inventory, grid_size, recipes) {
+                    let mut slots = inventory.slots.clone();
+                    transfer_stack(&mut result, &mut slots, items);
+                    if result.count > 0 {
+                        break;
+                    }
+                    inventory.slots = slots;
+                    use_up_grid(&mut inventory);
+                }
+            }
+            Some(slot) if slot.owner == SlotOwner::CraftResult => {
+                if let Some(mut result) = crafting_result(&inventory, grid_size, recipes) {
+                    let room = items.max_stack(result.item_type).saturating_sub(held.count);
+                    let fits = held.item_type == ItemType::NONE
+                        || (held.item_type == result.item_type && result.count <= room);
+                    if fits {
+                        let count = result.count;
+                        move_onto(&mut held, &mut result, count, items);
+                        use_up_grid(&mut inventory);
+                    }
+                }
+            }
+            Some(slot) if shift && button == MouseButton::Left => {
+                quick_move(slot, &mut inventory, block_entity, items, smelting);
+            }
+            Some(slot) if held.item_type == ItemType::NONE => {
+                if let Some(from) = slot_mut(slot, &mut inventory, block_entity) {
+                    let count = match button {
+                        MouseButton::Left => from.count,
+                        _ => from.count.div_ceil(2),
+                    };
+                    move_onto(&mut held, from, count, items);
+                }
+            }
+            Some(slot) => {
+                drag.button = Some(button);
+                drag.slots = vec![slot];
+            }
+            None if !params
+                .window_query
+                .iter()
+                .any(RelativeCursorPosition::cursor_over) =>
+            {
+                if let Ok(eye) = params.eye_query.single() {
+                    let count = match button {
+                        MouseButton::Left => held.count,
+                        _ => 1,
+                    };
+                    throw_items(
+                        &mut params.commands,
+                        &params.block_assets,
+                        eye,
+                        &mut held,
+                        count,
+                    );
+                }
+            }
+            None => {}
+        }
+    } else if released {
+        let button = drag.button.take().unwrap_or(MouseButton::Left);
+        let slots = std::mem::take(&mut drag.slots);
+        if let [slot] = slots[..] {
+            let accepted = accepts(slot, held.item_type, block_entity.as_deref(), smelting);
+            if let Some(into) = slot_mut(slot, &mut inventory, block_entity) {
+                let count = match button {
+                    MouseButton::Left => held.count,
+                    _ => 1,
+                };
+                if !accepted {
+                    // Output slots can only be taken from
+                    move_onto(&mut held, into, into.count, items);
+                } else if into.item_type == ItemType::NONE || into.item_type == held.item_type {
+                    move_onto(into, &mut held, count, items);
+                } else {
+                    std::mem::swap(into, &mut held);
+                }
+            }
+        } else {
+            let each = match button {
+                MouseButton::Left => held.count / slots.len().max(1) as u32,
+                _ => 1,
+            };
+            for slot in slots {
+                if let Some(into) = slot_mut(slot, &mut inventory, block_entity.as_deref_mut()) {
+                    move_onto(into, &mut held, each, items);
+                }
+            }
+        }
+    } else if let Some(slot) = hovered
+        && drag.slots.len() < held.count as usize
+        && accepts(slot, held.item_type, block_entity.as_deref(), smelting)
+        && slot_mut(slot, &mut inventory, block_entity).is_some_and(|into| {
+            into.item_type == ItemType::NONE
+                || (into.item_type == held.item_type
+                    && into.count < items.max_stack(held.item_type))
+        })
+    {
+        drag.slots.push(slot);
+    }
+
+    inventory.held = held;
+}
+
+/// The menu's title, panels, progress bars, label and held stack, which
+/// share `Text` and `Node` with the slots and each other.
+type LayoutParams<'w, 's> = ParamSet<
+    'w,
+    's,
+    (
+        Query<'static, 'static, &'static mut Text, With<ContainerTitle>>,
+        Query<'static, 'static, (&'static ContainerPanel, &'static mut Node)>,
+        Query<'static, 'static, (&'static FurnaceBar, &'static mut Node)>,
+        Query<'static, 'static, &'static mut Node, With<InventoryLabel>>,
+        Query<'static, 'static, &'static mut Node, With<HeldStack>>,
+    ),
+>;
+
+fn set_display(node: &mut Node, shown: bool) {
+    let wanted = if shown { Display::Flex } else { Display::None };
+    if node.display != wanted {
+        node.display = wanted;
+    }
+}
+
+/// Shows the menu while it's open, laid out for the container's kind or as
+/// the inventory screen, and closes it if the container's block went away.
+#[allow(clippy::too_many_arguments)]
+pub fn update_container_ui(
+    mut open_container: ResMut<OpenContainer>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    voxel_world: Res<VoxelWorld>,
+    chunk_query: Query<&Chunk>,
+    inventory_query: Query<&Inventory, With<Player>>,
+    icon_assets: Res<InventoryIconAssets>,
+    images: Res<Assets<Image>>,
+    registry: Res<BlockRegistry>,
+    smelting: Res<SmeltingRegistry>,
+    recipes: Res<RecipeRegistry>,
+    mut menu_query: Query<&mut Visibility, With<ContainerMenu>>,
+    mut layout: LayoutParams,
+    mut icon_query: Query<(&ContainerSlot, &mut ImageNode)>,
+    mut text_query: Query<(&ContainerSlot, &mut Text), Without<ContainerTitle>>,
+) {
+    let block = open_container.pos.and_then(|pos| {
+        let chunk_pos = VoxelWorld::world_to_chunk_pos(pos.as_vec3());
+        let chunk = voxel_world
+            .chunks
+            .get(&chunk_pos)
+            .and_then(|entity| chunk_query.get(*entity).ok())?;
+        Some((chunk, VoxelWorld::voxel_to_local_pos(pos)))
+    });
+    let block_entity = block.and_then(|(chunk, local)| chunk.block_entities.get(&local).cloned());
+    let still_there = match open_container.layout {
+        Some(MenuLayout::Container(_)) => block_entity.is_some(),
+        Some(MenuLayout::CraftingTable) => block.is_some_and(|(chunk, local)| {
+            registry.get(chunk.get_voxel(local)).use_behavior == Some(UseBehavior::Crafting)
+        }),
+        _ => true,
+    };
+    if !still_there {
+        open_container.close();
+    }
+
+    if let Ok(mut visibility) = menu_query.single_mut() {
+        let wanted = if open_container.is_open() {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+    }
+    let (Some(menu_layout), Ok(inventory)) = (open_container.layout, inventory_query.single())
+    else {
+        return;
+    };
+
+    if let Ok(mut title) = layout.p0().single_mut() {
+        let wanted = match menu_layout {
+            MenuLayout::Inventory => "Inventory",
+            MenuLayout::CraftingTable => "Crafting",
+            MenuLayout::Container(BlockEntityKind::Chest) => "Chest",
+            MenuLayout::Container(BlockEntityKind::Furnace) => "Furnace",
+        };
+        if title.0 != wanted {
+            title.0 = wanted.to_string();
+        }
+    }
+    for (panel, mut node) in layout.p1().iter_mut() {
+        set_display(&mut node, panel.0 == menu_layout);
+    }
+    if let Ok(mut node) = layout.p3().single_mut() {
+        set_display(&mut node, menu_layout != MenuLayout::Inventory);
+    }
+    let cursor = window_query.single().ok().and_then(Window::cursor_position);
+    if let Ok(mut node) = layout.p4().single_mut()
+        && let Some(cursor) = cursor
+    {
+        node.left = Val::Px(cursor.x - 20.0);
+        node.top = Val::Px(cursor.y - 20.0);
+    }
+    if let Some(BlockEntity::Furnace(furnace)) = &block_entity {
+        for (bar, mut node) in layout.p2().iter_mut() {
+            let fraction = match bar {
+                FurnaceBar::Burn => furnace.burn_fraction(),
+                FurnaceBar::Cook => furnace.cook_fraction(&smelting),
+            };
+            node.width = Val::Percent(fraction * 100.0);
+        }
+    }
+
+    let contents = block_entity.as_ref().map_or(&[][..], BlockEntity::slots);
+    let result = crafting_result(inventory, menu_layout.grid_size(), &recipes);
+
+    let slot_at = |slot: &ContainerSlot| match slot.owner {
+        SlotOwner::Container => contents.get(slot.index).copied(),
+        SlotOwner::Player => inventory.slots.get(slot.index).copied(),
+        SlotOwner::Offhand => Some(inventory.offhand),
+        SlotOwner::Held => Some(inventory.held),
+        SlotOwner::Crafting => inventory.crafting.get(slot.index).copied(),
+        SlotOwner::CraftResult => result,
+    };
+    for (slot, mut text) in text_query.iter_mut() {
+        let count = slot_at(slot).map_or(0, |slot| slot.count);
+        text.0 = if count > 0 {
+            count.to_string()
+        } else {
+            String::new()
+        };
+    }
+    for (slot, mut image) in icon_query.iter_mut() {
+        let item_type = slot_at(slot).map_or(ItemType::NONE, |slot| slot.item_type);
+        set_item_icon(&mut image, item_type, &icon_assets, &images);
+    }
+}
+
+fn set_item_icon(
+    image: &mut ImageNode,
+    item_type: ItemType,
+    icon_assets: &InventoryIconAssets,
+    images: &Assets<Image>,
+) {
+    image.image = icon_assets
+        .icons
+        .get(item_type.0 as usize)
+        .cloned()
+        .flatten()
+        .unwrap_or(TRANSPARENT_IMAGE_HANDLE);
+    // Animated textures are vertical strips, so only show the first frame
+    image.rect = images.get(&image.image).and_then(|icon| {
+        let size = icon.size().as_vec2();
+        (size.y > size.x).then(|| Rect::new(0.0, 0.0, size.x, size.x))
+    });
+}
+
+type RecipeBookQuery<'w, 's> =
+    Query<'w, 's, &'static mut Node, (With<RecipeBook>, Without<RecipeEntry>)>;
+
+/// Shows the recipe book beside the open crafting grid, listing the
+/// recipes that fit it, greyed out where the inventory lacks ingredients.
+#[allow(clippy::too_many_arguments)]
+pub fn update_recipe_book(
+    open_container: Res<OpenContainer>,
+    inventory_query: Query<&Inventory, With<Player>>,
+    recipes: Res<RecipeRegistry>,
+    icon_assets: Res<InventoryIconAssets>,
+    images: Res<Assets<Image>>,
+    mut book_query: RecipeBookQuery,
+    mut entry_query: Query<(&RecipeEntry, &mut Node, &mut BackgroundColor)>,
+    mut icon_query: Query<(&RecipeIcon, &mut ImageNode), Without<ContainerSlot>>,
+) {
+    let grid_size = open_container.layout.map_or(0, MenuLayout::grid_size);
+    let shown = open_container.recipe_book && grid_size > 0;
+    if let Ok(mut node) = book_query.single_mut() {
+        set_display(&mut node, shown);
+    }
+    let Ok(inventory) = inventory_query.single() else {
+        return;
+    };
+    if !shown {
+        return;
+    }
+
+    for (entry, mut node, mut background) in entry_query.iter_mut() {
+        let Some(recipe) = recipes.get(entry.0) else {
+            continue;
+        };
+        set_display(&mut node, recipe.fits(grid_size));
+        let wanted = if can_make(inventory, recipe) {
+            Color::srgba(0.6, 0.6, 0.6, 1.0)
+        } else {
+            Color::srgba(0.6, 0.2, 0.2, 1.0)
+        };
+        if background.0 != wanted {
+            background.0 = wanted;
+        }
+    }
+    for (icon, mut image) in icon_query.iter_mut() {
+        let item_type = recipes
+            .get(icon.0)
+            .map_or(ItemType::NONE, |recipe| recipe.result);
+        set_item_icon(&mut image, item_type, &icon_assets, &images);
+    }
+}
+
+type RecipeButtonQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Interaction,
+        Option<&'static RecipeEntry>,
+        Has<RecipeBookButton>,
+    ),
+    (Changed<Interaction>, With<Button>),
+>;
+
+/// Opens and closes the recipe book, and lays a recipe clicked in it out in
+/// the crafting grid.
+pub fn handle_recipe_book(
+    interaction_query: RecipeButtonQuery,
+    mut open_container: ResMut<OpenContainer>,
+    mut inventory_query: Query<&mut Inventory, With<Player>>,
+    recipes: Res<RecipeRegistry>,
+    items: Res<ItemRegistry>,
+) {
+    let grid_size = open_container.layout.map_or(0, MenuLayout::grid_size);
+    for (interaction, entry, is_book_button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if is_book_button {
+            open_container.recipe_book = !open_container.recipe_book;
+        }
+        if let Some(recipe) = entry.and_then(|entry| recipes.get(entry.0))
+            && recipe.fits(grid_size)
+            && let Ok(mut inventory) = inventory_query.single_mut()
+        {
+            fill_grid(&mut inventory, recipe, grid_size, &items);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+
+use crate::main_menu::WorldSettings;
+use crate::player::components::{CharacterController, Dead, Health, Hunger, Player};
+use crate::player::systems::respawn_position;
+
+#[derive(Component)]
+pub struct DeathScreen;
+
+/// Says what killed the player.
+#[derive(Component)]
+pub struct DeathMessageText;
+
+#[derive(Component)]
+pub struct RespawnButton;
+
+pub fn setup_death_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Vw(100.0),
+                height: Val::Vh(100.0),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(20.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.5, 0.0, 0.0, 0.5)),
+            DeathScreen,
+            crate::world::components::InGameEntity,
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("YOU DIED!"),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                DeathMessageText,
+            ));
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(200.0),
+                        height: Val::Px(50.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                    BorderColor::all(Color::WHITE),
+                    RespawnButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("RESPAWN"),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+        });
+}
+
+/// Shows the death screen while the player is dead, with what killed them.
+pub fn update_death_screen(
+    player_query: Query<(&Health, Has<Dead>), With<Player>>,
+    mut screen_query: Query<&mut Visibility, With<DeathScreen>>,
+    mut text_query: Query<&mut Text, With<DeathMessageText>>,
+) {
+    let Ok((health, dead)) = player_query.single() else {
+        return;
+    };
+    let Ok(mut visibility) = screen_query.single_mut() else {
+        return;
+    };
+    let target = if dead {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    if *visibility == target {
+        return;
+    }
+    *visibility = target;
+
+    if let Ok(mut text) = text_query.single_mut() {
+        text.0 = health
+            .last_damage
+            .map_or("You died", |cause| cause.death_message())
+            .to_string();
+    }
+}
+
+type RespawnQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Transform,
+        &'static mut Velocity,
+        &'static mut Health,
+        &'static mut Hunger,
+        &'static mut CharacterController,
+    ),
+    (With<Player>, With<Dead>),
+>;
+
+/// Brings the player back at their respawn point with full health and
+/// hunger.
+pub fn handle_respawn_button(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>, With<RespawnButton>),
+    >,
+    mut player_query: RespawnQuery,
+    world_settings: Res<WorldSettings>,
+) {
+    for (interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                let Ok((
+                    entity,
+                    mut transform,
+                    mut velocity,
+                    mut health,
+                    mut hunger,
+                    mut controller,
+                )) = player_query.single_mut()
+                else {
+                    continue;
+                };
+                commands.entity(entity).remove::<Dead>();
+                *health = Health::default();
+                *hunger = Hunger::default();
+                transform.translation = respawn_position(&world_settings);
+                velocity.linvel = Vec3::ZERO;
+                controller.is_grounded = false;
+                controller.fall_start_y = transform.translation.y;
+            }
+            Interaction::Hovered => {
+                *color = BackgroundColor(Color::srgb(0.4, 0.4, 0.4));
+            }
+            Interaction::None => {
+                *color = BackgroundColor(Color::srgb(0.2, 0.2, 0.2));
+            }
+        }
+    }
+}
@@ -1,13 +1,17 @@
+use crate::main_menu::WorldSettings;
 use crate::mob::components::Mob;
 use crate::player::components::{Health, Hunger, Inventory, InventorySlotIcon};
 use crate::player::settings_menu::{
     FootstepVolumeDecreaseButton, FootstepVolumeIncreaseButton, FootstepVolumeText,
     FovDecreaseButton, FovIncreaseButton, FovText, MasterVolumeDecreaseButton,
-    MasterVolumeIncreaseButton, MasterVolumeText, QuitToMenuButton, RenderDistanceDecreaseButton,
-    RenderDistanceIncreaseButton, RenderDistanceText, ResumeButton, SettingsMenu,
+    MasterVolumeIncreaseButton, MasterVolumeText, QuitToMenuButton, ReloadResourcePacksButton,
+    RenderDistanceDecreaseButton, RenderDistanceIncreaseButton, RenderDistanceText,
+    ResourcePackList, ResumeButton, SettingsMenu,
 };
-use crate::world::components::{ItemType, SunLight};
-use bevy::image::{ImageLoaderSettings, ImageSampler, TRANSPARENT_IMAGE_HANDLE};
+use crate::resource_pack::{ResourcePacks, ResourcePacksReloaded};
+use crate::world::components::SunLight;
+use crate::world::items::ItemRegistry;
+use bevy::image::TRANSPARENT_IMAGE_HANDLE;
 use bevy::input::keyboard::KeyboardInput;
 use bevy::prelude::*;
 
@@ -21,6 +25,11 @@ pub struct InventorySlotUi(pub usize);
 #[derive(Component)]
 pub struct InventorySlotText(pub usize);
 
+/// Bar along the bottom of a hotbar slot showing a worn tool's remaining
+/// uses.
+#[derive(Component)]
+pub struct InventorySlotDurability(pub usize);
+
 #[derive(Component)]
 pub struct Crosshair;
 
@@ -54,16 +63,39 @@ pub struct KillEvent {
     pub selector: String,
 }
 
+#[derive(Component)]
+pub struct SelectedItemText;
+
 #[derive(Resource)]
 pub struct InventoryIconAssets {
-    pub grass: Handle<Image>,
-    pub dirt: Handle<Image>,
-    pub stone: Handle<Image>,
-    pub coal_ore: Handle<Image>,
-    pub iron_ore: Handle<Image>,
-    pub gold_ore: Handle<Image>,
-    pub diamond_ore: Handle<Image>,
-    pub wheat: Handle<Image>,
+    /// Icon per item id; empty slots and unknown ids have none.
+    pub icons: Vec<Option<Handle<Image>>>,
+}
+
+fn build_icon_assets(
+    asset_server: &AssetServer,
+    packs: &ResourcePacks,
+    items: &ItemRegistry,
+) -> InventoryIconAssets {
+    let mut icons = vec![None; items.id_count()];
+    for (item, def) in items.iter() {
+        icons[item.0 as usize] = Some(packs.load_image(asset_server, &def.icon));
+    }
+    InventoryIconAssets { icons }
+}
+
+pub fn reload_icon_assets(
+    mut commands: Commands,
+    mut reloaded: MessageReader<ResourcePacksReloaded>,
+    asset_server: Res<AssetServer>,
+    packs: Res<ResourcePacks>,
+    items: Res<ItemRegistry>,
+) {
+    if reloaded.is_empty() {
+        return;
+    }
+    reloaded.clear();
+    commands.insert_resource(build_icon_assets(&asset_server, &packs, &items));
 }
 
 #[derive(Component)]
@@ -73,65 +105,13 @@ pub struct HotbarRoot;
 #[allow(dead_code)]
 pub struct InventoryRoot;
 
-pub fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let grass_icon = asset_server.load_with_settings(
-        "textures/block/grass_block_top.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let dirt_icon = asset_server.load_with_settings(
-        "textures/block/dirt.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let stone_icon = asset_server.load_with_settings(
-        "textures/block/stone.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let coal_ore_icon = asset_server.load_with_settings(
-        "textures/block/coal_ore.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let iron_ore_icon = asset_server.load_with_settings(
-        "textures/block/iron_ore.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let gold_ore_icon = asset_server.load_with_settings(
-        "textures/block/gold_ore.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let diamond_ore_icon = asset_server.load_with_settings(
-        "textures/block/diamond_ore.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let wheat_icon = asset_server.load_with_settings(
-        "textures/item/wheat.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    commands.insert_resource(InventoryIconAssets {
-        grass: grass_icon,
-        dirt: dirt_icon,
-        stone: stone_icon,
-        coal_ore: coal_ore_icon,
-        iron_ore: iron_ore_icon,
-        gold_ore: gold_ore_icon,
-        diamond_ore: diamond_ore_icon,
-        wheat: wheat_icon,
-    });
+pub fn setup_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    packs: Res<ResourcePacks>,
+    items: Res<ItemRegistry>,
+) {
+    commands.insert_resource(build_icon_assets(&asset_server, &packs, &items));
 
     // Crosshair
     commands
@@ -284,6 +264,30 @@ pub fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
             ));
         });
 
+    // Name of the held item, just above the hotbar
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(84.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            crate::world::components::InGameEntity,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(""),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                SelectedItemText,
+            ));
+        });
+
     // Inventory Bar Container
     commands
         .spawn((
@@ -337,6 +341,18 @@ pub fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                             TextColor(Color::WHITE),
                             InventorySlotText(i),
                         ));
+                        parent.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: Val::Px(3.0),
+                                bottom: Val::Px(3.0),
+                                height: Val::Px(3.0),
+                                display: Display::None,
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.0, 1.0, 0.0)),
+                            InventorySlotDurability(i),
+                        ));
                     });
             }
         });
@@ -362,7 +378,7 @@ pub fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 .spawn((
                     Node {
                         width: Val::Px(400.0),
-                        height: Val::Px(600.0),
+                        height: Val::Auto,
                         flex_direction: FlexDirection::Column,
                         align_items: AlignItems::Center,
                         padding: UiRect::all(Val::Px(20.0)),
@@ -725,6 +741,62 @@ pub fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                                 });
                         });
 
+                    // Resource Packs Section
+                    parent
+                        .spawn(Node {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            row_gap: Val::Px(10.0),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Text::new("Resource Packs"),
+                                TextFont {
+                                    font_size: 20.0,
+                                    ..default()
+                                },
+                                TextColor(Color::WHITE),
+                            ));
+
+                            // Filled in by update_resource_pack_list
+                            parent.spawn((
+                                Node {
+                                    flex_direction: FlexDirection::Column,
+                                    align_items: AlignItems::Center,
+                                    row_gap: Val::Px(6.0),
+                                    ..default()
+                                },
+                                ResourcePackList,
+                            ));
+
+                            parent
+                                .spawn((
+                                    Button,
+                                    Node {
+                                        width: Val::Px(160.0),
+                                        height: Val::Px(32.0),
+                                        border: UiRect::all(Val::Px(2.0)),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                                    BorderColor::all(Color::WHITE),
+                                    ReloadResourcePacksButton,
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        Text::new("Reload Packs"),
+                                        TextFont {
+                                            font_size: 16.0,
+                                            ..default()
+                                        },
+                                        TextColor(Color::WHITE),
+                                    ));
+                                });
+                        });
+
                     parent
                         .spawn((
                             Button,
@@ -820,6 +892,7 @@ pub fn handle_command_input(
         ),
     >,
     mut kill_events: MessageWriter<KillEvent>,
+    mut world_settings: ResMut<WorldSettings>,
 ) {
     if let Ok(visibility) = visibility_params.p0().single()
         && *visibility != Visibility::Hidden
@@ -855,6 +928,7 @@ pub fn handle_command_input(
             &mut player_query,
             &mob_query,
             &mut kill_events,
+            &mut world_settings,
         );
         if !response.is_empty() {
             let sys_msg = format!("[System] {}", response);
@@ -965,6 +1039,7 @@ fn execute_command(
         ),
     >,
     kill_events: &mut MessageWriter<KillEvent>,
+    world_settings: &mut WorldSettings,
 ) -> String {
     let input = buffer.trim();
     if input.is_empty() {
@@ -994,7 +1069,14 @@ fn execute_command(
                 .arg(Arg::new("arg3").required(false))
                 .arg(Arg::new("arg4").required(false)),
         )
-        .subcommand(Command::new("kill").arg(Arg::new("target").required(true)));
+        .subcommand(Command::new("kill").arg(Arg::new("target").required(true)))
+        .subcommand(
+            Command::new("gamerule")
+                .arg(Arg::new("rule").required(true))
+                .arg(Arg::new("value").required(false)),
+        )
+        .subcommand(Command::new("spawnpoint"))
+        .subcommand(Command::new("setworldspawn"));
 
     let matches = match app.try_get_matches_from(cmd_text.split_whitespace()) {
         Ok(m) => m,
@@ -1167,16 +1249,57 @@ fn execute_command(
             }
             format!("Invalid target for kill: {}", target)
         }
+        Some(("gamerule", sub_m)) => {
+            let rule = sub_m.get_one::<String>("rule").unwrap();
+            if rule != "keepInventory" {
+                return format!("Unknown game rule: {}", rule);
+            }
+            match sub_m
+                .get_one::<String>("value")
+                .map(|value| value.parse::<bool>())
+            {
+                None => format!("keepInventory = {}", world_settings.keep_inventory),
+                Some(Ok(value)) => {
+                    world_settings.keep_inventory = value;
+                    format!("Set keepInventory to {}", value)
+                }
+                Some(Err(_)) => "Usage: /gamerule keepInventory [true|false]".to_string(),
+            }
+        }
+        Some(("spawnpoint", _)) => {
+            let Ok(player_transform) = player_query.single() else {
+                return "Player not found".to_string();
+            };
+            let pos = player_transform.translation;
+            world_settings.respawn_point = Some(pos);
+            format!("Set respawn point to {:?}", pos)
+        }
+        Some(("setworldspawn", _)) => {
+            let Ok(player_transform) = player_query.single() else {
+                return "Player not found".to_string();
+            };
+            let pos = player_transform.translation;
+            world_settings.spawn_point = Some(pos);
+            format!("Set world spawn point to {:?}", pos)
+        }
         _ => "Unknown command".to_string(),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_inventory_ui(
     inventory_query: Query<&Inventory>,
     icon_assets: Res<InventoryIconAssets>,
+    items: Res<ItemRegistry>,
+    images: Res<Assets<Image>>,
     mut slot_query: Query<(&InventorySlotUi, &mut BackgroundColor, &mut BorderColor)>,
     mut text_query: Query<(&InventorySlotText, &mut Text)>,
+    mut selected_text_query: Query<&mut Text, (With<SelectedItemText>, Without<InventorySlotText>)>,
     mut icon_query: Query<(&InventorySlotIcon, &mut ImageNode)>,
+    mut durability_query: Query<
+        (&InventorySlotDurability, &mut Node, &mut BackgroundColor),
+        Without<InventorySlotUi>,
+    >,
 ) {
     if let Ok(inventory) = inventory_query.single() {
         for (slot_ui, mut background, mut border) in slot_query.iter_mut() {
@@ -1200,17 +1323,46 @@ pub fn update_inventory_ui(
 
         for (slot_icon, mut image) in icon_query.iter_mut() {
             let slot = &inventory.slots[slot_icon.0];
-            image.image = match slot.item_type {
-                ItemType::GrassBlock => icon_assets.grass.clone(),
-                ItemType::Dirt => icon_assets.dirt.clone(),
-                ItemType::Stone => icon_assets.stone.clone(),
-                ItemType::CoalOre => icon_assets.coal_ore.clone(),
-                ItemType::IronOre => icon_assets.iron_ore.clone(),
-                ItemType::GoldOre => icon_assets.gold_ore.clone(),
-                ItemType::DiamondOre => icon_assets.diamond_ore.clone(),
-                ItemType::Wheat => icon_assets.wheat.clone(),
-                ItemType::None => TRANSPARENT_IMAGE_HANDLE,
+            image.image = icon_assets
+                .icons
+                .get(slot.item_type.0 as usize)
+                .cloned()
+                .flatten()
+                .unwrap_or(TRANSPARENT_IMAGE_HANDLE);
+            // Animated textures are vertical strips, so only show the first frame
+            image.rect = images.get(&image.image).and_then(|icon| {
+                let size = icon.size().as_vec2();
+                (size.y > size.x).then(|| Rect::new(0.0, 0.0, size.x, size.x))
+            });
+        }
+
+        for (slot_bar, mut node, mut color) in durability_query.iter_mut() {
+            let slot = &inventory.slots[slot_bar.0];
+            let left = items
+                .durability(slot.item_type)
+                .filter(|_| slot.damage > 0)
+                .map(|durability| 1.0 - slot.damage as f32 / durability as f32);
+            node.display = if left.is_some() {
+                Display::Flex
+            } else {
+                Display::None
             };
+            if let Some(left) = left {
+                // 30px is the slot's inner width less the bar's margins
+                node.width = Val::Px(30.0 * left);
+                color.0 = Color::hsl(120.0 * left, 1.0, 0.5);
+            }
+        }
+
+        if let Ok(mut text) = selected_text_query.single_mut() {
+            let selected = inventory.slots[inventory.selected_slot].item_type;
+            let name = items
+                .get(selected)
+                .map(|def| def.display_name.as_str())
+                .unwrap_or("");
+            if text.0 != name {
+                text.0 = name.to_string();
+            }
         }
     }
 }
@@ -1,11 +1,15 @@
 use bevy::prelude::*;
 
 pub mod components;
+pub mod container_ui;
+pub mod death_screen;
 pub mod inventory_ui;
 pub mod resources;
 pub mod settings_menu;
 pub mod systems;
 
+use container_ui::*;
+use death_screen::*;
 use inventory_ui::*;
 use resources::*;
 use settings_menu::*;
@@ -17,11 +21,17 @@ impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Settings>()
             .init_resource::<SoundAssets>()
+            .init_resource::<RightClickUsed>()
             .add_message::<KillEvent>()
             .add_systems(
 
                 OnEnter(crate::main_menu::AppState::InGame),
-                (setup_ui, load_sound_assets),
+                (
+                    setup_ui,
+                    setup_container_ui,
+                    setup_death_screen,
+                    load_sound_assets,
+                ),
             )
             .init_resource::<CommandState>()
             .add_systems(
@@ -30,11 +40,22 @@ impl Plugin for PlayerPlugin {
             )
             .add_systems(
                 Update,
-                player_look.run_if(in_state(crate::main_menu::AppState::InGame)),
+                player_look
+                    .run_if(in_state(crate::main_menu::AppState::InGame))
+                    .run_if(player_alive),
+            )
+            .add_systems(
+                Update,
+                player_interact
+                    .run_if(in_state(crate::main_menu::AppState::InGame))
+                    .run_if(player_alive),
             )
             .add_systems(
                 Update,
-                player_interact.run_if(in_state(crate::main_menu::AppState::InGame)),
+                eat_food
+                    .after(player_interact)
+                    .run_if(in_state(crate::main_menu::AppState::InGame))
+                    .run_if(player_alive),
             )
             .add_systems(
                 Update,
@@ -47,7 +68,7 @@ impl Plugin for PlayerPlugin {
                     update_health_ui,
                     update_hunger_ui,
                     handle_command_input,
-                    pickup_drops,
+                    pickup_drops.run_if(player_alive),
                     toggle_settings_menu,
                     handle_fov_buttons,
                     handle_render_distance_buttons,
@@ -55,15 +76,38 @@ impl Plugin for PlayerPlugin {
                     handle_footstep_volume_buttons,
                     handle_resume_button,
                     handle_quit_button,
+                    update_resource_pack_list,
+                    handle_resource_pack_buttons,
+                    handle_reload_resource_packs_button,
+                    reload_sound_assets,
+                    reload_icon_assets,
                     update_sprint_fov,
                     update_footsteps,
                     despawn_mining_effects,
                 )
                     .run_if(in_state(crate::main_menu::AppState::InGame)),
             )
+            .add_systems(
+                Update,
+                (
+                    toggle_container_menu.after(toggle_settings_menu),
+                    handle_slot_clicks.after(toggle_container_menu),
+                    update_container_ui,
+                    handle_recipe_book.after(toggle_container_menu),
+                    update_recipe_book,
+                )
+                    .run_if(in_state(crate::main_menu::AppState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (update_death_screen, handle_respawn_button)
+                    .run_if(in_state(crate::main_menu::AppState::InGame)),
+            )
             .add_systems(
                 FixedUpdate,
-                player_move.run_if(in_state(crate::main_menu::AppState::InGame)),
+                player_move
+                    .run_if(in_state(crate::main_menu::AppState::InGame))
+                    .run_if(player_alive),
             )
             .add_systems(
                 FixedUpdate,
@@ -71,11 +115,15 @@ impl Plugin for PlayerPlugin {
             )
             .add_systems(
                 FixedUpdate,
-                update_hunger.run_if(in_state(crate::main_menu::AppState::InGame)),
+                update_hunger
+                    .run_if(in_state(crate::main_menu::AppState::InGame))
+                    .run_if(player_alive),
             )
             .add_systems(
                 FixedUpdate,
-                handle_player_death.run_if(in_state(crate::main_menu::AppState::InGame)),
+                handle_player_death
+                    .run_if(in_state(crate::main_menu::AppState::InGame))
+                    .run_if(player_alive),
             );
     }
 }
@@ -1,48 +1,125 @@
 use bevy::audio::AudioSource;
 use bevy::prelude::*;
 
+use crate::mob::components::MobType;
+use crate::resource_pack::{ResourcePacks, ResourcePacksReloaded};
+use crate::world::blocks::BlockRegistry;
+use crate::world::components::VoxelType;
+
+#[derive(Default)]
+pub struct BlockSounds {
+    pub break_sound: Handle<AudioSource>,
+    pub hit: Vec<Handle<AudioSource>>,
+    pub step: Handle<AudioSource>,
+}
+
+/// Set when right click was taken up by using a block, such as opening a
+/// door or flipping a lever, so holding it on doesn't also eat. Cleared once
+/// it's released.
+#[derive(Resource, Default)]
+pub struct RightClickUsed(pub bool);
+
 #[derive(Resource, Default)]
 pub struct SoundAssets {
-    pub break_grass: Handle<AudioSource>,
-    pub break_stone: Handle<AudioSource>,
     pub place_block: Handle<AudioSource>,
     pub pickup_item: Handle<AudioSource>,
-    pub step_grass: Handle<AudioSource>,
-    pub step_stone: Handle<AudioSource>,
-    pub step_dirt: Handle<AudioSource>,
-    pub hit_grass: [Handle<AudioSource>; 4],
-    pub hit_stone: [Handle<AudioSource>; 4],
+    pub fuse: Handle<AudioSource>,
+    pub click: Handle<AudioSource>,
+    pub door_open: Handle<AudioSource>,
+    pub door_close: Handle<AudioSource>,
+    pub chest_open: Handle<AudioSource>,
+    pub chest_close: Handle<AudioSource>,
+    pub tool_break: Handle<AudioSource>,
+    pub cow_hurt: Vec<Handle<AudioSource>>,
+    pub slime_hurt: Vec<Handle<AudioSource>>,
+    pub eat: Vec<Handle<AudioSource>>,
+    pub burp: Handle<AudioSource>,
+    pub explode: Vec<Handle<AudioSource>>,
+    /// One entry per `BlockRegistry::sound_groups` entry, same order.
+    pub block_sounds: Vec<BlockSounds>,
 }
 
-pub fn load_sound_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let mut hit_grass: [Handle<AudioSource>; 4] = [
-        Handle::default(),
-        Handle::default(),
-        Handle::default(),
-        Handle::default(),
-    ];
-    for i in 0..4 {
-        hit_grass[i] = asset_server.load(format!("sounds/step/grass{}.ogg", i + 1));
+impl SoundAssets {
+    pub fn for_block(&self, registry: &BlockRegistry, voxel: VoxelType) -> Option<&BlockSounds> {
+        registry
+            .get(voxel)
+            .sound_group
+            .and_then(|group| self.block_sounds.get(group))
+    }
+
+    pub fn hurt_for(&self, mob_type: MobType) -> &[Handle<AudioSource>] {
+        match mob_type {
+            MobType::Cow => &self.cow_hurt,
+            MobType::Slime => &self.slime_hurt,
+        }
     }
-    let mut hit_stone: [Handle<AudioSource>; 4] = [
-        Handle::default(),
-        Handle::default(),
-        Handle::default(),
-        Handle::default(),
-    ];
-    for i in 0..4 {
-        hit_stone[i] = asset_server.load(format!("sounds/step/stone{}.ogg", i + 1));
+}
+
+pub fn load_sound_assets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    packs: Res<ResourcePacks>,
+    registry: Res<BlockRegistry>,
+) {
+    commands.insert_resource(build_sound_assets(&asset_server, &packs, &registry));
+}
+
+pub fn reload_sound_assets(
+    mut commands: Commands,
+    mut reloaded: MessageReader<ResourcePacksReloaded>,
+    asset_server: Res<AssetServer>,
+    packs: Res<ResourcePacks>,
+    registry: Res<BlockRegistry>,
+) {
+    if reloaded.is_empty() {
+        return;
     }
+    reloaded.clear();
+    commands.insert_resource(build_sound_assets(&asset_server, &packs, &registry));
+}
+
+fn build_sound_assets(
+    asset_server: &AssetServer,
+    packs: &ResourcePacks,
+    registry: &BlockRegistry,
+) -> SoundAssets {
+    let block_sounds = registry
+        .sound_groups
+        .iter()
+        .map(|group| BlockSounds {
+            break_sound: packs.load(asset_server, &group.break_sound),
+            hit: group
+                .hit
+                .iter()
+                .map(|path| packs.load(asset_server, path))
+                .collect(),
+            step: packs.load(asset_server, &group.step),
+        })
+        .collect();
 
-    commands.insert_resource(SoundAssets {
-        break_grass: asset_server.load("sounds/dig/grass1.ogg"),
-        break_stone: asset_server.load("sounds/dig/stone1.ogg"),
-        place_block: asset_server.load("sounds/random/wood_click.ogg"),
-        pickup_item: asset_server.load("sounds/random/pop.ogg"),
-        step_grass: asset_server.load("sounds/block/moss/step1.ogg"),
-        step_stone: asset_server.load("sounds/block/deepslate/step1.ogg"),
-        step_dirt: asset_server.load("sounds/block/rooted_dirt/step1.ogg"),
-        hit_grass,
-        hit_stone,
-    });
+    SoundAssets {
+        place_block: packs.load(asset_server, "sounds/random/wood_click.ogg"),
+        pickup_item: packs.load(asset_server, "sounds/random/pop.ogg"),
+        fuse: packs.load(asset_server, "sounds/random/fuse.ogg"),
+        click: packs.load(asset_server, "sounds/random/click.ogg"),
+        door_open: packs.load(asset_server, "sounds/random/door_open.ogg"),
+        door_close: packs.load(asset_server, "sounds/random/door_close.ogg"),
+        chest_open: packs.load(asset_server, "sounds/random/chestopen.ogg"),
+        chest_close: packs.load(asset_server, "sounds/random/chestclosed.ogg"),
+        tool_break: packs.load(asset_server, "sounds/random/break.ogg"),
+        cow_hurt: (1..=3)
+            .map(|i| packs.load(asset_server, &format!("sounds/mob/cow/hurt{}.ogg", i)))
+            .collect(),
+        slime_hurt: (1..=5)
+            .map(|i| packs.load(asset_server, &format!("sounds/mob/slime/small{}.ogg", i)))
+            .collect(),
+        eat: (1..=3)
+            .map(|i| packs.load(asset_server, &format!("sounds/random/eat{}.ogg", i)))
+            .collect(),
+        burp: packs.load(asset_server, "sounds/random/burp.ogg"),
+        explode: (1..=4)
+            .map(|i| packs.load(asset_server, &format!("sounds/random/explode{}.ogg", i)))
+            .collect(),
+        block_sounds,
+    }
 }
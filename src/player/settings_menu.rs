@@ -1,10 +1,15 @@
 use bevy::prelude::*;
 use bevy::window::CursorOptions;
 
+use crate::player::container_ui::OpenContainer;
+use crate::resource_pack::{ResourcePacks, ResourcePacksReloaded};
+
 #[derive(Resource)]
 pub struct Settings {
     pub fov: f32,
     pub render_distance: i32,
+    /// Chunk columns past `render_distance` and up to this distance are drawn as LOD meshes.
+    pub lod_distance: i32,
     pub master_volume: f32,
     pub footstep_volume: f32,
 }
@@ -14,6 +19,7 @@ impl Default for Settings {
         Self {
             fov: 120.0,
             render_distance: 7,
+            lod_distance: 20,
             master_volume: 0.5,
             footstep_volume: 0.3,
         }
@@ -65,13 +71,29 @@ pub struct FootstepVolumeDecreaseButton;
 #[derive(Component)]
 pub struct FootstepVolumeIncreaseButton;
 
+#[derive(Component)]
+pub struct ResourcePackList;
+
+/// Toggles the named pack on or off.
+#[derive(Component)]
+pub struct ResourcePackButton(pub String);
+
+#[derive(Component)]
+pub struct ReloadResourcePacksButton;
+
 pub fn toggle_settings_menu(
     key: Res<ButtonInput<KeyCode>>,
     mut settings_menu_query: Query<&mut Visibility, With<SettingsMenu>>,
     mut cameras: Query<&mut Projection, With<Camera3d>>,
     settings: Res<Settings>,
     mut window_query: Query<(Entity, &mut Window, &mut CursorOptions)>,
+    open_container: Res<OpenContainer>,
 ) {
+    // Escape closes an open container instead
+    if open_container.is_open() {
+        return;
+    }
+
     if key.just_pressed(KeyCode::Escape)
         && let Ok(mut visibility) = settings_menu_query.single_mut()
     {
@@ -128,7 +150,11 @@ pub fn handle_quit_button(
     mut next_state: ResMut<NextState<crate::main_menu::AppState>>,
     mut window_query: Query<(Entity, &mut Window, &mut CursorOptions)>,
     player_query: Query<
-        (&Transform, &crate::player::components::Inventory),
+        (
+            &Transform,
+            &crate::player::components::Inventory,
+            Has<crate::player::components::Dead>,
+        ),
         With<crate::player::components::Player>,
     >,
     mut world_settings: ResMut<crate::main_menu::WorldSettings>,
@@ -137,8 +163,8 @@ pub fn handle_quit_button(
         match *interaction {
             Interaction::Pressed => {
                 // Manually trigger inventory save before state transition to be safe
-                if let Ok((transform, inventory)) = player_query.single() {
-                    world_settings.player_position = Some(transform.translation);
+                if let Ok((transform, inventory, dead)) = player_query.single() {
+                    world_settings.player_position = (!dead).then_some(transform.translation);
                     world_settings.inventory = Some(inventory.clone());
                 }
 
@@ -349,3 +375,134 @@ pub fn handle_footstep_volume_buttons(
         text.0 = format!("{:.0}%", settings.footstep_volume * 100.0);
     }
 }
+
+/// Rebuilds the pack buttons whenever the available or enabled packs change.
+pub fn update_resource_pack_list(
+    mut commands: Commands,
+    packs: Res<ResourcePacks>,
+    list_query: Query<(Entity, Ref<ResourcePackList>)>,
+) {
+    let Ok((list, marker)) = list_query.single() else {
+        return;
+    };
+    if !packs.is_changed() && !marker.is_added() {
+        return;
+    }
+
+    commands.entity(list).despawn_related::<Children>();
+    commands.entity(list).with_children(|parent| {
+        if packs.available.is_empty() {
+            parent.spawn((
+                Text::new("No packs found"),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.5, 0.5, 0.5)),
+            ));
+            return;
+        }
+
+        for pack in &packs.available {
+            let state = if packs.is_enabled(&pack.name) {
+                "[on]"
+            } else {
+                "[off]"
+            };
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(320.0),
+                        min_height: Val::Px(32.0),
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(4.0)),
+                        border: UiRect::all(Val::Px(1.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+                    BorderColor::all(Color::WHITE),
+                    ResourcePackButton(pack.name.clone()),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(format!("{} {}", state, pack.name)),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                    if !pack.description.is_empty() {
+                        parent.spawn((
+                            Text::new(pack.description.clone()),
+                            TextFont {
+                                font_size: 12.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                        ));
+                    }
+                });
+        }
+    });
+}
+
+pub fn handle_resource_pack_buttons(
+    mut packs: ResMut<ResourcePacks>,
+    mut reloaded: MessageWriter<ResourcePacksReloaded>,
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &ResourcePackButton),
+        Changed<Interaction>,
+    >,
+) {
+    for (interaction, mut color, button) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                packs.toggle(&button.0);
+                reloaded.write(ResourcePacksReloaded);
+            }
+            Interaction::Hovered => {
+                *color = BackgroundColor(Color::srgb(0.3, 0.3, 0.3));
+            }
+            Interaction::None => {
+                *color = BackgroundColor(Color::srgb(0.2, 0.2, 0.2));
+            }
+        }
+    }
+}
+
+pub fn handle_reload_resource_packs_button(
+    asset_server: Res<AssetServer>,
+    mut packs: ResMut<ResourcePacks>,
+    mut reloaded: MessageWriter<ResourcePacksReloaded>,
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (
+            Changed<Interaction>,
+            With<Button>,
+            With<ReloadResourcePacksButton>,
+        ),
+    >,
+) {
+    for (interaction, mut color) in interaction_query.iter_mut() {
+        match *interaction {
+            Interaction::Pressed => {
+                // Re-read files from packs that stay enabled, then pick up added
+                // or removed packs and overrides
+                packs.reload_files(&asset_server);
+                packs.rescan();
+                reloaded.write(ResourcePacksReloaded);
+                println!("[Resource Packs] Reloaded {} packs", packs.enabled.len());
+            }
+            Interaction::Hovered => {
+                *color = BackgroundColor(Color::srgb(0.3, 0.3, 0.3));
+            }
+            Interaction::None => {
+                *color = BackgroundColor(Color::srgb(0.2, 0.2, 0.2));
+            }
+        }
+    }
+}
@@ -1,30 +1,97 @@
 use crate::mob::components::{Mob, MobBehavior, MobState, MobType};
 use crate::player::components::{
-    CameraController, CharacterController, DespawnMiningEffect, FootstepTimer, Health, Hunger,
-    Inventory, MiningProgress, PickupDrops, Player,
+    AttackCooldown, CameraController, CharacterController, DamageCause, Dead, DespawnMiningEffect,
+    EXHAUSTION_PER_POINT, EatingProgress, FootstepTimer, Health, Hunger, Inventory, InventorySlot,
+    MiningProgress, PickupDrops, Player,
 };
-use crate::player::resources::SoundAssets;
+use crate::player::container_ui::{MenuLayout, OpenContainer};
+use crate::player::resources::{RightClickUsed, SoundAssets};
 use crate::player::settings_menu::Settings;
-use crate::world::components::{CHUNK_SIZE, Chunk, DropItem, ItemType, NeedsMeshUpdate, VoxelType};
+use crate::world::block_entity::EMPTY_SLOT;
+use crate::world::block_state::{SLAB_DOUBLE, UPPER, completes_slab, placement_state};
+use crate::world::blocks::{BlockModel, BlockRegistry};
+use crate::world::colliders::ChunkColliders;
+use crate::world::components::{
+    BlockChanged, Chunk, DropItem, ItemType, NeedsMeshUpdate, VoxelType,
+};
+use crate::world::explosion::{LIT_FUSE_SECONDS, spawn_primed_explosive};
+use crate::world::farming::till;
+use crate::world::interact::{UseOutcome, break_block, use_block};
+use crate::world::items::{ItemRegistry, ToolKind};
+use crate::world::loot::LootContext;
+use crate::world::redstone;
 use crate::world::resources::VoxelWorld;
-use crate::world::systems::{BlockAssets, InitialChunkMeshing};
+use crate::world::shapes::STEP_HEIGHT;
+use crate::world::systems::{
+    BlockAssets, InitialChunkMeshing, RemeshBatch, block_at, mark_neighbor_chunks, set_block_at,
+};
 use bevy::audio::{AudioPlayer, AudioSource, PlaybackSettings, Volume};
 use bevy::ecs::system::SystemParam;
 use bevy::input::mouse::MouseMotion;
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 
+/// How fast, and for how many seconds before they can be picked up, items
+/// thrown out of the inventory fly.
+const THROW_SPEED: f32 = 6.0;
+const THROW_PICKUP_DELAY: f32 = 1.5;
+/// How quickly thrown items slow down along the ground, per second.
+const THROW_DRAG: f32 = 3.0;
+/// Fastest an item flies off a dead player's body.
+const DEATH_SCATTER_SPEED: f32 = 3.0;
+/// How hard, and for how many seconds, a hit mob is knocked back.
+const KNOCKBACK_SPEED: f32 = 6.0;
+const KNOCKBACK_LIFT: f32 = 4.0;
+pub const KNOCKBACK_SECONDS: f32 = 0.4;
+/// Exhaustion per block sprinted, per jump, per block mined and per attack.
+/// See `Hunger`.
+const SPRINT_EXHAUSTION: f32 = 0.1;
+const JUMP_EXHAUSTION: f32 = 0.05;
+const SPRINT_JUMP_EXHAUSTION: f32 = 0.2;
+const MINING_EXHAUSTION: f32 = 0.005;
+const ATTACK_EXHAUSTION: f32 = 0.1;
+/// Hunger needed to regenerate health, the seconds each point takes and the
+/// exhaustion it costs.
+const REGEN_MIN_HUNGER: i32 = 18;
+const REGEN_SECONDS: f32 = 4.0;
+const REGEN_EXHAUSTION: f32 = 6.0;
+/// Seconds right click is held to eat an item, and between chewing sounds.
+const EAT_SECONDS: f32 = 1.6;
+const CHEW_SECONDS: f32 = 0.25;
+
+/// Where the player appears on loading the world: where they left off, or
+/// where they'd respawn if they're new or left while dead.
+pub fn spawn_position(world_settings: &crate::main_menu::WorldSettings) -> Vec3 {
+    world_settings
+        .player_position
+        .unwrap_or_else(|| respawn_position(world_settings))
+}
+
+/// Where the player appears after dying.
+pub fn respawn_position(world_settings: &crate::main_menu::WorldSettings) -> Vec3 {
+    world_settings
+        .respawn_point
+        .unwrap_or_else(|| world_spawn(world_settings))
+}
+
+/// The world spawn point, or above the world origin if none was set.
+fn world_spawn(world_settings: &crate::main_menu::WorldSettings) -> Vec3 {
+    world_settings
+        .spawn_point
+        .unwrap_or_else(|| Vec3::new(0.0, spawn_height(), 0.0))
+}
+
 pub fn spawn_player(mut commands: Commands, world_settings: Res<crate::main_menu::WorldSettings>) {
     let inventory = world_settings.inventory.clone().unwrap_or_default();
-    let spawn_pos = world_settings
-        .player_position
-        .unwrap_or_else(|| Vec3::new(0.0, spawn_height(), 0.0));
+    let spawn_pos = spawn_position(&world_settings);
 
     let player_entity = commands
         .spawn((
             Player,
             CharacterController::default(),
             MiningProgress::default(),
+            AttackCooldown::default(),
+            EatingProgress::default(),
             inventory,
             Health::default(),
             Hunger::default(),
@@ -83,7 +150,7 @@ pub fn spawn_player_when_ready(
     meshing: Res<InitialChunkMeshing>,
     players: Query<Entity, With<Player>>,
     voxel_world: Res<VoxelWorld>,
-    chunk_colliders: Query<(), With<Collider>>,
+    chunk_colliders: Query<&ChunkColliders>,
     world_settings: Res<crate::main_menu::WorldSettings>,
 ) {
     if meshing.0 {
@@ -93,16 +160,12 @@ pub fn spawn_player_when_ready(
         return;
     }
 
-    let spawn_pos = world_settings
-        .player_position
-        .unwrap_or_else(|| Vec3::new(0.0, spawn_height(), 0.0));
-
-    let spawn_chunk_pos = VoxelWorld::world_to_chunk_pos(spawn_pos);
+    let spawn_chunk_pos = VoxelWorld::world_to_chunk_pos(spawn_position(&world_settings));
     let has_chunk = voxel_world
         .chunks
         .get(&spawn_chunk_pos)
-        .map(|entity| chunk_colliders.contains(*entity))
-        .unwrap_or(false);
+        .and_then(|entity| chunk_colliders.get(*entity).ok())
+        .is_some_and(ChunkColliders::is_built);
     if !has_chunk {
         return;
     }
@@ -117,10 +180,11 @@ pub fn player_move(
     time: Res<Time<Fixed>>,
     mut query: Query<(
         Entity,
-        &Transform,
+        &mut Transform,
         &mut CharacterController,
         &mut Velocity,
         &mut Health,
+        &mut Hunger,
     )>,
     rapier_context: ReadRapierContext,
     settings_menu: Query<&Visibility, With<crate::player::settings_menu::SettingsMenu>>,
@@ -136,7 +200,9 @@ pub fn player_move(
         return;
     }
 
-    if let Ok((entity, transform, mut controller, mut velocity, mut health)) = query.single_mut() {
+    if let Ok((entity, mut transform, mut controller, mut velocity, mut health, mut hunger)) =
+        query.single_mut()
+    {
         let rapier_context = rapier_context.single().expect("No RapierContext found");
         let rotation = transform.rotation;
         let forward = rotation * Vec3::NEG_Z;
@@ -217,6 +283,28 @@ pub fn player_move(
             next_velocity.y
         };
 
+        // Walk up slabs and stairs: probe the ground just ahead of the feet and
+        // lift the player onto it when it's no higher than a step
+        if controller.is_grounded && direction != Vec3::ZERO {
+            let probe_height = STEP_HEIGHT + 0.05;
+            let feet = transform.translation - Vec3::Y * 0.9;
+            let probe = feet + Vec3::Y * probe_height + direction * 0.4;
+            if let Some((_entity, toi)) = rapier_context.cast_ray(
+                probe,
+                -Vec3::Y,
+                probe_height,
+                true,
+                QueryFilter::default()
+                    .exclude_rigid_body(entity)
+                    .exclude_sensors(),
+            ) {
+                let rise = probe_height - toi;
+                if rise > 0.05 && rise <= STEP_HEIGHT + 0.01 {
+                    transform.translation.y += rise + 0.01;
+                }
+            }
+        }
+
         // Ground check using Rapier raycast from player's feet
         let was_grounded = controller.is_grounded;
         let ray_pos = transform.translation + Vec3::new(0.0, -0.9, 0.0); // Feet position (bottom of collider)
@@ -243,7 +331,7 @@ pub fn player_move(
             let fall_distance = controller.fall_start_y - transform.translation.y;
             if fall_distance > 3.0 {
                 let damage = (fall_distance - 3.0).floor() as i32;
-                health.current = (health.current - damage).max(0);
+                health.damage(damage, DamageCause::Fall);
             }
             controller.fall_start_y = transform.translation.y;
         }
@@ -251,6 +339,11 @@ pub fn player_move(
         if controller.is_grounded && keyboard_input.pressed(KeyCode::Space) {
             velocity.linvel.y = controller.jump_force;
             controller.is_grounded = false;
+            hunger.exhaust(if keyboard_input.pressed(KeyCode::ShiftLeft) {
+                SPRINT_JUMP_EXHAUSTION
+            } else {
+                JUMP_EXHAUSTION
+            });
         }
 
         if !controller.is_grounded {
@@ -269,41 +362,132 @@ pub fn update_hunger(
     };
 
     let dt = time.delta_secs();
-    let moving = velocity.linvel.xz().length_squared() > 0.1;
-    let sprinting = keyboard_input.pressed(KeyCode::ShiftLeft) && moving;
-    let interval = if sprinting { 8.0 } else { 20.0 };
+    let speed = velocity.linvel.xz().length();
+    if keyboard_input.pressed(KeyCode::ShiftLeft) && speed * speed > 0.1 {
+        hunger.exhaust(SPRINT_EXHAUSTION * speed * dt);
+    }
 
-    if hunger.current > 0 {
-        hunger.timer += dt;
-        if hunger.timer >= interval {
-            hunger.timer -= interval;
+    while hunger.exhaustion >= EXHAUSTION_PER_POINT {
+        hunger.exhaustion -= EXHAUSTION_PER_POINT;
+        if hunger.saturation > 0.0 {
+            hunger.saturation = (hunger.saturation - 1.0).max(0.0);
+        } else {
             hunger.current = (hunger.current - 1).max(0);
         }
+    }
+
+    // Heal slowly while well fed, which makes the player hungrier
+    if hunger.current >= REGEN_MIN_HUNGER && health.current > 0 && health.current < health.max {
+        hunger.regen_timer += dt;
+        if hunger.regen_timer >= REGEN_SECONDS {
+            hunger.regen_timer -= REGEN_SECONDS;
+            health.current += 1;
+            hunger.exhaust(REGEN_EXHAUSTION);
+        }
+    } else {
+        hunger.regen_timer = 0.0;
+    }
+
+    if hunger.current > 0 {
         hunger.damage_timer = 0.0;
     } else {
         hunger.damage_timer += dt;
         if hunger.damage_timer >= 2.0 {
             hunger.damage_timer -= 2.0;
-            health.current = (health.current - 1).max(0);
+            health.damage(1, DamageCause::Starvation);
         }
     }
 }
 
+/// Eats the selected item while right click is held on it, restoring hunger
+/// once it's been held for `EAT_SECONDS`. Only a hungry player can eat, and
+/// not while holding a right click that used a block.
+#[allow(clippy::too_many_arguments)]
+pub fn eat_food(
+    mut commands: Commands,
+    time: Res<Time>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut right_click_used: ResMut<RightClickUsed>,
+    open_container: Res<OpenContainer>,
+    settings_menu: Query<&Visibility, With<crate::player::settings_menu::SettingsMenu>>,
+    items: Res<ItemRegistry>,
+    sound_assets: Res<SoundAssets>,
+    settings: Res<Settings>,
+    mut query: Query<(&mut Inventory, &mut Hunger, &mut EatingProgress), With<Player>>,
+) {
+    if !mouse_input.pressed(MouseButton::Right) {
+        right_click_used.0 = false;
+    }
+    let Ok((mut inventory, mut hunger, mut eating)) = query.single_mut() else {
+        return;
+    };
+    let paused = open_container.is_open()
+        || settings_menu
+            .single()
+            .is_ok_and(|visibility| *visibility != Visibility::Hidden);
+    let selected = inventory.selected_slot;
+    let food = items
+        .food_for(inventory.slots[selected].item_type)
+        .filter(|_| mouse_input.pressed(MouseButton::Right) && !paused && !right_click_used.0)
+        .filter(|_| hunger.current < hunger.max);
+    let Some(food) = food else {
+        *eating = EatingProgress::default();
+        return;
+    };
+    if eating.slot != Some(selected) {
+        *eating = EatingProgress {
+            slot: Some(selected),
+            ..default()
+        };
+    }
+
+    let dt = time.delta_secs();
+    eating.timer += dt;
+    eating.sound_timer -= dt;
+    if eating.sound_timer <= 0.0 && !sound_assets.eat.is_empty() {
+        use rand::Rng;
+        eating.sound_timer += CHEW_SECONDS;
+        let index = rand::thread_rng().gen_range(0..sound_assets.eat.len());
+        play_sound(
+            &mut commands,
+            sound_assets.eat[index].clone(),
+            settings.master_volume,
+        );
+    }
+
+    if eating.timer >= EAT_SECONDS {
+        hunger.eat(food);
+        let slot = &mut inventory.slots[selected];
+        slot.count -= 1;
+        if slot.count == 0 {
+            *slot = EMPTY_SLOT;
+        }
+        play_sound(
+            &mut commands,
+            sound_assets.burp.clone(),
+            settings.master_volume,
+        );
+        *eating = EatingProgress::default();
+    }
+}
+
+/// Run condition for the systems that move the player and let them act,
+/// which stop while they're dead.
+pub fn player_alive(dead_query: Query<(), (With<Player>, With<Dead>)>) -> bool {
+    dead_query.is_empty()
+}
+
+/// Marks a player whose health ran out as `Dead`, which brings up the death
+/// screen, and scatters their inventory where they fell unless the world
+/// keeps it.
 pub fn handle_player_death(
-    mut query: Query<
-        (
-            &mut Transform,
-            &mut Velocity,
-            &mut Health,
-            &mut Hunger,
-            &mut CharacterController,
-        ),
-        With<Player>,
-    >,
+    mut commands: Commands,
+    mut query: Query<(Entity, &Transform, &mut Velocity, &Health, &mut Inventory), With<Player>>,
+    block_assets: Res<BlockAssets>,
+    world_settings: Res<crate::main_menu::WorldSettings>,
+    mut open_container: ResMut<OpenContainer>,
 ) {
-    let Ok((mut transform, mut velocity, mut health, mut hunger, mut controller)) =
-        query.single_mut()
-    else {
+    let Ok((entity, transform, mut velocity, health, mut inventory)) = query.single_mut() else {
         return;
     };
 
@@ -311,15 +495,43 @@ pub fn handle_player_death(
         return;
     }
 
-    health.current = health.max;
-    hunger.current = hunger.max;
-    hunger.timer = 0.0;
-    hunger.damage_timer = 0.0;
-    transform.translation = Vec3::new(0.0, spawn_height(), 0.0);
+    commands.entity(entity).insert(Dead);
     velocity.linvel = Vec3::ZERO;
-    controller.is_grounded = false;
-    controller.is_grounded = false;
-    controller.fall_start_y = transform.translation.y;
+    open_container.close();
+    if world_settings.keep_inventory {
+        return;
+    }
+
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+    let Inventory {
+        slots,
+        offhand,
+        held,
+        crafting,
+        ..
+    } = &mut *inventory;
+    let stacks = slots
+        .iter_mut()
+        .chain(crafting.iter_mut())
+        .chain([offhand, held]);
+    for stack in stacks {
+        if stack.item_type == ItemType::NONE {
+            continue;
+        }
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let speed = rng.gen_range(0.0..DEATH_SCATTER_SPEED);
+        let velocity = Vec3::new(angle.cos() * speed, 3.0, angle.sin() * speed);
+        spawn_moving_drop(
+            &mut commands,
+            &block_assets,
+            transform.translation,
+            velocity,
+            THROW_PICKUP_DELAY,
+            stack,
+        );
+        *stack = EMPTY_SLOT;
+    }
 }
 
 pub fn update_sprint_fov(
@@ -385,6 +597,19 @@ pub fn despawn_mining_effects(mut commands: Commands, query: Query<Entity, With<
     }
 }
 
+type InteractMobQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Mob,
+        &'static mut MobState,
+        &'static MeshMaterial3d<StandardMaterial>,
+        &'static mut Health,
+        &'static mut Velocity,
+    ),
+    Without<Player>,
+>;
+
 #[derive(SystemParam)]
 pub struct InteractionParams<'w, 's> {
     pub commands: Commands<'w, 's>,
@@ -394,36 +619,50 @@ pub struct InteractionParams<'w, 's> {
     pub chunk_query: Query<'w, 's, &'static mut Chunk>,
     pub voxel_world: Res<'w, VoxelWorld>,
     pub block_assets: Res<'w, BlockAssets>,
+    pub registry: Res<'w, BlockRegistry>,
+    pub items: Res<'w, ItemRegistry>,
     pub sound_assets: Res<'w, SoundAssets>,
     pub settings: Res<'w, Settings>,
     pub inventory_query: Query<'w, 's, &'static mut Inventory, With<Player>>,
     pub mining_query: Query<'w, 's, &'static mut MiningProgress, With<Player>>,
+    pub hunger_query: Query<'w, 's, &'static mut Hunger, With<Player>>,
     pub player_query: Query<'w, 's, (Entity, &'static GlobalTransform), With<Player>>,
     pub settings_menu:
         Query<'w, 's, &'static Visibility, With<crate::player::settings_menu::SettingsMenu>>,
-    pub mob_query: Query<
+    pub mob_query: InteractMobQuery<'w, 's>,
+    pub attacker_query: Query<
         'w,
         's,
         (
-            &'static Mob,
-            &'static mut MobState,
-            &'static MeshMaterial3d<StandardMaterial>,
+            &'static mut AttackCooldown,
+            &'static Velocity,
+            &'static CharacterController,
         ),
+        With<Player>,
     >,
     pub materials: ResMut<'w, Assets<StandardMaterial>>,
+    pub block_changes: MessageWriter<'w, BlockChanged>,
+    pub open_container: ResMut<'w, OpenContainer>,
+    pub right_click_used: ResMut<'w, RightClickUsed>,
 }
 
 pub fn player_interact(mut params: InteractionParams, time: Res<Time>) {
     let mut rng = rand::thread_rng();
+    if let Ok((mut cooldown, _, _)) = params.attacker_query.single_mut() {
+        cooldown.0 = (cooldown.0 - time.delta_secs()).max(0.0);
+    }
     if let Ok(visibility) = params.settings_menu.single()
         && *visibility != Visibility::Hidden
     {
         return;
     }
+    if params.open_container.is_open() {
+        return;
+    }
 
     let right_click = params.mouse_input.just_pressed(MouseButton::Right);
     let left_click_pressed = params.mouse_input.pressed(MouseButton::Left);
-    let _left_click_just_pressed = params.mouse_input.just_pressed(MouseButton::Left);
+    let left_click_just_pressed = params.mouse_input.just_pressed(MouseButton::Left);
 
     let Ok(mut mining_progress) = params.mining_query.single_mut() else {
         return;
@@ -452,26 +691,49 @@ pub fn player_interact(mut params: InteractionParams, time: Res<Time>) {
         // Offset ray origin slightly forward to avoid self-collision if filter fails
         let ray_origin = ray_origin + *ray_direction * 0.1;
 
-        if let Some((target_entity, toi)) =
-            rapier_context.cast_ray(ray_origin, *ray_direction, 4.0, true, filter)
+        if let Some((target_entity, intersection)) =
+            rapier_context.cast_ray_and_get_normal(ray_origin, *ray_direction, 4.0, true, filter)
         {
+            let toi = intersection.time_of_impact;
             // Skip hits that are too close (e.g. self)
             if toi < 0.1 {
                 return;
             }
 
             let hit_point = ray_origin + *ray_direction * toi;
+            let hit_normal = intersection.normal;
+
+            // Left clicking a mob attacks it rather than mining behind it
+            if left_click_pressed && params.mob_query.contains(target_entity) {
+                mining_progress.target = None;
+                mining_progress.progress = 0.0;
+                if left_click_just_pressed {
+                    attack_mob(&mut params, player_entity, target_entity, *ray_direction);
+                }
+                return;
+            }
 
             // Target position based on interaction type
 
-            // Left click: remove block (aim slightly inside the block)
-            // Right click: add block (aim slightly outside the block)
-            let (world_voxel_pos, target_entity) = if left_click_pressed {
-                let p: Vec3 = hit_point + *ray_direction * 0.05;
-                (p.floor().as_ivec3(), target_entity)
+            // Left click: remove the block that was hit (aim slightly inside it)
+            // Right click: add a block against the face that was hit
+            let clicked_voxel_pos = (hit_point - hit_normal * 0.05).floor().as_ivec3();
+            let (world_voxel_pos, place_state) = if left_click_pressed {
+                (clicked_voxel_pos, 0)
             } else {
-                let p: Vec3 = hit_point - *ray_direction * 0.05;
-                (p.floor().as_ivec3(), target_entity)
+                let held_block = params.inventory_query.single().ok().and_then(|inventory| {
+                    let selected = inventory.slots[inventory.selected_slot].item_type;
+                    params.items.block_for(selected)
+                });
+                placement_target(
+                    &params.registry,
+                    held_block,
+                    clicked_voxel_pos,
+                    block_at(&params.voxel_world, &params.chunk_query, clicked_voxel_pos),
+                    hit_point,
+                    hit_normal,
+                    *ray_direction,
+                )
             };
 
             if left_click_pressed {
@@ -485,8 +747,8 @@ pub fn player_interact(mut params: InteractionParams, time: Res<Time>) {
                 let selected_slot = inventory.selected_slot;
                 let selected_item = inventory.slots[selected_slot].item_type;
 
-                if selected_item == ItemType::Wheat
-                    && let Ok((mob, mut state, material_handle)) =
+                if Some(selected_item) == params.items.by_name("wheat")
+                    && let Ok((mob, mut state, material_handle, _, _)) =
                         params.mob_query.get_mut(target_entity)
                     && matches!(mob.mob_type, MobType::Cow)
                     && state.state != MobBehavior::Love
@@ -501,12 +763,156 @@ pub fn player_interact(mut params: InteractionParams, time: Res<Time>) {
                     let slot = &mut inventory.slots[selected_slot];
                     slot.count -= 1;
                     if slot.count == 0 {
-                        slot.item_type = ItemType::None;
+                        slot.item_type = ItemType::NONE;
                     }
                     return; // Don't place block
                 }
             }
 
+            // Right-clicking an explosive lights it instead of placing against it
+            if right_click
+                && let Some((voxel, _)) =
+                    block_at(&params.voxel_world, &params.chunk_query, clicked_voxel_pos)
+                && params.registry.get(voxel).explosion_power.is_some()
+            {
+                set_block_at(
+                    &mut params.commands,
+                    &params.voxel_world,
+                    &mut params.chunk_query,
+                    clicked_voxel_pos,
+                    VoxelType::AIR,
+                    0,
+                );
+                params.block_changes.write(BlockChanged {
+                    pos: clicked_voxel_pos,
+                });
+                spawn_primed_explosive(
+                    &mut params.commands,
+                    &params.block_assets,
+                    &params.registry,
+                    voxel,
+                    clicked_voxel_pos,
+                    LIT_FUSE_SECONDS,
+                );
+                play_sound(
+                    &mut params.commands,
+                    params.sound_assets.fuse.clone(),
+                    params.settings.master_volume,
+                );
+                return;
+            }
+
+            // Doors, gates and containers are opened rather than built on
+            if right_click
+                && let Some(outcome) = use_block(
+                    &mut params.commands,
+                    &params.voxel_world,
+                    &mut params.chunk_query,
+                    &params.registry,
+                    &mut params.block_changes,
+                    clicked_voxel_pos,
+                )
+            {
+                let sound = match outcome {
+                    UseOutcome::Swung { open: true } => Some(params.sound_assets.door_open.clone()),
+                    UseOutcome::Swung { open: false } => {
+                        Some(params.sound_assets.door_close.clone())
+                    }
+                    UseOutcome::OpenedContainer(kind) => {
+                        params
+                            .open_container
+                            .open_block(clicked_voxel_pos, MenuLayout::Container(kind));
+                        Some(params.sound_assets.chest_open.clone())
+                    }
+                    UseOutcome::OpenedCraftingTable => {
+                        params
+                            .open_container
+                            .open_block(clicked_voxel_pos, MenuLayout::CraftingTable);
+                        None
+                    }
+                };
+                if let Some(sound) = sound {
+                    play_sound(&mut params.commands, sound, params.settings.master_volume);
+                }
+                params.right_click_used.0 = true;
+                return;
+            }
+
+            // Levers, buttons and repeaters are switched rather than built on
+            if right_click
+                && let Some((voxel, state)) =
+                    block_at(&params.voxel_world, &params.chunk_query, clicked_voxel_pos)
+                && let Some(new_state) = redstone::use_state(params.registry.get(voxel), state)
+            {
+                set_block_at(
+                    &mut params.commands,
+                    &params.voxel_world,
+                    &mut params.chunk_query,
+                    clicked_voxel_pos,
+                    voxel,
+                    new_state,
+                );
+                params.block_changes.write(BlockChanged {
+                    pos: clicked_voxel_pos,
+                });
+                play_sound(
+                    &mut params.commands,
+                    params.sound_assets.click.clone(),
+                    params.settings.master_volume,
+                );
+                params.right_click_used.0 = true;
+                return;
+            }
+
+            // Hoes till the top of dirt and grass rather than build on it
+            if right_click
+                && hit_normal.y > 0.5
+                && let Ok(inventory) = params.inventory_query.single()
+                && params
+                    .items
+                    .tool_for(inventory.slots[inventory.selected_slot].item_type)
+                    .is_some_and(|(kind, _)| kind == ToolKind::Hoe)
+                && let Some(tilled) = till(
+                    &mut params.commands,
+                    &params.voxel_world,
+                    &mut params.chunk_query,
+                    &params.registry,
+                    &mut params.block_changes,
+                    clicked_voxel_pos,
+                )
+            {
+                wear_selected_tool(
+                    &mut params.commands,
+                    &mut params.inventory_query,
+                    &params.items,
+                    &params.sound_assets,
+                    &params.settings,
+                );
+                if let Some(sounds) = params.sound_assets.for_block(&params.registry, tilled) {
+                    play_sound(
+                        &mut params.commands,
+                        sounds.step.clone(),
+                        params.settings.master_volume,
+                    );
+                }
+                return;
+            }
+
+            // A door fills the cell above too, so it needs room there
+            let room_above = block_at(
+                &params.voxel_world,
+                &params.chunk_query,
+                world_voxel_pos + IVec3::Y,
+            )
+            .is_some_and(|(voxel, _)| voxel == VoxelType::AIR);
+            // Crops only take root in soil
+            let soil_below = block_at(
+                &params.voxel_world,
+                &params.chunk_query,
+                world_voxel_pos - IVec3::Y,
+            )
+            .is_some_and(|(voxel, _)| params.registry.get(voxel).soil.is_some());
+
             let world_pos: Vec3 = world_voxel_pos.as_vec3();
             let chunk_pos = VoxelWorld::world_to_chunk_pos(world_pos);
             let local_voxel_pos = VoxelWorld::voxel_to_local_pos(world_voxel_pos);
@@ -518,14 +924,16 @@ pub fn player_interact(mut params: InteractionParams, time: Res<Time>) {
             {
                 if left_click_pressed {
                     let voxel = chunk.get_voxel(local_voxel_pos);
-                    if voxel != VoxelType::Air && voxel != VoxelType::Bedrock {
-                        // Calculate mining speed
-                        let hardness = voxel.hardness();
-                        let tool_speed = 1.0; // Hand speed for now
-                        let multiplier = 1.5; // Correct tool multiplier is usually 1.5
-
-                        // T = (Hardness * multiplier) / Speed
-                        let time_to_break = (hardness * multiplier) / tool_speed;
+                    let block = params.registry.get(voxel);
+                    if block.model != BlockModel::None && block.is_breakable() {
+                        let held = params
+                            .inventory_query
+                            .single()
+                            .map_or(ItemType::NONE, |inventory| {
+                                inventory.slots[inventory.selected_slot].item_type
+                            });
+                        let tool = params.items.tool_for(held);
+                        let time_to_break = block.mining_time(tool);
 
                         if time_to_break <= 0.0 {
                             mining_progress.progress = 1.0;
@@ -538,64 +946,71 @@ pub fn player_interact(mut params: InteractionParams, time: Res<Time>) {
                             mining_progress.progress = 0.0;
                             mining_progress.timer = 0.0;
 
-                            chunk.set_voxel(local_voxel_pos, VoxelType::Air);
-                            params.commands.entity(chunk_entity).insert(NeedsMeshUpdate);
-                            mark_neighbor_chunks(
+                            let state = chunk.get_state(local_voxel_pos);
+                            let mut batch = RemeshBatch::default();
+                            break_block(
                                 &mut params.commands,
                                 &params.voxel_world,
-                                chunk_pos,
-                                local_voxel_pos,
+                                &mut params.chunk_query,
+                                &params.registry,
+                                &params.block_assets,
+                                &mut params.block_changes,
+                                &mut batch,
+                                world_voxel_pos,
                             );
+                            batch.flush(&mut params.commands, &params.voxel_world);
 
-                            let drop_item_type = if voxel == VoxelType::TallGrass {
-                                use rand::Rng;
-                                if rng.gen_bool(0.1) {
-                                    ItemType::Wheat
-                                } else {
-                                    ItemType::None
-                                }
+                            // Too weak a tool breaks the block without dropping it
+                            let drops = if block.harvestable_with(tool) {
+                                let context = LootContext::mined_with(held, &params.items);
+                                block.drops_for(state).roll(&context, &mut rng)
                             } else {
-                                match voxel {
-                                    VoxelType::Grass => ItemType::GrassBlock,
-                                    VoxelType::Dirt => ItemType::Dirt,
-                                    VoxelType::Stone => ItemType::Stone,
-                                    VoxelType::CoalOre => ItemType::CoalOre,
-                                    VoxelType::IronOre => ItemType::IronOre,
-                                    VoxelType::GoldOre => ItemType::GoldOre,
-                                    VoxelType::DiamondOre => ItemType::DiamondOre,
-                                    _ => ItemType::None,
-                                }
+                                Vec::new()
                             };
-
-                            if drop_item_type != ItemType::None {
+                            for item in drops {
                                 spawn_drop_item(
                                     &mut params.commands,
                                     &params.block_assets,
                                     world_voxel_pos,
-                                    drop_item_type,
+                                    item,
                                 );
                             }
 
-                            if let Some(sound) = block_break_sound(voxel, &params.sound_assets) {
+                            if let Some(sound) =
+                                block_break_sound(voxel, &params.registry, &params.sound_assets)
+                            {
                                 play_sound(
                                     &mut params.commands,
                                     sound,
                                     params.settings.master_volume,
                                 );
                             }
+                            if let Ok(mut hunger) = params.hunger_query.single_mut() {
+                                hunger.exhaust(MINING_EXHAUSTION);
+                            }
+                            // Blocks that break instantly don't wear tools
+                            if block.hardness > 0.0 {
+                                wear_selected_tool(
+                                    &mut params.commands,
+                                    &mut params.inventory_query,
+                                    &params.items,
+                                    &params.sound_assets,
+                                    &params.settings,
+                                );
+                            }
                         } else {
                             // Play hit sound every 0.25s
                             mining_progress.timer += time.delta_secs();
                             if mining_progress.timer >= 0.25 {
                                 mining_progress.timer -= 0.25;
                                 use rand::Rng;
-                                let index = rng.gen_range(0..4);
-                                let sound = match voxel {
-                                    VoxelType::Grass | VoxelType::Dirt | VoxelType::TallGrass => {
-                                        Some(params.sound_assets.hit_grass[index].clone())
-                                    }
-                                    _ => Some(params.sound_assets.hit_stone[index].clone()),
-                                };
+                                let sound = params
+                                    .sound_assets
+                                    .for_block(&params.registry, voxel)
+                                    .filter(|sounds| !sounds.hit.is_empty())
+                                    .map(|sounds| {
+                                        sounds.hit[rng.gen_range(0..sounds.hit.len())].clone()
+                                    });
                                 if let Some(sound) = sound {
                                     play_sound(
                                         &mut params.commands,
@@ -626,19 +1041,24 @@ pub fn player_interact(mut params: InteractionParams, time: Res<Time>) {
                     let selected_slot = inventory.selected_slot;
                     let selected_item = inventory.slots[selected_slot].item_type;
 
-                    let place_voxel = match selected_item {
-                        ItemType::GrassBlock => VoxelType::Grass,
-                        ItemType::Dirt => VoxelType::Dirt,
-                        ItemType::Stone => VoxelType::Stone,
-                        ItemType::CoalOre => VoxelType::CoalOre,
-                        ItemType::IronOre => VoxelType::IronOre,
-                        ItemType::GoldOre => VoxelType::GoldOre,
-                        ItemType::DiamondOre => VoxelType::DiamondOre,
-                        _ => VoxelType::Air,
-                    };
-
-                    if place_voxel != VoxelType::Air
-                        && chunk.get_voxel(local_voxel_pos) == VoxelType::Air
+                    let place_voxel = params
+                        .items
+                        .block_for(selected_item)
+                        .unwrap_or(VoxelType::AIR);
+
+                    let block = params.registry.get(place_voxel);
+                    let upper_half = block.variant.filter(|_| block.model == BlockModel::Door);
+                    if (upper_half.is_some() && !room_above)
+                        || (block.crop.is_some() && !soil_below)
+                    {
+                        return;
+                    }
+
+                    let existing = chunk.get_voxel(local_voxel_pos);
+                    // A double slab goes where the half it completes already is
+                    let completes_slab = existing == place_voxel && place_state == SLAB_DOUBLE;
+                    if place_voxel != VoxelType::AIR
+                        && (existing == VoxelType::AIR || completes_slab)
                     {
                         // Prevent placing block inside player
                         if let Ok((_player_entity, player_global_transform)) =
@@ -657,7 +1077,7 @@ pub fn player_interact(mut params: InteractionParams, time: Res<Time>) {
                             }
                         }
 
-                        chunk.set_voxel(local_voxel_pos, place_voxel);
+                        chunk.set_block(local_voxel_pos, place_voxel, place_state);
                         params.commands.entity(chunk_entity).insert(NeedsMeshUpdate);
                         mark_neighbor_chunks(
                             &mut params.commands,
@@ -665,6 +1085,21 @@ pub fn player_interact(mut params: InteractionParams, time: Res<Time>) {
                             chunk_pos,
                             local_voxel_pos,
                         );
+                        params.block_changes.write(BlockChanged {
+                            pos: world_voxel_pos,
+                        });
+                        if let Some(upper_half) = upper_half {
+                            let upper_pos = world_voxel_pos + IVec3::Y;
+                            set_block_at(
+                                &mut params.commands,
+                                &params.voxel_world,
+                                &mut params.chunk_query,
+                                upper_pos,
+                                upper_half,
+                                place_state | UPPER,
+                            );
+                            params.block_changes.write(BlockChanged { pos: upper_pos });
+                        }
                         play_sound(
                             &mut params.commands,
                             params.sound_assets.place_block.clone(),
@@ -675,7 +1110,7 @@ pub fn player_interact(mut params: InteractionParams, time: Res<Time>) {
                         if slot.count > 0 {
                             slot.count -= 1;
                             if slot.count == 0 {
-                                slot.item_type = ItemType::None;
+                                slot.item_type = ItemType::NONE;
                             }
                         }
                     }
@@ -685,29 +1120,216 @@ pub fn player_interact(mut params: InteractionParams, time: Res<Time>) {
     }
 }
 
-fn spawn_drop_item(
+/// Hits `target` with the selected item, knocking it back along
+/// `direction`. Hits while falling are critical, dealing half again as much.
+fn attack_mob(params: &mut InteractionParams, player: Entity, target: Entity, direction: Vec3) {
+    let Ok((mut cooldown, velocity, controller)) = params.attacker_query.single_mut() else {
+        return;
+    };
+    if cooldown.0 > 0.0 {
+        return;
+    }
+    let held = params
+        .inventory_query
+        .single()
+        .map_or(ItemType::NONE, |inventory| {
+            inventory.slots[inventory.selected_slot].item_type
+        });
+    let attack = params.items.attack(held);
+    cooldown.0 = attack.cooldown;
+    if let Ok(mut hunger) = params.hunger_query.single_mut() {
+        hunger.exhaust(ATTACK_EXHAUSTION);
+    }
+    let critical = !controller.is_grounded && velocity.linvel.y < 0.0;
+    let damage = if critical {
+        (attack.damage * 3 + 1) / 2
+    } else {
+        attack.damage
+    };
+
+    let Ok((mut mob, mut state, _, mut health, mut mob_velocity)) =
+        params.mob_query.get_mut(target)
+    else {
+        return;
+    };
+    health.damage(damage, DamageCause::Player);
+    let push = Vec3::new(direction.x, 0.0, direction.z).normalize_or_zero();
+    mob_velocity.linvel = push * KNOCKBACK_SPEED + Vec3::Y * KNOCKBACK_LIFT;
+    mob.knockback_timer = KNOCKBACK_SECONDS;
+    // Slimes turn on whoever hits them
+    if mob.mob_type == MobType::Slime {
+        state.state = MobBehavior::Attacking;
+        state.target_entity = Some(player);
+        state.timer = 10.0;
+    }
+
+    let sounds = params.sound_assets.hurt_for(mob.mob_type);
+    if !sounds.is_empty() {
+        use rand::Rng;
+        let sound = sounds[rand::thread_rng().gen_range(0..sounds.len())].clone();
+        play_sound(&mut params.commands, sound, params.settings.master_volume);
+    }
+    wear_selected_tool(
+        &mut params.commands,
+        &mut params.inventory_query,
+        &params.items,
+        &params.sound_assets,
+        &params.settings,
+    );
+}
+
+/// Wears the selected tool by a use, breaking it once it's used up.
+fn wear_selected_tool(
+    commands: &mut Commands,
+    inventory_query: &mut Query<&mut Inventory, With<Player>>,
+    items: &ItemRegistry,
+    sound_assets: &SoundAssets,
+    settings: &Settings,
+) {
+    let Ok(mut inventory) = inventory_query.single_mut() else {
+        return;
+    };
+    let selected_slot = inventory.selected_slot;
+    let slot = &mut inventory.slots[selected_slot];
+    let Some(durability) = items.durability(slot.item_type) else {
+        return;
+    };
+    slot.damage += 1;
+    if slot.damage >= durability {
+        *slot = EMPTY_SLOT;
+        play_sound(
+            commands,
+            sound_assets.tool_break.clone(),
+            settings.master_volume,
+        );
+    }
+}
+
+/// The axis-aligned direction a hit normal points along, so diagonal faces
+/// like cross models still place into a neighboring cell.
+fn face_offset(normal: Vec3) -> IVec3 {
+    let abs = normal.abs();
+    if abs.x >= abs.y && abs.x >= abs.z {
+        IVec3::new(normal.x.signum() as i32, 0, 0)
+    } else if abs.y >= abs.z {
+        IVec3::new(0, normal.y.signum() as i32, 0)
+    } else {
+        IVec3::new(0, 0, normal.z.signum() as i32)
+    }
+}
+
+/// Where a right click puts the held block, and in which state: against the
+/// clicked face, oriented by the face and the look direction, or into the
+/// clicked slab when it completes it.
+fn placement_target(
+    registry: &BlockRegistry,
+    held_block: Option<VoxelType>,
+    clicked_voxel_pos: IVec3,
+    clicked_block: Option<(VoxelType, u8)>,
+    hit_point: Vec3,
+    hit_normal: Vec3,
+    look: Vec3,
+) -> (IVec3, u8) {
+    let adjacent = clicked_voxel_pos + face_offset(hit_normal);
+    let Some(place_voxel) = held_block else {
+        return (adjacent, 0);
+    };
+
+    let block = registry.get(place_voxel);
+    if block.model == BlockModel::Slab
+        && let Some((voxel, state)) = clicked_block
+        && voxel == place_voxel
+        && completes_slab(state, hit_normal)
+    {
+        return (clicked_voxel_pos, SLAB_DOUBLE);
+    }
+    (
+        adjacent,
+        placement_state(block, hit_normal, hit_point, look),
+    )
+}
+
+pub fn spawn_drop_item(
     commands: &mut Commands,
     block_assets: &BlockAssets,
     voxel_pos: IVec3,
     item_type: ItemType,
 ) {
-    let material = match item_type {
-        ItemType::GrassBlock => block_assets.grass_side_material.clone(),
-        ItemType::Dirt => block_assets.dirt_material.clone(),
-        ItemType::Stone => block_assets.stone_material.clone(),
-        ItemType::CoalOre => block_assets.coal_ore_material.clone(),
-        ItemType::IronOre => block_assets.iron_ore_material.clone(),
-        ItemType::GoldOre => block_assets.gold_ore_material.clone(),
-        ItemType::DiamondOre => block_assets.diamond_ore_material.clone(),
-        ItemType::Wheat => block_assets.wheat_material.clone(),
-        _ => block_assets.stone_material.clone(), // Fallback for tools/other items
+    let translation = voxel_pos.as_vec3() + Vec3::splat(0.5);
+    let stack = InventorySlot {
+        item_type,
+        count: 1,
+        damage: 0,
     };
+    spawn_moving_drop(commands, block_assets, translation, Vec3::ZERO, 0.0, &stack);
+}
 
+/// Drops the stack in `slot` at `voxel_pos` as one item, keeping a tool's
+/// wear.
+pub fn spawn_slot_drops(
+    commands: &mut Commands,
+    block_assets: &BlockAssets,
+    voxel_pos: IVec3,
+    slot: &InventorySlot,
+) {
+    if slot.item_type == ItemType::NONE || slot.count == 0 {
+        return;
+    }
     let translation = voxel_pos.as_vec3() + Vec3::splat(0.5);
+    spawn_moving_drop(commands, block_assets, translation, Vec3::ZERO, 0.0, slot);
+}
+
+/// Throws `count` of the items in `stack` from `eye` the way it's facing.
+pub fn throw_items(
+    commands: &mut Commands,
+    block_assets: &BlockAssets,
+    eye: &GlobalTransform,
+    stack: &mut InventorySlot,
+    count: u32,
+) {
+    let count = count.min(stack.count);
+    if count == 0 {
+        return;
+    }
+    let velocity = eye.forward() * THROW_SPEED + Vec3::Y * 2.0;
+    spawn_moving_drop(
+        commands,
+        block_assets,
+        eye.translation() + eye.forward() * 0.3,
+        velocity,
+        THROW_PICKUP_DELAY,
+        &InventorySlot { count, ..*stack },
+    );
+    stack.count -= count;
+    if stack.count == 0 {
+        stack.item_type = ItemType::NONE;
+    }
+}
+
+/// Spawns the whole of `stack` as one dropped item.
+fn spawn_moving_drop(
+    commands: &mut Commands,
+    block_assets: &BlockAssets,
+    translation: Vec3,
+    velocity: Vec3,
+    pickup_delay: f32,
+    stack: &InventorySlot,
+) {
+    let material = block_assets
+        .item_materials
+        .get(stack.item_type.0 as usize)
+        .cloned();
+    let Some(material) = material else {
+        return;
+    };
+
     commands.spawn((
         DropItem {
-            item_type,
-            velocity: Vec3::ZERO,
+            item_type: stack.item_type,
+            count: stack.count,
+            damage: stack.damage,
+            velocity,
+            pickup_delay,
         },
         Mesh3d(block_assets.mesh.clone()),
         MeshMaterial3d(material),
@@ -718,40 +1340,11 @@ fn spawn_drop_item(
     ));
 }
 
-fn mark_neighbor_chunks(
-    commands: &mut Commands,
-    voxel_world: &VoxelWorld,
-    chunk_pos: IVec3,
-    local_pos: IVec3,
-) {
-    let mut neighbors = Vec::new();
-    if local_pos.x == 0 {
-        neighbors.push(IVec3::new(-1, 0, 0));
-    } else if local_pos.x == CHUNK_SIZE as i32 - 1 {
-        neighbors.push(IVec3::new(1, 0, 0));
-    }
-    if local_pos.y == 0 {
-        neighbors.push(IVec3::new(0, -1, 0));
-    } else if local_pos.y == CHUNK_SIZE as i32 - 1 {
-        neighbors.push(IVec3::new(0, 1, 0));
-    }
-    if local_pos.z == 0 {
-        neighbors.push(IVec3::new(0, 0, -1));
-    } else if local_pos.z == CHUNK_SIZE as i32 - 1 {
-        neighbors.push(IVec3::new(0, 0, 1));
-    }
-
-    for offset in neighbors {
-        if let Some(entity) = voxel_world.chunks.get(&(chunk_pos + offset)) {
-            commands.entity(*entity).insert(NeedsMeshUpdate);
-        }
-    }
-}
-
 pub fn update_drop_items(
     time: Res<Time>,
     rapier_context: ReadRapierContext,
     voxel_world: Res<VoxelWorld>,
+    registry: Res<BlockRegistry>,
     chunk_query: Query<&Chunk>,
     mut drops: Query<(&mut Transform, &mut DropItem)>,
 ) {
@@ -761,10 +1354,31 @@ pub fn update_drop_items(
     let radius = 0.2;
 
     for (mut transform, mut drop) in drops.iter_mut() {
-        resolve_drop_overlap(&voxel_world, &chunk_query, &mut transform, radius);
+        resolve_drop_overlap(
+            &voxel_world,
+            &registry,
+            &chunk_query,
+            &mut transform,
+            radius,
+        );
 
+        drop.pickup_delay = (drop.pickup_delay - dt).max(0.0);
         drop.velocity.y -= gravity * dt;
 
+        // Thrown items slide to a stop, and stop dead at walls
+        let sideways = Vec3::new(drop.velocity.x, 0.0, drop.velocity.z);
+        if sideways != Vec3::ZERO {
+            let ahead = transform.translation + sideways.normalize() * radius + sideways * dt;
+            if drop_blocked(&voxel_world, &registry, &chunk_query, ahead) {
+                drop.velocity.x = 0.0;
+                drop.velocity.z = 0.0;
+            } else {
+                let drag = (1.0 - THROW_DRAG * dt).max(0.0);
+                drop.velocity.x *= drag;
+                drop.velocity.z *= drag;
+            }
+        }
+
         let mut target_pos = transform.translation + drop.velocity * dt;
         if drop.velocity.y <= 0.0 {
             let ray_origin = transform.translation;
@@ -793,13 +1407,17 @@ pub fn update_drop_items(
 pub fn pickup_drops(
     mut commands: Commands,
     mut inventories: Query<(&GlobalTransform, &mut Inventory), With<PickupDrops>>,
-    drops: Query<(Entity, &Transform, &DropItem)>,
+    mut drops: Query<(Entity, &Transform, &mut DropItem)>,
     sound_assets: Res<SoundAssets>,
+    items: Res<ItemRegistry>,
     settings: Res<Settings>,
 ) {
     let pickup_radius = 1.2;
 
-    for (drop_entity, drop_transform, drop) in drops.iter() {
+    for (drop_entity, drop_transform, mut drop) in drops.iter_mut() {
+        if drop.pickup_delay > 0.0 {
+            continue;
+        }
         for (picker_transform, mut inventory) in inventories.iter_mut() {
             let distance = picker_transform
                 .translation()
@@ -808,38 +1426,57 @@ pub fn pickup_drops(
                 continue;
             }
 
-            if add_to_inventory(&mut inventory, drop.item_type) {
+            let left = add_to_inventory(&mut inventory, &items, &drop);
+            if left == drop.count {
+                continue;
+            }
+            play_sound(
+                &mut commands,
+                sound_assets.pickup_item.clone(),
+                settings.master_volume,
+            );
+            drop.count = left;
+            if left == 0 {
                 commands.entity(drop_entity).despawn();
-                play_sound(
-                    &mut commands,
-                    sound_assets.pickup_item.clone(),
-                    settings.master_volume,
-                );
                 break;
             }
         }
     }
 }
 
-fn add_to_inventory(inventory: &mut Inventory, item_type: ItemType) -> bool {
-    let max_stack = 64;
+/// Adds as much of `drop` as fits onto the inventory's stacks, topping up
+/// matching ones before filling empty slots. Returns how many are left.
+fn add_to_inventory(inventory: &mut Inventory, items: &ItemRegistry, drop: &DropItem) -> u32 {
+    let max_stack = items.max_stack(drop.item_type);
+    let mut left = drop.count;
 
     for slot in &mut inventory.slots {
-        if slot.item_type == item_type && slot.count < max_stack {
-            slot.count += 1;
-            return true;
+        if slot.item_type == drop.item_type && slot.count < max_stack {
+            let moved = left.min(max_stack - slot.count);
+            slot.count += moved;
+            left -= moved;
+            if left == 0 {
+                return 0;
+            }
         }
     }
 
     for slot in &mut inventory.slots {
-        if slot.item_type == ItemType::None {
-            slot.item_type = item_type;
-            slot.count = 1;
-            return true;
+        if slot.item_type == ItemType::NONE {
+            let moved = left.min(max_stack);
+            *slot = InventorySlot {
+                item_type: drop.item_type,
+                count: moved,
+                damage: drop.damage,
+            };
+            left -= moved;
+            if left == 0 {
+                return 0;
+            }
         }
     }
 
-    false
+    left
 }
 
 // update footsteps params
@@ -860,6 +1497,7 @@ pub struct FootstepParams<'w, 's> {
         With<Player>,
     >,
     pub sound_assets: Res<'w, SoundAssets>,
+    pub registry: Res<'w, BlockRegistry>,
     pub settings: Res<'w, Settings>,
     pub commands: Commands<'w, 's>,
     pub settings_menu:
@@ -894,8 +1532,10 @@ pub fn update_footsteps(mut params: FootstepParams) {
         return;
     }
 
-    let voxel_pos = VoxelWorld::world_to_voxel_pos(transform.translation);
-    let chunk_pos = VoxelWorld::world_to_chunk_pos(transform.translation);
+    // Sample the block just below the collider's feet
+    let feet = transform.translation - Vec3::Y * 1.0;
+    let voxel_pos = VoxelWorld::world_to_voxel_pos(feet);
+    let chunk_pos = VoxelWorld::world_to_chunk_pos(feet);
     let local_pos = VoxelWorld::voxel_to_local_pos(voxel_pos);
 
     let Some(&chunk_entity) = params.voxel_world.chunks.get(&chunk_pos) else {
@@ -907,17 +1547,10 @@ pub fn update_footsteps(mut params: FootstepParams) {
 
     let voxel = chunk.get_voxel(local_pos);
 
-    let step_sound = match voxel {
-        VoxelType::Grass => Some(params.sound_assets.step_grass.clone()),
-        VoxelType::Dirt => Some(params.sound_assets.step_dirt.clone()),
-        VoxelType::Stone
-        | VoxelType::CoalOre
-        | VoxelType::IronOre
-        | VoxelType::GoldOre
-        | VoxelType::DiamondOre
-        | VoxelType::Bedrock => Some(params.sound_assets.step_stone.clone()),
-        _ => Some(params.sound_assets.step_stone.clone()),
-    };
+    let step_sound = params
+        .sound_assets
+        .for_block(&params.registry, voxel)
+        .map(|sounds| sounds.step.clone());
 
     if let Some(sound) = step_sound {
         play_sound(
@@ -929,7 +1562,7 @@ pub fn update_footsteps(mut params: FootstepParams) {
     }
 }
 
-fn play_sound(commands: &mut Commands, sound: Handle<AudioSource>, volume: f32) {
+pub fn play_sound(commands: &mut Commands, sound: Handle<AudioSource>, volume: f32) {
     commands.spawn((
         AudioPlayer::new(sound),
         PlaybackSettings::DESPAWN
@@ -941,40 +1574,25 @@ fn play_sound(commands: &mut Commands, sound: Handle<AudioSource>, volume: f32)
     ));
 }
 
-fn block_break_sound(voxel: VoxelType, sound_assets: &SoundAssets) -> Option<Handle<AudioSource>> {
-    match voxel {
-        VoxelType::Grass | VoxelType::Dirt | VoxelType::TallGrass => {
-            Some(sound_assets.break_grass.clone())
-        }
-        VoxelType::Stone
-        | VoxelType::CoalOre
-        | VoxelType::IronOre
-        | VoxelType::GoldOre
-        | VoxelType::DiamondOre
-        | VoxelType::Bedrock => Some(sound_assets.break_stone.clone()),
-        VoxelType::Air => None,
-    }
+fn block_break_sound(
+    voxel: VoxelType,
+    registry: &BlockRegistry,
+    sound_assets: &SoundAssets,
+) -> Option<Handle<AudioSource>> {
+    sound_assets
+        .for_block(registry, voxel)
+        .map(|sounds| sounds.break_sound.clone())
 }
 
 fn resolve_drop_overlap(
     voxel_world: &VoxelWorld,
+    registry: &BlockRegistry,
     chunk_query: &Query<&Chunk>,
     transform: &mut Transform,
     radius: f32,
 ) {
     for _ in 0..4 {
-        let voxel_pos = VoxelWorld::world_to_voxel_pos(transform.translation);
-        let chunk_pos = VoxelWorld::world_to_chunk_pos(transform.translation);
-        let local_pos = VoxelWorld::voxel_to_local_pos(voxel_pos);
-
-        let voxel = voxel_world
-            .chunks
-            .get(&chunk_pos)
-            .and_then(|entity| chunk_query.get(*entity).ok())
-            .map(|chunk| chunk.get_voxel(local_pos))
-            .unwrap_or(VoxelType::Air);
-
-        if voxel == VoxelType::Air {
+        if !drop_blocked(voxel_world, registry, chunk_query, transform.translation) {
             break;
         }
 
@@ -982,6 +1600,25 @@ fn resolve_drop_overlap(
     }
 }
 
+fn drop_blocked(
+    voxel_world: &VoxelWorld,
+    registry: &BlockRegistry,
+    chunk_query: &Query<&Chunk>,
+    pos: Vec3,
+) -> bool {
+    let voxel_pos = VoxelWorld::world_to_voxel_pos(pos);
+    let chunk_pos = VoxelWorld::world_to_chunk_pos(pos);
+    let local_pos = VoxelWorld::voxel_to_local_pos(voxel_pos);
+
+    let voxel = voxel_world
+        .chunks
+        .get(&chunk_pos)
+        .and_then(|entity| chunk_query.get(*entity).ok())
+        .map(|chunk| chunk.get_voxel(local_pos))
+        .unwrap_or(VoxelType::AIR);
+    registry.has_collision(voxel)
+}
+
 
 
 
@@ -998,8 +1635,9 @@ pub fn player_look(
     mut player_query: Query<&mut Transform, (With<CharacterController>, Without<Camera>)>,
     settings_menu: Query<&Visibility, With<crate::player::settings_menu::SettingsMenu>>,
     command_state: Res<crate::player::inventory_ui::CommandState>,
+    open_container: Res<OpenContainer>,
 ) {
-    if command_state.open {
+    if command_state.open || open_container.is_open() {
         return;
     }
 
@@ -0,0 +1,236 @@
+use bevy::asset::AssetPath;
+use bevy::asset::io::AssetSourceBuilder;
+use bevy::asset::io::file::FileAssetReader;
+use bevy::image::{ImageLoaderSettings, ImageSampler};
+use bevy::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Asset source rooted at the resource pack directory.
+pub const RESOURCE_PACK_SOURCE: &str = "resourcepacks";
+/// Where a pack keeps its overrides, mirroring our own `assets/` layout.
+const PACK_ASSETS_DIR: &str = "assets/minecraft";
+/// Zip packs are extracted here, inside the resource pack directory.
+const ZIP_CACHE_DIR: &str = ".cache";
+const ENABLED_FILE: &str = "enabled.json";
+
+/// Registers the resource pack asset source, so it has to be added before
+/// `DefaultPlugins`.
+pub struct ResourcePackPlugin;
+
+impl Plugin for ResourcePackPlugin {
+    fn build(&self, app: &mut App) {
+        let packs_dir = get_resource_packs_dir();
+        app.register_asset_source(
+            RESOURCE_PACK_SOURCE,
+            AssetSourceBuilder::new(move || Box::new(FileAssetReader::new(packs_dir.clone()))),
+        )
+        .insert_resource(ResourcePacks::scan())
+        .add_message::<ResourcePacksReloaded>();
+    }
+}
+
+/// Sent after the enabled packs or their files change. Anything holding
+/// handles to overridable assets reloads them through `ResourcePacks`.
+#[derive(Message, Clone, Copy)]
+pub struct ResourcePacksReloaded;
+
+pub struct ResourcePack {
+    /// Directory or zip file name, which is also how the pack is enabled.
+    pub name: String,
+    pub description: String,
+    /// Pack root relative to the resource pack directory.
+    root: PathBuf,
+}
+
+#[derive(Resource, Default)]
+pub struct ResourcePacks {
+    pub available: Vec<ResourcePack>,
+    /// Enabled pack names, highest priority first.
+    pub enabled: Vec<String>,
+    /// Pack files handed out so far, so `reload_files` can re-read them.
+    loaded: Mutex<Vec<AssetPath<'static>>>,
+}
+
+impl ResourcePacks {
+    pub fn scan() -> Self {
+        let packs_dir = get_resource_packs_dir();
+        let mut available = Vec::new();
+
+        if let Ok(entries) = fs::read_dir(&packs_dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with('.') {
+                    continue;
+                }
+
+                let root = if path.is_dir() {
+                    PathBuf::from(&name)
+                } else if path.extension().is_some_and(|ext| ext == "zip") {
+                    match extract_zip(&packs_dir, &path, &name) {
+                        Ok(root) => root,
+                        Err(e) => {
+                            println!("[Resource Packs] Skipping {}: {}", name, e);
+                            continue;
+                        }
+                    }
+                } else {
+                    continue;
+                };
+
+                let Some(description) = read_pack_description(&packs_dir.join(&root)) else {
+                    println!("[Resource Packs] Skipping {}: missing pack.mcmeta", name);
+                    continue;
+                };
+                available.push(ResourcePack {
+                    name,
+                    description,
+                    root,
+                });
+            }
+        }
+        available.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let enabled = fs::read_to_string(packs_dir.join(ENABLED_FILE))
+            .ok()
+            .and_then(|data| serde_json::from_str::<Vec<String>>(&data).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|name| available.iter().any(|pack| &pack.name == name))
+            .collect();
+
+        Self {
+            available,
+            enabled,
+            loaded: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Re-scans the pack directory, keeping track of already loaded files.
+    pub fn rescan(&mut self) {
+        let fresh = Self::scan();
+        self.available = fresh.available;
+        self.enabled = fresh.enabled;
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.enabled.iter().any(|enabled| enabled == name)
+    }
+
+    /// Turns a pack on at the highest priority, or turns it off.
+    pub fn toggle(&mut self, name: &str) {
+        if self.is_enabled(name) {
+            self.enabled.retain(|enabled| enabled != name);
+        } else {
+            self.enabled.insert(0, name.to_string());
+        }
+        self.save_enabled();
+    }
+
+    fn save_enabled(&self) {
+        let path = get_resource_packs_dir().join(ENABLED_FILE);
+        if let Ok(json) = serde_json::to_string(&self.enabled) {
+            fs::write(path, json).ok();
+        }
+    }
+
+    /// Finds the highest priority enabled pack that overrides `path`, as a
+    /// path relative to the resource pack directory.
+    fn find_override(&self, path: &str) -> Option<PathBuf> {
+        let packs_dir = get_resource_packs_dir();
+        self.enabled.iter().find_map(|name| {
+            let pack = self.available.iter().find(|pack| &pack.name == name)?;
+            let relative = pack.root.join(PACK_ASSETS_DIR).join(path);
+            packs_dir.join(&relative).is_file().then_some(relative)
+        })
+    }
+
+    /// Picks the highest priority enabled pack that overrides `path`, falling
+    /// back to the built-in assets.
+    pub fn resolve(&self, path: &str) -> AssetPath<'static> {
+        let Some(relative) = self.find_override(path) else {
+            return AssetPath::from(path.to_string());
+        };
+        let asset_path = AssetPath::from_path_buf(relative).with_source(RESOURCE_PACK_SOURCE);
+        if let Ok(mut loaded) = self.loaded.lock()
+            && !loaded.contains(&asset_path)
+        {
+            loaded.push(asset_path.clone());
+        }
+        asset_path
+    }
+
+    /// Where `path` resolves to on disk, for files read outside the asset
+    /// server.
+    pub fn resolve_file(&self, path: &str) -> PathBuf {
+        match self.find_override(path) {
+            Some(relative) => get_resource_packs_dir().join(relative),
+            None => FileAssetReader::get_base_path().join("assets").join(path),
+        }
+    }
+
+    pub fn load<A: Asset>(&self, asset_server: &AssetServer, path: &str) -> Handle<A> {
+        asset_server.load(self.resolve(path))
+    }
+
+    /// Loads a texture with the nearest-neighbor sampling all block art uses.
+    pub fn load_image(&self, asset_server: &AssetServer, path: &str) -> Handle<Image> {
+        asset_server.load_with_settings(self.resolve(path), |settings: &mut ImageLoaderSettings| {
+            settings.sampler = ImageSampler::nearest();
+        })
+    }
+
+    /// Re-reads every pack file loaded so far, picking up edits made on disk.
+    pub fn reload_files(&self, asset_server: &AssetServer) {
+        if let Ok(loaded) = self.loaded.lock() {
+            for path in loaded.iter() {
+                asset_server.reload(path.clone());
+            }
+        }
+    }
+}
+
+pub fn get_resource_packs_dir() -> PathBuf {
+    let mut path = home::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push(".explr");
+    path.push("resourcepacks");
+    fs::create_dir_all(&path).ok();
+    path
+}
+
+/// Extracts a zip pack into the cache unless the cached copy is newer than
+/// the archive, and returns the cached root relative to `packs_dir`.
+fn extract_zip(packs_dir: &Path, zip_path: &Path, name: &str) -> Result<PathBuf, String> {
+    let root = Path::new(ZIP_CACHE_DIR).join(name);
+    let target = packs_dir.join(&root);
+
+    let zip_modified = fs::metadata(zip_path).and_then(|meta| meta.modified());
+    let cache_modified = fs::metadata(&target).and_then(|meta| meta.modified());
+    if let (Ok(zip_modified), Ok(cache_modified)) = (zip_modified, cache_modified)
+        && cache_modified >= zip_modified
+    {
+        return Ok(root);
+    }
+
+    fs::remove_dir_all(&target).ok();
+    let file = fs::File::open(zip_path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    archive.extract(&target).map_err(|e| e.to_string())?;
+    Ok(root)
+}
+
+fn read_pack_description(root: &Path) -> Option<String> {
+    let data = fs::read_to_string(root.join("pack.mcmeta")).ok()?;
+    let meta: serde_json::Value = serde_json::from_str(&data).ok()?;
+    let description = &meta["pack"]["description"];
+    // Descriptions are either plain strings or text components
+    Some(
+        description
+            .as_str()
+            .or_else(|| description["text"].as_str())
+            .unwrap_or_default()
+            .to_string(),
+    )
+}
@@ -0,0 +1,176 @@
+use bevy::math::Affine2;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fs;
+
+use crate::resource_pack::ResourcePacks;
+use crate::world::blocks::BlockRegistry;
+
+/// Animation ticks per second, matching the game tick rate the `.mcmeta`
+/// frame times are written against.
+const ANIMATION_TICKS_PER_SECOND: f32 = 20.0;
+
+fn default_frametime() -> u32 {
+    1
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FrameFile {
+    Index(u32),
+    Timed { index: u32, time: Option<u32> },
+}
+
+#[derive(Deserialize)]
+struct AnimationFile {
+    #[serde(default = "default_frametime")]
+    frametime: u32,
+    #[serde(default)]
+    frames: Vec<FrameFile>,
+}
+
+#[derive(Deserialize)]
+struct McmetaFile {
+    animation: Option<AnimationFile>,
+}
+
+/// Frame order and timing for a vertical strip of square frames.
+#[derive(Clone, Debug)]
+pub struct TextureAnimation {
+    /// Frame index and duration in ticks. Empty means every frame of the
+    /// strip in order, each shown for `frametime` ticks.
+    frames: Vec<(u32, u32)>,
+    frametime: u32,
+}
+
+impl TextureAnimation {
+    /// Reads the `.mcmeta` next to `texture`, from the same pack the texture
+    /// itself resolves to. `None` for textures that aren't animated.
+    pub fn load(packs: &ResourcePacks, texture: &str) -> Option<Self> {
+        let mut path = packs.resolve_file(texture).into_os_string();
+        path.push(".mcmeta");
+        let data = fs::read_to_string(path).ok()?;
+        let meta: McmetaFile = match serde_json::from_str(&data) {
+            Ok(meta) => meta,
+            Err(e) => {
+                println!("[Textures] Invalid {}.mcmeta: {}", texture, e);
+                return None;
+            }
+        };
+        let animation = meta.animation?;
+
+        let frametime = animation.frametime.max(1);
+        let frames = animation
+            .frames
+            .into_iter()
+            .map(|frame| match frame {
+                FrameFile::Index(index) => (index, frametime),
+                FrameFile::Timed { index, time } => (index, time.unwrap_or(frametime).max(1)),
+            })
+            .collect();
+        Some(Self { frames, frametime })
+    }
+
+    /// The strip frame shown `ticks` into the animation.
+    fn frame_at(&self, ticks: u64, frame_count: u32) -> u32 {
+        if self.frames.is_empty() {
+            let cycle = frame_count as u64 * self.frametime as u64;
+            return ((ticks % cycle) / self.frametime as u64) as u32;
+        }
+
+        let cycle: u64 = self.frames.iter().map(|&(_, time)| time as u64).sum();
+        let mut remaining = ticks % cycle;
+        for &(index, time) in &self.frames {
+            if remaining < time as u64 {
+                return index.min(frame_count - 1);
+            }
+            remaining -= time as u64;
+        }
+        0
+    }
+}
+
+struct AnimatedMaterial {
+    material: Handle<StandardMaterial>,
+    image: Handle<Image>,
+    animation: TextureAnimation,
+    /// Frames in the strip, known once the image has loaded.
+    frame_count: Option<u32>,
+    current_frame: Option<u32>,
+}
+
+/// Block materials whose texture is an animation strip. Frames are picked by
+/// moving the material's UV window, so chunk meshes never need rebuilding.
+#[derive(Resource, Default)]
+pub struct BlockAnimations {
+    animated: Vec<AnimatedMaterial>,
+}
+
+impl BlockAnimations {
+    /// `block_materials` holds one handle per `BlockRegistry::materials`
+    /// entry, in the same order.
+    pub fn new(
+        registry: &BlockRegistry,
+        block_materials: &[Handle<StandardMaterial>],
+        materials: &Assets<StandardMaterial>,
+        packs: &ResourcePacks,
+    ) -> Self {
+        let animated = registry
+            .materials
+            .iter()
+            .zip(block_materials)
+            .filter(|(def, _)| !def.texture.is_empty())
+            .filter_map(|(def, handle)| {
+                let animation = TextureAnimation::load(packs, &def.texture)?;
+                let image = materials.get(handle)?.base_color_texture.clone()?;
+                Some(AnimatedMaterial {
+                    material: handle.clone(),
+                    image,
+                    animation,
+                    frame_count: None,
+                    current_frame: None,
+                })
+            })
+            .collect();
+        Self { animated }
+    }
+}
+
+pub fn animate_block_textures(
+    time: Res<Time>,
+    images: Res<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut animations: ResMut<BlockAnimations>,
+) {
+    let ticks = (time.elapsed_secs_f64() * ANIMATION_TICKS_PER_SECOND as f64) as u64;
+
+    for animated in animations.animated.iter_mut() {
+        let frame_count = match animated.frame_count {
+            Some(count) => count,
+            None => {
+                let Some(image) = images.get(&animated.image) else {
+                    continue;
+                };
+                let size = image.size();
+                let count = (size.y / size.x.max(1)).max(1);
+                animated.frame_count = Some(count);
+                count
+            }
+        };
+
+        let frame = animated.animation.frame_at(ticks, frame_count);
+        if animated.current_frame == Some(frame) {
+            continue;
+        }
+        animated.current_frame = Some(frame);
+
+        if let Some(material) = materials.get_mut(&animated.material) {
+            let height = 1.0 / frame_count as f32;
+            material.uv_transform = Affine2::from_scale_angle_translation(
+                Vec2::new(1.0, height),
+                0.0,
+                Vec2::new(0.0, frame as f32 * height),
+            );
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Extra data for single voxels that don't fit in a state byte, such as a
+//! chest's contents or a furnace's progress. Each chunk keeps its own in
+//! `Chunk::block_entities`, keyed by local position, and saves them with its
+//! voxels.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::player::components::InventorySlot;
+use crate::player::systems::spawn_slot_drops;
+use crate::world::blocks::{BlockEntityKind, BlockRegistry};
+use crate::world::components::{Chunk, ItemType};
+use crate::world::resources::VoxelWorld;
+use crate::world::smelting::{FURNACE_FUEL, FURNACE_INPUT, Furnace, SmeltingRegistry};
+use crate::world::systems::BlockAssets;
+
+pub const CHEST_SLOTS: usize = 27;
+
+pub const EMPTY_SLOT: InventorySlot = InventorySlot {
+    item_type: ItemType::NONE,
+    count: 0,
+    damage: 0,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BlockEntity {
+    Chest { slots: Vec<InventorySlot> },
+    Furnace(Furnace),
+}
+
+impl BlockEntity {
+    pub fn new(kind: BlockEntityKind) -> Self {
+        match kind {
+            BlockEntityKind::Chest => BlockEntity::Chest {
+                slots: vec![EMPTY_SLOT; CHEST_SLOTS],
+            },
+            BlockEntityKind::Furnace => BlockEntity::Furnace(Furnace::default()),
+        }
+    }
+
+    pub fn slots(&self) -> &[InventorySlot] {
+        match self {
+            BlockEntity::Chest { slots } => slots,
+            BlockEntity::Furnace(furnace) => &furnace.slots,
+        }
+    }
+
+    pub fn slots_mut(&mut self) -> &mut [InventorySlot] {
+        match self {
+            BlockEntity::Chest { slots } => slots,
+            BlockEntity::Furnace(furnace) => &mut furnace.slots,
+        }
+    }
+
+    /// Slots an item put in from the player's inventory may go into.
+    /// Furnaces sort items into their input or fuel slot and never take
+    /// anything into their output.
+    pub fn slots_accepting(
+        &self,
+        item: ItemType,
+        smelting: &SmeltingRegistry,
+    ) -> std::ops::Range<usize> {
+        match self {
+            BlockEntity::Chest { slots } => 0..slots.len(),
+            BlockEntity::Furnace(_) if smelting.recipe(item).is_some() => {
+                FURNACE_INPUT..FURNACE_INPUT + 1
+            }
+            BlockEntity::Furnace(_) if smelting.burn_seconds(item).is_some() => {
+                FURNACE_FUEL..FURNACE_FUEL + 1
+            }
+            BlockEntity::Furnace(_) => 0..0,
+        }
+    }
+}
+
+/// The chunk holding world position `pos`, and `pos` local to it.
+pub fn chunk_for<'a>(
+    voxel_world: &VoxelWorld,
+    chunk_query: &'a mut Query<&mut Chunk>,
+    pos: IVec3,
+) -> Option<(Mut<'a, Chunk>, IVec3)> {
+    let chunk_pos = VoxelWorld::world_to_chunk_pos(pos.as_vec3());
+    let entity = *voxel_world.chunks.get(&chunk_pos)?;
+    let chunk = chunk_query.get_mut(entity).ok()?;
+    Some((chunk, VoxelWorld::voxel_to_local_pos(pos)))
+}
+
+/// Gives the block at `pos` its entity if its definition calls for one and
+/// it has none yet. Returns whether it has one now.
+pub fn ensure_block_entity(
+    voxel_world: &VoxelWorld,
+    chunk_query: &mut Query<&mut Chunk>,
+    registry: &BlockRegistry,
+    pos: IVec3,
+) -> bool {
+    let Some((mut chunk, local)) = chunk_for(voxel_world, chunk_query, pos) else {
+        return false;
+    };
+    let Some(kind) = registry.get(chunk.get_voxel(local)).block_entity else {
+        return false;
+    };
+    chunk
+        .block_entities
+        .entry(local)
+        .or_insert_with(|| BlockEntity::new(kind));
+    true
+}
+
+/// Drops everything a destroyed block at `pos` was holding.
+pub fn spill_contents(
+    commands: &mut Commands,
+    block_assets: &BlockAssets,
+    pos: IVec3,
+    block_entity: &BlockEntity,
+) {
+    for slot in block_entity.slots() {
+        spawn_slot_drops(commands, block_assets, pos, slot);
+    }
+}
@@ -0,0 +1,199 @@
+//! Layout of the per-voxel state byte stored next to each `VoxelType`.
+//!
+//! The low bits are shared by whichever orientation a block has: two bits
+//! for a horizontal facing, an axis or a slab's half, or three for a
+//! direction that can also point up or down. The bits above them are flags
+//! with the same meaning for every block that uses them. Redstone parts are
+//! the exception: a wire's power level fills the low four bits, and a
+//! repeater keeps its delay in two of the bits crops use for age.
+
+use bevy::prelude::*;
+
+use crate::world::blocks::{BlockDef, BlockModel, Orientation};
+
+/// Horizontal facing index into `HORIZONTAL_FACINGS`.
+pub const FACING_MASK: u8 = 0b0000_0011;
+/// Direction index into `DIRECTIONS`, for blocks like pistons that can
+/// point up and down as well.
+pub const DIRECTION_MASK: u8 = 0b0000_0111;
+/// Power level of a wire, from 0 to 15. Wires have no orientation or flags,
+/// so the level takes the whole low nibble.
+pub const POWER_MASK: u8 = 0b0000_1111;
+/// Axis a log or pillar runs along, one of the `AXIS_*` values.
+pub const AXIS_MASK: u8 = 0b0000_0011;
+/// Upside-down stairs, or the upper half of a two-block-tall block.
+pub const UPPER: u8 = 0b0000_0100;
+/// An open door, trapdoor or gate, or an extended piston.
+pub const OPEN: u8 = 0b0000_1000;
+/// A repeater's delay, one less than its redstone ticks.
+pub const DELAY_MASK: u8 = 0b0011_0000;
+pub const DELAY_SHIFT: u8 = 4;
+/// A crop's growth stage, from 0 up to its last stage.
+pub const AGE_MASK: u8 = 0b0111_0000;
+pub const AGE_SHIFT: u8 = 4;
+/// Stages the age bits have room for.
+pub const MAX_AGES: usize = 8;
+/// A power source that is switched on, or a repeater that is passing power.
+pub const POWERED: u8 = 0b1000_0000;
+
+pub const AXIS_Y: u8 = 0;
+pub const AXIS_X: u8 = 1;
+pub const AXIS_Z: u8 = 2;
+
+/// Slab halves, one of the `SLAB_*` values.
+pub const SLAB_MASK: u8 = 0b0000_0011;
+pub const SLAB_BOTTOM: u8 = 0;
+pub const SLAB_TOP: u8 = 1;
+pub const SLAB_DOUBLE: u8 = 2;
+
+/// All six directions in direction order: down, up, north, east, south, west.
+pub const DIRECTIONS: [IVec3; 6] = [
+    IVec3::new(0, -1, 0),
+    IVec3::new(0, 1, 0),
+    IVec3::new(0, 0, -1),
+    IVec3::new(1, 0, 0),
+    IVec3::new(0, 0, 1),
+    IVec3::new(-1, 0, 0),
+];
+
+/// Horizontal directions in facing order: north, east, south, west.
+pub const HORIZONTAL_FACINGS: [IVec3; 4] = [
+    IVec3::new(0, 0, -1),
+    IVec3::new(1, 0, 0),
+    IVec3::new(0, 0, 1),
+    IVec3::new(-1, 0, 0),
+];
+
+pub fn facing(state: u8) -> u8 {
+    state & FACING_MASK
+}
+
+pub fn axis(state: u8) -> u8 {
+    state & AXIS_MASK
+}
+
+pub fn direction(state: u8) -> u8 {
+    (state & DIRECTION_MASK).min(DIRECTIONS.len() as u8 - 1)
+}
+
+pub fn is_open(state: u8) -> bool {
+    state & OPEN != 0
+}
+
+pub fn with_open(state: u8, open: bool) -> u8 {
+    if open { state | OPEN } else { state & !OPEN }
+}
+
+pub fn power(state: u8) -> u8 {
+    state & POWER_MASK
+}
+
+pub fn with_power(state: u8, power: u8) -> u8 {
+    (state & !POWER_MASK) | (power & POWER_MASK)
+}
+
+pub fn is_powered(state: u8) -> bool {
+    state & POWERED != 0
+}
+
+pub fn with_powered(state: u8, powered: bool) -> u8 {
+    if powered {
+        state | POWERED
+    } else {
+        state & !POWERED
+    }
+}
+
+/// Redstone ticks a repeater waits, from 1 to 4.
+pub fn delay(state: u8) -> u64 {
+    ((state & DELAY_MASK) >> DELAY_SHIFT) as u64 + 1
+}
+
+/// Steps a repeater's delay on to the next setting, wrapping 4 back to 1.
+pub fn cycle_delay(state: u8) -> u8 {
+    let next = (((state & DELAY_MASK) >> DELAY_SHIFT) + 1) % 4;
+    (state & !DELAY_MASK) | (next << DELAY_SHIFT)
+}
+
+pub fn age(state: u8) -> u8 {
+    (state & AGE_MASK) >> AGE_SHIFT
+}
+
+pub fn with_age(state: u8, age: u8) -> u8 {
+    (state & !AGE_MASK) | ((age << AGE_SHIFT) & AGE_MASK)
+}
+
+/// The horizontal facing closest to `direction`.
+pub fn facing_from_direction(direction: Vec3) -> u8 {
+    if direction.x.abs() > direction.z.abs() {
+        if direction.x > 0.0 { 1 } else { 3 }
+    } else if direction.z > 0.0 {
+        2
+    } else {
+        0
+    }
+}
+
+/// The one of `DIRECTIONS` closest to `vector`.
+pub fn direction_from_vector(vector: Vec3) -> u8 {
+    let abs = vector.abs();
+    if abs.y >= abs.x && abs.y >= abs.z {
+        if vector.y < 0.0 { 0 } else { 1 }
+    } else {
+        2 + facing_from_direction(vector)
+    }
+}
+
+fn axis_from_normal(normal: Vec3) -> u8 {
+    let abs = normal.abs();
+    if abs.x >= abs.y && abs.x >= abs.z {
+        AXIS_X
+    } else if abs.y >= abs.z {
+        AXIS_Y
+    } else {
+        AXIS_Z
+    }
+}
+
+/// Whether a block placed against `hit_normal` at `hit_point` goes in the
+/// upper half of its cell, the way slabs and stairs pick their half.
+fn places_upper_half(hit_normal: Vec3, hit_point: Vec3) -> bool {
+    if hit_normal.y > 0.5 {
+        false
+    } else if hit_normal.y < -0.5 {
+        true
+    } else {
+        hit_point.y - hit_point.y.floor() > 0.5
+    }
+}
+
+/// State for a block placed by a player looking along `look` who clicked a
+/// face with normal `hit_normal` at `hit_point`.
+pub fn placement_state(block: &BlockDef, hit_normal: Vec3, hit_point: Vec3, look: Vec3) -> u8 {
+    let upper = places_upper_half(hit_normal, hit_point);
+    match block.model {
+        BlockModel::Slab if upper => SLAB_TOP,
+        BlockModel::Slab => SLAB_BOTTOM,
+        BlockModel::Stairs | BlockModel::Trapdoor => {
+            let facing = facing_from_direction(look);
+            if upper { facing | UPPER } else { facing }
+        }
+        // Shut across the way the player is looking, so they walk through
+        // once it's open
+        BlockModel::Door | BlockModel::Gate => facing_from_direction(look),
+        _ => match block.orientation {
+            Orientation::None => 0,
+            Orientation::Axis => axis_from_normal(hit_normal),
+            // The front faces back toward the player
+            Orientation::Facing => facing_from_direction(-look),
+            Orientation::Direction => direction_from_vector(-look),
+        },
+    }
+}
+
+/// Whether clicking `hit_normal` of an existing slab in state `state` with
+/// the same slab fills it in to a double slab.
+pub fn completes_slab(state: u8, hit_normal: Vec3) -> bool {
+    let half = state & SLAB_MASK;
+    (half == SLAB_BOTTOM && hit_normal.y > 0.5) || (half == SLAB_TOP && hit_normal.y < -0.5)
+}
@@ -0,0 +1,607 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::world::block_state::{MAX_AGES, age};
+use crate::world::components::VoxelType;
+use crate::world::items::{ItemRegistry, ToolKind, ToolTier};
+use crate::world::loot::{LootEntryFile, LootTable};
+
+/// Block definitions, relative to the assets directory.
+pub const BLOCKS_FILE: &str = "data/blocks.json";
+
+/// Face indices used by `BlockDef::face_materials`, in mesher order.
+pub const FACE_TOP: usize = 0;
+pub const FACE_BOTTOM: usize = 1;
+pub const FACE_SIDE: usize = 2;
+/// The side a `Orientation::Facing` block turns toward the player.
+pub const FACE_FRONT: usize = 3;
+
+/// Blast resistance per point of hardness.
+const BLAST_RESISTANCE_PER_HARDNESS: f32 = 3.0;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockModel {
+    #[default]
+    Cube,
+    /// Two diagonal quads, used by plants.
+    Cross,
+    /// Half a block, in the lower or upper half or doubled up by state.
+    Slab,
+    /// A lower half plus a raised step, facing and upside down by state.
+    Stairs,
+    /// A post that grows rails toward neighboring fences and full blocks.
+    Fence,
+    /// A thin sheet that joins neighboring panes and full blocks.
+    Pane,
+    /// A one pixel thick sheet on the floor.
+    Carpet,
+    /// A small pad on the floor that sinks while pressed.
+    Button,
+    /// A base on the floor with a handle thrown one way or the other.
+    Lever,
+    /// A piston's plate and the arm joining it to the base, pointing along
+    /// its direction.
+    PistonHead,
+    /// One half of a two-block-tall panel that swings to the side when open.
+    Door,
+    /// A thin hatch in the lower or upper half that flips up when open.
+    Trapdoor,
+    /// A fence section that lets things through when open.
+    Gate,
+    /// A block a pixel short of full, like tilled soil.
+    Farmland,
+    /// Not rendered at all.
+    None,
+}
+
+/// Which state bits a block sets when placed, and how its cube faces follow
+/// them. See `block_state` for the bit layout.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Orientation {
+    #[default]
+    None,
+    /// Runs along the axis of the clicked face, like logs. The top texture
+    /// covers both ends.
+    Axis,
+    /// Turns its front texture toward the player, like furnaces.
+    Facing,
+    /// Like `Facing`, but can also point up or down, like pistons. The
+    /// bottom texture goes on the back.
+    Direction,
+}
+
+impl BlockModel {
+    /// Models built from the boxes in `shapes`, rather than a full cube or
+    /// cross quads.
+    pub fn is_shaped(self) -> bool {
+        matches!(
+            self,
+            BlockModel::Slab
+                | BlockModel::Stairs
+                | BlockModel::Fence
+                | BlockModel::Pane
+                | BlockModel::Carpet
+                | BlockModel::Button
+                | BlockModel::Lever
+                | BlockModel::PistonHead
+                | BlockModel::Door
+                | BlockModel::Trapdoor
+                | BlockModel::Gate
+                | BlockModel::Farmland
+        )
+    }
+
+    /// Whether the model's boxes depend on the block state, so changing the
+    /// state alone needs a remesh.
+    pub fn is_shaped_by_state(self) -> bool {
+        matches!(
+            self,
+            BlockModel::Slab
+                | BlockModel::Stairs
+                | BlockModel::Button
+                | BlockModel::Lever
+                | BlockModel::PistonHead
+                | BlockModel::Door
+                | BlockModel::Trapdoor
+                | BlockModel::Gate
+        )
+    }
+
+    /// Whether a fence or pane of this model grows an arm toward a neighbor
+    /// of model `neighbor`.
+    pub fn connects_to(self, neighbor: BlockModel) -> bool {
+        neighbor == self || (self == BlockModel::Fence && neighbor == BlockModel::Gate)
+    }
+}
+
+/// Part a block plays in the power network. See `redstone`.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum RedstoneKind {
+    /// Switched on and off by hand.
+    Lever,
+    /// Powered for a moment after being pressed.
+    Button,
+    /// Powered while a player or mob stands on it.
+    PressurePlate,
+    /// Carries power to its neighbors, a level weaker per block.
+    Wire,
+    /// Passes power through to the other side at full strength, after a
+    /// delay set by hand.
+    Repeater,
+    /// Turns into its `variant` when powered.
+    Lamp,
+    /// Turns into its `variant` when no longer powered.
+    LitLamp,
+    /// Pushes blocks along its direction when powered, with its `variant`
+    /// as the head.
+    Piston,
+    /// A piston that also pulls the block in front of its head back when it
+    /// retracts.
+    StickyPiston,
+    /// A door, trapdoor or gate that swings open when it becomes powered
+    /// and shut when it stops being. Powering either half of a door moves
+    /// both.
+    Door,
+}
+
+/// Data a block keeps in its chunk's block entities. See `block_entity`.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockEntityKind {
+    Chest,
+    Furnace,
+}
+
+/// Tilled ground that crops grow on. See `farming`.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Soil {
+    /// Turns into its `variant` once water is near, or back to dirt if it
+    /// has nothing planted.
+    Dry,
+    /// Turns back into its `variant` once no water is near. Crops grow
+    /// faster on it.
+    Moist,
+}
+
+/// What right-clicking a block does instead of placing against it. See
+/// `interact`.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum UseBehavior {
+    /// Swings open or shut. A door takes its other half, its `variant`,
+    /// along.
+    Open,
+    /// Shows the slots of its block entity.
+    Container,
+    /// Shows a 3x3 crafting grid. See `crafting`.
+    Crafting,
+}
+
+/// How a block's faces are blended with what's behind them.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderLayer {
+    Opaque,
+    /// Alpha-tested and double sided: pixels are either fully drawn or
+    /// skipped, so faces don't need sorting. Glass, leaves and plants.
+    Cutout,
+    /// Alpha-blended and double sided, drawn back to front. Water and ice.
+    Translucent,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(untagged)]
+enum TextureRef {
+    Path(String),
+    Tinted { texture: String, tint: [f32; 3] },
+}
+
+impl TextureRef {
+    fn parts(&self) -> (&str, [f32; 3]) {
+        match self {
+            TextureRef::Path(texture) => (texture, [1.0, 1.0, 1.0]),
+            TextureRef::Tinted { texture, tint } => (texture, *tint),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+struct BlockTexturesFile {
+    all: Option<TextureRef>,
+    top: Option<TextureRef>,
+    bottom: Option<TextureRef>,
+    side: Option<TextureRef>,
+    front: Option<TextureRef>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct CropDefFile {
+    /// Texture for each growth stage, youngest first.
+    stages: Vec<TextureRef>,
+    /// Replaces the block's drops once it reaches its last stage.
+    #[serde(default)]
+    mature_drops: Vec<LootEntryFile>,
+}
+
+/// A plant that grows through stages kept in its age bits. See `farming`.
+#[derive(Clone, Debug)]
+pub struct CropDef {
+    /// Material index per growth stage.
+    pub stage_materials: Vec<usize>,
+    pub mature_drops: LootTable,
+}
+
+impl CropDef {
+    pub fn last_stage(&self) -> u8 {
+        self.stage_materials.len() as u8 - 1
+    }
+
+    pub fn is_mature(&self, state: u8) -> bool {
+        age(state) >= self.last_stage()
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct SoundGroupDef {
+    #[serde(rename = "break")]
+    pub break_sound: String,
+    pub hit: Vec<String>,
+    pub step: String,
+}
+
+#[derive(Deserialize)]
+struct BlockDefFile {
+    id: u16,
+    name: String,
+    #[serde(default)]
+    model: BlockModel,
+    #[serde(default)]
+    orientation: Orientation,
+    #[serde(default)]
+    textures: BlockTexturesFile,
+    #[serde(default)]
+    hardness: f32,
+    /// Tool that mines the block faster.
+    tool: Option<ToolKind>,
+    /// Lowest tier of `tool` that gets the block's drops; without one,
+    /// anything does.
+    harvest_tier: Option<ToolTier>,
+    #[serde(default)]
+    drops: Vec<LootEntryFile>,
+    sounds: Option<String>,
+    /// Defaults to cutout for cross models and opaque otherwise.
+    render_layer: Option<RenderLayer>,
+    #[serde(default = "default_true")]
+    collision: bool,
+    /// Whether the crosshair can target the block when it has no collision.
+    #[serde(default = "default_true")]
+    selectable: bool,
+    #[serde(default)]
+    light_emission: u8,
+    /// Falls when the block below it has no collision, like sand.
+    #[serde(default)]
+    gravity: bool,
+    /// Power of the blast when the block is lit, like TNT.
+    explosion_power: Option<f32>,
+    redstone: Option<RedstoneKind>,
+    #[serde(rename = "use")]
+    use_behavior: Option<UseBehavior>,
+    block_entity: Option<BlockEntityKind>,
+    soil: Option<Soil>,
+    crop: Option<CropDefFile>,
+    /// Name of the block a hoe turns this one into.
+    tills_to: Option<String>,
+    /// Name of the block this one swaps with, like a lamp and its lit form.
+    variant: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BlocksFile {
+    sound_groups: HashMap<String, SoundGroupDef>,
+    blocks: Vec<BlockDefFile>,
+}
+
+/// One distinct material the mesher renders block faces with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockMaterialDef {
+    pub texture: String,
+    pub tint: [f32; 3],
+    pub render_layer: RenderLayer,
+    pub light_emission: u8,
+}
+
+#[derive(Clone, Debug)]
+pub struct BlockDef {
+    pub model: BlockModel,
+    pub orientation: Orientation,
+    /// Material index per face: top, bottom, the sides, then the front.
+    pub face_materials: [usize; 4],
+    /// Scales how long the block takes to mine, see `mining_time`; negative
+    /// means unbreakable.
+    pub hardness: f32,
+    pub tool: Option<ToolKind>,
+    pub harvest_tier: Option<ToolTier>,
+    pub drops: LootTable,
+    pub sound_group: Option<usize>,
+    pub render_layer: RenderLayer,
+    pub collision: bool,
+    pub selectable: bool,
+    pub gravity: bool,
+    pub explosion_power: Option<f32>,
+    pub redstone: Option<RedstoneKind>,
+    pub use_behavior: Option<UseBehavior>,
+    pub block_entity: Option<BlockEntityKind>,
+    pub soil: Option<Soil>,
+    pub crop: Option<CropDef>,
+    pub tills_to: Option<VoxelType>,
+    pub variant: Option<VoxelType>,
+}
+
+impl BlockDef {
+    pub fn is_breakable(&self) -> bool {
+        self.hardness >= 0.0
+    }
+
+    /// Whether mining it with `tool` gets its drops.
+    pub fn harvestable_with(&self, tool: Option<(ToolKind, ToolTier)>) -> bool {
+        match (self.tool, self.harvest_tier) {
+            (Some(kind), Some(needed)) => tool.is_some_and(|(tool_kind, tier)| {
+                tool_kind == kind && tier.harvest_level() >= needed.harvest_level()
+            }),
+            _ => true,
+        }
+    }
+
+    /// Seconds it takes to mine with `tool`, or a hand for `None`.
+    pub fn mining_time(&self, tool: Option<(ToolKind, ToolTier)>) -> f32 {
+        let speed = match tool {
+            Some((kind, tier)) if self.tool == Some(kind) => tier.mining_speed(),
+            _ => 1.0,
+        };
+        // Mining with too weak a tool, or none, is much slower
+        let multiplier = if self.harvestable_with(tool) {
+            1.5
+        } else {
+            5.0
+        };
+        self.hardness * multiplier / speed
+    }
+
+    /// What breaking the block in state `state` may drop.
+    pub fn drops_for(&self, state: u8) -> &LootTable {
+        match &self.crop {
+            Some(crop) if crop.is_mature(state) => &crop.mature_drops,
+            _ => &self.drops,
+        }
+    }
+
+    /// How much of a blast ray the block absorbs; unbreakable blocks stop it.
+    pub fn blast_resistance(&self) -> f32 {
+        if self.is_breakable() {
+            self.hardness * BLAST_RESISTANCE_PER_HARDNESS
+        } else {
+            f32::INFINITY
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct BlockRegistry {
+    blocks: Vec<BlockDef>,
+    pub materials: Vec<BlockMaterialDef>,
+    pub sound_groups: Vec<SoundGroupDef>,
+    by_name: HashMap<String, VoxelType>,
+}
+
+impl BlockRegistry {
+    pub fn load(items: &ItemRegistry) -> Self {
+        let path = FileAssetReader::get_base_path()
+            .join("assets")
+            .join(BLOCKS_FILE);
+        let data = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+        Self::from_json(&data, items).unwrap_or_else(|e| panic!("Invalid {}: {}", BLOCKS_FILE, e))
+    }
+
+    pub fn from_json(data: &str, items: &ItemRegistry) -> Result<Self, String> {
+        let file: BlocksFile = serde_json::from_str(data).map_err(|e| e.to_string())?;
+
+        let mut group_names: Vec<String> = file.sound_groups.keys().cloned().collect();
+        group_names.sort();
+        let sound_groups = group_names
+            .iter()
+            .map(|name| file.sound_groups[name].clone())
+            .collect();
+
+        let mut materials: Vec<BlockMaterialDef> = Vec::new();
+        let mut blocks: Vec<Option<BlockDef>> = Vec::new();
+        let mut by_name = HashMap::new();
+        // Resolved once every name is known, since variants refer both ways
+        let mut variants: Vec<(usize, String, String)> = Vec::new();
+        let mut tilled: Vec<(usize, String, String)> = Vec::new();
+
+        for def in file.blocks {
+            let render_layer = def.render_layer.unwrap_or(match def.model {
+                BlockModel::Cross => RenderLayer::Cutout,
+                _ => RenderLayer::Opaque,
+            });
+            let mut material_for = |texture: Option<&TextureRef>| -> usize {
+                let (texture, tint) = texture.map(TextureRef::parts).unwrap_or(("", [1.0; 3]));
+                let material = BlockMaterialDef {
+                    texture: texture.to_string(),
+                    tint,
+                    render_layer,
+                    light_emission: def.light_emission,
+                };
+                match materials.iter().position(|m| *m == material) {
+                    Some(index) => index,
+                    None => {
+                        materials.push(material);
+                        materials.len() - 1
+                    }
+                }
+            };
+
+            let all = def.textures.all.as_ref();
+            let side = def.textures.side.as_ref().or(all);
+            let face_materials = [
+                material_for(def.textures.top.as_ref().or(all)),
+                material_for(def.textures.bottom.as_ref().or(all)),
+                material_for(side),
+                material_for(def.textures.front.as_ref().or(side)),
+            ];
+            let stage_materials: Option<Vec<usize>> = def
+                .crop
+                .as_ref()
+                .map(|crop| crop.stages.iter().map(|t| material_for(Some(t))).collect());
+
+            let sound_group = match &def.sounds {
+                Some(name) => {
+                    Some(group_names.iter().position(|g| g == name).ok_or_else(|| {
+                        format!("block {}: unknown sound group {}", def.name, name)
+                    })?)
+                }
+                None => None,
+            };
+
+            let id = VoxelType(def.id);
+            if by_name.insert(def.name.clone(), id).is_some() {
+                return Err(format!("duplicate block name {}", def.name));
+            }
+            let resolve_drops = |drops: &[LootEntryFile]| {
+                LootTable::from_file(drops, items).map_err(|e| format!("block {}: {}", def.name, e))
+            };
+            let drops = resolve_drops(&def.drops)?;
+            let crop = match (&def.crop, stage_materials) {
+                (Some(crop), Some(stage_materials)) => {
+                    if stage_materials.is_empty() || stage_materials.len() > MAX_AGES {
+                        return Err(format!(
+                            "block {}: crops need 1 to {} stages",
+                            def.name, MAX_AGES
+                        ));
+                    }
+                    Some(CropDef {
+                        stage_materials,
+                        mature_drops: resolve_drops(&crop.mature_drops)?,
+                    })
+                }
+                _ => None,
+            };
+
+            if def.harvest_tier.is_some() && def.tool.is_none() {
+                return Err(format!("block {}: a harvest tier needs a tool", def.name));
+            }
+
+            let index = def.id as usize;
+            if blocks.len() <= index {
+                blocks.resize(index + 1, None);
+            }
+            if blocks[index].is_some() {
+                return Err(format!("duplicate block id {}", def.id));
+            }
+            blocks[index] = Some(BlockDef {
+                model: def.model,
+                orientation: def.orientation,
+                face_materials,
+                hardness: def.hardness,
+                tool: def.tool,
+                harvest_tier: def.harvest_tier,
+                drops,
+                sound_group,
+                render_layer,
+                collision: def.collision,
+                selectable: def.selectable,
+                gravity: def.gravity,
+                explosion_power: def.explosion_power,
+                redstone: def.redstone,
+                use_behavior: def.use_behavior,
+                block_entity: def.block_entity,
+                soil: def.soil,
+                crop,
+                tills_to: None,
+                variant: None,
+            });
+            if let Some(tills_to) = def.tills_to {
+                tilled.push((index, def.name.clone(), tills_to));
+            }
+            if let Some(variant) = def.variant {
+                variants.push((index, def.name, variant));
+            }
+        }
+
+        for (index, name, variant) in variants {
+            let id = *by_name
+                .get(&variant)
+                .ok_or_else(|| format!("block {}: unknown variant {}", name, variant))?;
+            if let Some(block) = blocks[index].as_mut() {
+                block.variant = Some(id);
+            }
+        }
+        for (index, name, tills_to) in tilled {
+            let id = *by_name
+                .get(&tills_to)
+                .ok_or_else(|| format!("block {}: unknown tilled block {}", name, tills_to))?;
+            if let Some(block) = blocks[index].as_mut() {
+                block.tills_to = Some(id);
+            }
+        }
+
+        let air = blocks
+            .first()
+            .cloned()
+            .flatten()
+            .filter(|b| b.model == BlockModel::None)
+            .ok_or("block id 0 must be an air block with model \"none\"")?;
+        // Unused ids behave like air so stray data can't crash the mesher.
+        let blocks = blocks
+            .into_iter()
+            .map(|block| block.unwrap_or_else(|| air.clone()))
+            .collect();
+
+        Ok(Self {
+            blocks,
+            materials,
+            sound_groups,
+            by_name,
+        })
+    }
+
+    pub fn get(&self, voxel: VoxelType) -> &BlockDef {
+        self.blocks
+            .get(voxel.0 as usize)
+            .unwrap_or(&self.blocks[VoxelType::AIR.0 as usize])
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<VoxelType> {
+        self.by_name.get(name).copied()
+    }
+
+    /// Whether this voxel is a full cube that hides whatever is behind it.
+    pub fn is_opaque(&self, voxel: VoxelType) -> bool {
+        let block = self.get(voxel);
+        block.model == BlockModel::Cube && block.render_layer == RenderLayer::Opaque
+    }
+
+    /// Whether a face of `voxel` is hidden by the block next to it. See-through
+    /// blocks only hide faces of their own kind, so glass next to water still
+    /// shows both surfaces but a body of water has no inner faces.
+    pub fn hides_face(&self, voxel: VoxelType, neighbor: VoxelType) -> bool {
+        self.is_opaque(neighbor)
+            || (neighbor == voxel && self.get(neighbor).model == BlockModel::Cube)
+    }
+
+    pub fn has_collision(&self, voxel: VoxelType) -> bool {
+        self.get(voxel).collision
+    }
+}
@@ -0,0 +1,273 @@
+//! Physics colliders for chunks. Only chunks near the player or another
+//! dynamic body carry any, and each chunk's are split into regions so an
+//! edit rebuilds only the regions whose shapes it changed.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::HashSet;
+
+use crate::main_menu::WorldSettings;
+use crate::player::components::Player;
+use crate::player::systems::spawn_position;
+use crate::world::blocks::{BlockModel, BlockRegistry};
+use crate::world::components::{CHUNK_SIZE, Chunk, ChunkPosition, NeedsMeshUpdate, VoxelType};
+use crate::world::resources::VoxelWorld;
+use crate::world::shapes::{BlockBox, collision_boxes};
+use crate::world::systems::{chunk_voxel_at, connections_at};
+
+/// Edge length of a collider region, in blocks.
+const REGION_SIZE: usize = 8;
+const REGIONS_PER_AXIS: usize = CHUNK_SIZE / REGION_SIZE;
+const REGION_COUNT: usize = REGIONS_PER_AXIS * REGIONS_PER_AXIS * REGIONS_PER_AXIS;
+/// Chunks around a dynamic body's own, in every direction, that get
+/// colliders. They keep them until the body is a chunk farther away, so a
+/// body pacing along a chunk border doesn't rebuild them over and over.
+const PHYSICS_CHUNK_RADIUS: i32 = 1;
+
+/// Marks the child entities holding a chunk's colliders, so remeshing
+/// leaves them alone.
+#[derive(Component)]
+pub struct ChunkCollider;
+
+/// What one region's colliders were built from.
+#[derive(Default, PartialEq)]
+struct RegionShapes {
+    /// Full blocks, merged into one voxel collider.
+    solid: Vec<IVec3>,
+    /// Boxes of shaped blocks, by cell.
+    boxes: Vec<(IVec3, BlockBox)>,
+    /// Cells of blocks that are clicked but walked through, like plants.
+    sensors: Vec<IVec3>,
+}
+
+#[derive(Default)]
+struct RegionColliders {
+    shapes: RegionShapes,
+    entities: Vec<Entity>,
+}
+
+/// The colliders of a chunk within physics range. Chunks without one have
+/// none at all.
+#[derive(Component, Default)]
+pub struct ChunkColliders {
+    regions: Vec<RegionColliders>,
+}
+
+impl ChunkColliders {
+    /// Whether the regions have been built since the chunk came into range.
+    pub fn is_built(&self) -> bool {
+        self.regions.len() == REGION_COUNT
+    }
+
+    fn despawn(&mut self, commands: &mut Commands) {
+        for entity in self.regions.drain(..).flat_map(|region| region.entities) {
+            if let Ok(mut entity_commands) = commands.get_entity(entity) {
+                entity_commands.despawn();
+            }
+        }
+    }
+}
+
+fn region_origin(index: usize) -> IVec3 {
+    let n = REGIONS_PER_AXIS;
+    IVec3::new(
+        (index % n) as i32,
+        (index / n % n) as i32,
+        (index / (n * n)) as i32,
+    ) * REGION_SIZE as i32
+}
+
+fn region_shapes(
+    registry: &BlockRegistry,
+    chunk: &Chunk,
+    origin: IVec3,
+    voxel_at: impl Fn(IVec3) -> VoxelType + Copy,
+) -> RegionShapes {
+    let mut shapes = RegionShapes::default();
+    for x in 0..REGION_SIZE as i32 {
+        for y in 0..REGION_SIZE as i32 {
+            for z in 0..REGION_SIZE as i32 {
+                let pos = origin + IVec3::new(x, y, z);
+                let block = registry.get(chunk.get_voxel(pos));
+                if block.model == BlockModel::None {
+                    continue;
+                }
+                if !block.collision {
+                    if block.selectable {
+                        shapes.sensors.push(pos);
+                    }
+                    continue;
+                }
+                if block.model == BlockModel::Cube {
+                    shapes.solid.push(pos);
+                    continue;
+                }
+                if !block.model.is_shaped() {
+                    continue;
+                }
+
+                let connections = connections_at(registry, block.model, pos, voxel_at);
+                let boxes = collision_boxes(block.model, chunk.get_state(pos), connections);
+                // An open gate is walked through but still clicked
+                if boxes.is_empty() && block.selectable {
+                    shapes.sensors.push(pos);
+                }
+                shapes
+                    .boxes
+                    .extend(boxes.into_iter().map(|block_box| (pos, block_box)));
+            }
+        }
+    }
+    shapes
+}
+
+fn spawn_region_colliders(
+    commands: &mut Commands,
+    chunk_entity: Entity,
+    shapes: &RegionShapes,
+) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    let mut spawn = |collider: Collider, sensor: bool| {
+        let mut entity_commands = commands.spawn((
+            collider,
+            ChunkCollider,
+            Transform::default(),
+            GlobalTransform::default(),
+            ChildOf(chunk_entity),
+        ));
+        if sensor {
+            entity_commands.insert(Sensor);
+        }
+        entities.push(entity_commands.id());
+    };
+
+    // A voxel grid collider aligns perfectly with the blocks, so there are
+    // no edges between them to catch on
+    if !shapes.solid.is_empty() {
+        spawn(Collider::voxels(Vec3::ONE, &shapes.solid), false);
+    }
+    if !shapes.boxes.is_empty() {
+        let boxes = shapes.boxes.iter().map(|(pos, block_box)| {
+            let half = block_box.half_extents();
+            (
+                pos.as_vec3() + block_box.center(),
+                Quat::IDENTITY,
+                Collider::cuboid(half.x, half.y, half.z),
+            )
+        });
+        spawn(Collider::compound(boxes.collect()), false);
+    }
+    if !shapes.sensors.is_empty() {
+        let cells = shapes.sensors.iter().map(|pos| {
+            (
+                pos.as_vec3() + Vec3::splat(0.5),
+                Quat::IDENTITY,
+                Collider::cuboid(0.5, 0.5, 0.5),
+            )
+        });
+        spawn(Collider::compound(cells.collect()), true);
+    }
+    entities
+}
+
+fn chunks_around(centers: &HashSet<IVec3>, radius: i32) -> HashSet<IVec3> {
+    let mut chunks = HashSet::new();
+    for center in centers {
+        for x in -radius..=radius {
+            for y in -radius..=radius {
+                for z in -radius..=radius {
+                    chunks.insert(*center + IVec3::new(x, y, z));
+                }
+            }
+        }
+    }
+    chunks
+}
+
+/// Gives colliders to the chunks around the player and every other dynamic
+/// body, and takes them from chunks nothing is near anymore. Before the
+/// player spawns, the spawn point stands in for them.
+pub fn update_physics_range(
+    mut commands: Commands,
+    voxel_world: Res<VoxelWorld>,
+    world_settings: Res<WorldSettings>,
+    bodies: Query<(&GlobalTransform, &RigidBody)>,
+    players: Query<(), With<Player>>,
+    mut in_range: Query<(Entity, &ChunkPosition, &mut ChunkColliders)>,
+) {
+    let mut centers: HashSet<IVec3> = bodies
+        .iter()
+        .filter(|(_, body)| matches!(body, RigidBody::Dynamic))
+        .map(|(transform, _)| VoxelWorld::world_to_chunk_pos(transform.translation()))
+        .collect();
+    if players.is_empty() {
+        centers.insert(VoxelWorld::world_to_chunk_pos(spawn_position(
+            &world_settings,
+        )));
+    }
+
+    let near = chunks_around(&centers, PHYSICS_CHUNK_RADIUS);
+    let kept = chunks_around(&centers, PHYSICS_CHUNK_RADIUS + 1);
+    for (entity, position, mut colliders) in in_range.iter_mut() {
+        if !kept.contains(&position.0) {
+            colliders.despawn(&mut commands);
+            commands.entity(entity).try_remove::<ChunkColliders>();
+        }
+    }
+    for chunk_pos in near {
+        if let Some(&entity) = voxel_world.chunks.get(&chunk_pos)
+            && !in_range.contains(entity)
+        {
+            // The chunk may be despawning this frame
+            commands
+                .entity(entity)
+                .try_insert(ChunkColliders::default());
+        }
+    }
+}
+
+type ColliderUpdateQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Chunk,
+        &'static ChunkPosition,
+        &'static mut ChunkColliders,
+    ),
+    Or<(With<NeedsMeshUpdate>, Added<ChunkColliders>)>,
+>;
+
+/// Rebuilds the colliders of each region whose shapes changed, in chunks in
+/// physics range that were edited or just came into range.
+pub fn update_chunk_colliders(
+    mut commands: Commands,
+    registry: Res<BlockRegistry>,
+    voxel_world: Res<VoxelWorld>,
+    chunk_lookup: Query<&Chunk>,
+    mut query: ColliderUpdateQuery,
+) {
+    for (entity, chunk, chunk_pos, mut colliders) in query.iter_mut() {
+        if !colliders.is_built() {
+            colliders.regions = (0..REGION_COUNT)
+                .map(|_| RegionColliders::default())
+                .collect();
+        }
+        let voxel_at =
+            |local: IVec3| chunk_voxel_at(&voxel_world, &chunk_lookup, chunk, chunk_pos.0, local);
+
+        for (index, region) in colliders.regions.iter_mut().enumerate() {
+            let shapes = region_shapes(&registry, chunk, region_origin(index), voxel_at);
+            if shapes == region.shapes {
+                continue;
+            }
+            for old in region.entities.drain(..) {
+                if let Ok(mut entity_commands) = commands.get_entity(old) {
+                    entity_commands.despawn();
+                }
+            }
+            region.entities = spawn_region_colliders(&mut commands, entity, &shapes);
+            region.shapes = shapes;
+        }
+    }
+}
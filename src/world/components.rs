@@ -1,24 +1,33 @@
 use bevy::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+use crate::world::block_entity::BlockEntity;
 
 pub const CHUNK_SIZE: usize = 16;
 
 #[derive(Component)]
 pub struct NeedsMeshUpdate;
 
-#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
-pub enum VoxelType {
-    #[default]
-    Air,
-    Grass,
-    Dirt,
-    Stone,
-    CoalOre,
-    IronOre,
-    GoldOre,
-    DiamondOre,
-    Bedrock,
-    TallGrass,
+/// Numeric block id; what each id means is defined in `assets/data/blocks.json`
+/// and looked up through `BlockRegistry`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct VoxelType(pub u16);
+
+/// Ids the world generator and other built-in systems refer to directly.
+impl VoxelType {
+    pub const AIR: Self = Self(0);
+    pub const GRASS: Self = Self(1);
+    pub const DIRT: Self = Self(2);
+    pub const STONE: Self = Self(3);
+    pub const COAL_ORE: Self = Self(4);
+    pub const IRON_ORE: Self = Self(5);
+    pub const GOLD_ORE: Self = Self(6);
+    pub const DIAMOND_ORE: Self = Self(7);
+    pub const BEDROCK: Self = Self(8);
+    pub const TALL_GRASS: Self = Self(9);
+    pub const WATER: Self = Self(15);
 }
 
 #[derive(Component)]
@@ -27,11 +36,26 @@ pub struct InGameEntity;
 #[derive(Component)]
 pub struct Chunk {
     pub voxels: [[[VoxelType; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+    /// Per-voxel state bits, such as a slab's half or a stair's facing. What
+    /// they mean depends on the block's model.
+    pub states: [[[u8; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+    /// Extra data for voxels that need it, by local position. Dropped when
+    /// the voxel is replaced by a different block.
+    pub block_entities: HashMap<IVec3, BlockEntity>,
 }
 
 #[derive(Component, Copy, Clone, Debug)]
 pub struct ChunkPosition(pub IVec3);
 
+/// Face centers of a translucent chunk mesh, in mesh space and vertex order,
+/// so its quads can be re-sorted back to front as the camera moves.
+#[derive(Component)]
+pub struct TranslucentFaces {
+    pub centers: Vec<Vec3>,
+    /// Camera block position, in mesh space, the indices were last sorted for.
+    pub sorted_from: Option<IVec3>,
+}
+
 #[derive(Resource)]
 pub struct GameTime {
     pub time: f32, // 0.0 to 1.0 (0.5 is noon, 0.0/1.0 is midnight)
@@ -55,75 +79,118 @@ pub struct CelestialBody {
     pub is_moon: bool,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
-pub enum ItemType {
-    #[default]
-    None,
-    GrassBlock,
-    Dirt,
-    Stone,
-    CoalOre,
-    IronOre,
-    GoldOre,
-    DiamondOre,
-    Wheat,
+/// Numeric item id; what each id means is defined in `assets/data/items.json`
+/// and looked up through `ItemRegistry`. Id 0 marks an empty slot.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug, Serialize)]
+pub struct ItemType(pub u16);
+
+impl ItemType {
+    pub const NONE: Self = Self(0);
+
+    /// Ids of the variants saves used before items were data driven.
+    fn from_legacy_name(name: &str) -> Option<Self> {
+        let id = match name {
+            "None" => 0,
+            "GrassBlock" => 1,
+            "Dirt" => 2,
+            "Stone" => 3,
+            "CoalOre" => 4,
+            "IronOre" => 5,
+            "GoldOre" => 6,
+            "DiamondOre" => 7,
+            "Wheat" => 8,
+            _ => return None,
+        };
+        Some(Self(id))
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Id(u16),
+            Legacy(String),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Id(id) => Ok(Self(id)),
+            Repr::Legacy(name) => Self::from_legacy_name(&name)
+                .ok_or_else(|| D::Error::custom(format!("unknown item {}", name))),
+        }
+    }
 }
 
 #[derive(Component)]
 pub struct DropItem {
     pub item_type: ItemType,
+    /// How many of the item lie here, as one entity.
+    pub count: u32,
+    /// Wear carried over from a dropped tool.
+    pub damage: u32,
     pub velocity: Vec3,
+    /// Seconds until the item can be picked up, so a thrown one isn't
+    /// picked right back up.
+    pub pickup_delay: f32,
 }
 
 #[derive(Component)]
 pub struct DespawnChunk;
 
+/// Written whenever a voxel is placed, broken or otherwise replaced, so
+/// systems reacting to their surroundings can re-check the position.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct BlockChanged {
+    /// World voxel position of the changed block.
+    pub pos: IVec3,
+}
+
 impl Chunk {
     pub fn empty() -> Self {
         Self {
-            voxels: [[[VoxelType::Air; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+            voxels: [[[VoxelType::AIR; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+            states: [[[0; CHUNK_SIZE]; CHUNK_SIZE]; CHUNK_SIZE],
+            block_entities: HashMap::new(),
         }
     }
 
+    fn contains(pos: IVec3) -> bool {
+        pos.x >= 0
+            && pos.x < CHUNK_SIZE as i32
+            && pos.y >= 0
+            && pos.y < CHUNK_SIZE as i32
+            && pos.z >= 0
+            && pos.z < CHUNK_SIZE as i32
+    }
+
     pub fn get_voxel(&self, pos: IVec3) -> VoxelType {
-        if pos.x < 0
-            || pos.x >= CHUNK_SIZE as i32
-            || pos.y < 0
-            || pos.y >= CHUNK_SIZE as i32
-            || pos.z < 0
-            || pos.z >= CHUNK_SIZE as i32
-        {
-            return VoxelType::Air;
+        if !Self::contains(pos) {
+            return VoxelType::AIR;
         }
         self.voxels[pos.x as usize][pos.y as usize][pos.z as usize]
     }
 
+    /// Sets the voxel with its state bits cleared.
     pub fn set_voxel(&mut self, pos: IVec3, voxel: VoxelType) {
-        if pos.x >= 0
-            && pos.x < CHUNK_SIZE as i32
-            && pos.y >= 0
-            && pos.y < CHUNK_SIZE as i32
-            && pos.z >= 0
-            && pos.z < CHUNK_SIZE as i32
-        {
-            self.voxels[pos.x as usize][pos.y as usize][pos.z as usize] = voxel;
+        self.set_block(pos, voxel, 0);
+    }
+
+    pub fn get_state(&self, pos: IVec3) -> u8 {
+        if !Self::contains(pos) {
+            return 0;
         }
+        self.states[pos.x as usize][pos.y as usize][pos.z as usize]
     }
-}
 
-impl VoxelType {
-    pub fn hardness(&self) -> f32 {
-        match self {
-            VoxelType::Air => 0.0,
-            VoxelType::Grass => 0.6,
-            VoxelType::Dirt => 0.5,
-            VoxelType::Stone => 1.5,
-            VoxelType::CoalOre => 3.0,
-            VoxelType::IronOre => 3.0,
-            VoxelType::GoldOre => 3.0,
-            VoxelType::DiamondOre => 3.0,
-            VoxelType::Bedrock => -1.0, // Unbreakable
-            VoxelType::TallGrass => 0.0,
+    /// Sets the voxel along with its state bits.
+    pub fn set_block(&mut self, pos: IVec3, voxel: VoxelType, state: u8) {
+        if Self::contains(pos) {
+            if self.get_voxel(pos) != voxel {
+                self.block_entities.remove(&pos);
+            }
+            self.voxels[pos.x as usize][pos.y as usize][pos.z as usize] = voxel;
+            self.states[pos.x as usize][pos.y as usize][pos.z as usize] = state;
         }
     }
 }
@@ -0,0 +1,346 @@
+//! Crafting recipes, from `assets/data/recipes.json`. A shaped recipe
+//! matches its pattern anywhere in the grid, mirrored or not; a shapeless
+//! one matches its ingredients in any arrangement.
+
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::world::components::ItemType;
+use crate::world::items::ItemRegistry;
+
+/// Crafting recipes, relative to the assets directory.
+pub const RECIPES_FILE: &str = "data/recipes.json";
+/// Width and height of the crafting table's grid, the largest there is.
+pub const MAX_GRID_SIZE: usize = 3;
+
+fn default_count() -> u32 {
+    1
+}
+
+#[derive(Deserialize)]
+struct RecipeFile {
+    /// Rows of the grid, with a character per cell: a `key` or a space for
+    /// an empty cell.
+    #[serde(default)]
+    pattern: Vec<String>,
+    #[serde(default)]
+    key: HashMap<char, String>,
+    /// Items of a shapeless recipe, instead of a pattern.
+    #[serde(default)]
+    ingredients: Vec<String>,
+    result: String,
+    #[serde(default = "default_count")]
+    count: u32,
+}
+
+#[derive(Deserialize)]
+struct RecipesFile {
+    recipes: Vec<RecipeFile>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Ingredients {
+    /// Items by cell, row by row, `width` cells wide. Empty cells are
+    /// `ItemType::NONE`.
+    Shaped { width: usize, cells: Vec<ItemType> },
+    /// Items in any arrangement, sorted.
+    Shapeless(Vec<ItemType>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Recipe {
+    pub ingredients: Ingredients,
+    pub result: ItemType,
+    pub count: u32,
+}
+
+/// The non-empty cells of a `size` wide grid, trimmed to their bounds:
+/// the trimmed width and the cells row by row.
+fn trimmed(grid: &[ItemType], size: usize) -> (usize, Vec<ItemType>) {
+    let filled = |index: usize| grid[index] != ItemType::NONE;
+    let rows: Vec<usize> = (0..grid.len() / size)
+        .filter(|row| (0..size).any(|col| filled(row * size + col)))
+        .collect();
+    let cols: Vec<usize> = (0..size)
+        .filter(|col| (0..grid.len() / size).any(|row| filled(row * size + col)))
+        .collect();
+    let (Some(&top), Some(&bottom), Some(&left), Some(&right)) =
+        (rows.first(), rows.last(), cols.first(), cols.last())
+    else {
+        return (0, Vec::new());
+    };
+
+    let mut cells = Vec::new();
+    for row in top..=bottom {
+        cells.extend_from_slice(&grid[row * size + left..=row * size + right]);
+    }
+    (right - left + 1, cells)
+}
+
+impl Recipe {
+    /// Whether it can be made in a grid `size` cells wide.
+    pub fn fits(&self, size: usize) -> bool {
+        match &self.ingredients {
+            Ingredients::Shaped { width, cells } => *width <= size && cells.len() / width <= size,
+            Ingredients::Shapeless(items) => items.len() <= size * size,
+        }
+    }
+
+    /// Where each ingredient goes to make it in the top left of a `size`
+    /// wide grid, by cell index.
+    pub fn placement(&self, size: usize) -> Vec<(usize, ItemType)> {
+        match &self.ingredients {
+            Ingredients::Shaped { width, cells } => cells
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| **item != ItemType::NONE)
+                .map(|(index, item)| ((index / width) * size + index % width, *item))
+                .collect(),
+            Ingredients::Shapeless(items) => items.iter().copied().enumerate().collect(),
+        }
+    }
+
+    /// How many of each item it uses.
+    pub fn item_counts(&self) -> HashMap<ItemType, u32> {
+        let mut counts = HashMap::new();
+        for (_, item) in self.placement(MAX_GRID_SIZE) {
+            *counts.entry(item).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    fn matches(&self, grid: &[ItemType], size: usize) -> bool {
+        match &self.ingredients {
+            Ingredients::Shaped { width, cells } => {
+                let (grid_width, grid_cells) = trimmed(grid, size);
+                if grid_width != *width || grid_cells.len() != cells.len() {
+                    return false;
+                }
+                let mirrored = grid_cells.chunks(*width).flat_map(|row| row.iter().rev());
+                grid_cells == *cells || mirrored.eq(cells.iter())
+            }
+            Ingredients::Shapeless(items) => {
+                let mut placed: Vec<ItemType> = grid
+                    .iter()
+                    .copied()
+                    .filter(|item| *item != ItemType::NONE)
+                    .collect();
+                placed.sort_by_key(|item| item.0);
+                placed == *items
+            }
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct RecipeRegistry {
+    recipes: Vec<Recipe>,
+}
+
+impl RecipeRegistry {
+    pub fn load(items: &ItemRegistry) -> Self {
+        let path = FileAssetReader::get_base_path()
+            .join("assets")
+            .join(RECIPES_FILE);
+        let data = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+        Self::from_json(&data, items).unwrap_or_else(|e| panic!("Invalid {}: {}", RECIPES_FILE, e))
+    }
+
+    pub fn from_json(data: &str, items: &ItemRegistry) -> Result<Self, String> {
+        let file: RecipesFile = serde_json::from_str(data).map_err(|e| e.to_string())?;
+        let item = |name: &str| {
+            items
+                .by_name(name)
+                .ok_or_else(|| format!("unknown item {}", name))
+        };
+
+        let mut recipes = Vec::new();
+        for recipe in file.recipes {
+            let context = |e: String| format!("recipe for {}: {}", recipe.result, e);
+            let ingredients = match (recipe.pattern.is_empty(), recipe.ingredients.is_empty()) {
+                (false, true) => {
+                    let width = recipe.pattern[0].chars().count();
+                    let height = recipe.pattern.len();
+                    if width == 0 {
+                        return Err(context("pattern rows are empty".to_string()));
+                    }
+                    if width > MAX_GRID_SIZE || height > MAX_GRID_SIZE {
+                        return Err(context("pattern is larger than 3x3".to_string()));
+                    }
+                    let mut cells = Vec::new();
+                    for row in &recipe.pattern {
+                        if row.chars().count() != width {
+                            return Err(context("pattern rows differ in length".to_string()));
+                        }
+                        for symbol in row.chars() {
+                            cells.push(match symbol {
+                                ' ' => ItemType::NONE,
+                                _ => {
+                                    let name = recipe.key.get(&symbol).ok_or_else(|| {
+                                        context(format!("no key for '{}'", symbol))
+                                    })?;
+                                    item(name).map_err(context)?
+                                }
+                            });
+                        }
+                    }
+                    if trimmed(&cells, width) != (width, cells.clone()) {
+                        return Err(context("pattern has an empty edge".to_string()));
+                    }
+                    Ingredients::Shaped { width, cells }
+                }
+                (true, false) => {
+                    if recipe.ingredients.len() > MAX_GRID_SIZE * MAX_GRID_SIZE {
+                        return Err(context("more than 9 ingredients".to_string()));
+                    }
+                    let mut placed = recipe
+                        .ingredients
+                        .iter()
+                        .map(|name| item(name))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(context)?;
+                    placed.sort_by_key(|item| item.0);
+                    Ingredients::Shapeless(placed)
+                }
+                _ => {
+                    return Err(context("needs either a pattern or ingredients".to_string()));
+                }
+            };
+            if recipe.count == 0 {
+                return Err(context("count must be positive".to_string()));
+            }
+
+            recipes.push(Recipe {
+                ingredients,
+                result: item(&recipe.result)?,
+                count: recipe.count,
+            });
+        }
+
+        Ok(Self { recipes })
+    }
+
+    /// The recipe the items in a `size` wide grid make, if any.
+    pub fn find(&self, grid: &[ItemType], size: usize) -> Option<&Recipe> {
+        self.recipes
+            .iter()
+            .find(|recipe| recipe.fits(size) && recipe.matches(grid, size))
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Recipe> {
+        self.recipes.get(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Recipe> {
+        self.recipes.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::items::test_items;
+
+    const RECIPES: &str = r#"{ "recipes": [
+        { "pattern": ["PP", "P/", " /"], "key": { "P": "oak_planks", "/": "stick" },
+          "result": "wooden_axe" },
+        { "pattern": ["SSS", "S S", "SSS"], "key": { "S": "stone" }, "result": "furnace" },
+        { "ingredients": ["stone", "stick", "oak_planks"], "result": "torch", "count": 4 }
+    ] }"#;
+
+    fn registries() -> (RecipeRegistry, ItemRegistry) {
+        let items = test_items(&[
+            "oak_planks",
+            "stick",
+            "stone",
+            "wooden_axe",
+            "furnace",
+            "torch",
+        ]);
+        let recipes = RecipeRegistry::from_json(RECIPES, &items).unwrap();
+        (recipes, items)
+    }
+
+    /// A grid from rows of `P` for planks, `/` for sticks, `S` for stone
+    /// and spaces for empty cells.
+    fn grid(items: &ItemRegistry, rows: &[&str]) -> Vec<ItemType> {
+        rows.iter()
+            .flat_map(|row| row.chars())
+            .map(|symbol| match symbol {
+                'P' => items.by_name("oak_planks").unwrap(),
+                '/' => items.by_name("stick").unwrap(),
+                'S' => items.by_name("stone").unwrap(),
+                _ => ItemType::NONE,
+            })
+            .collect()
+    }
+
+    fn result(recipes: &RecipeRegistry, grid: &[ItemType], size: usize) -> Option<ItemType> {
+        recipes.find(grid, size).map(|recipe| recipe.result)
+    }
+
+    #[test]
+    fn trims_empty_rows_and_columns() {
+        let (_, items) = registries();
+        let cells = grid(&items, &["   ", " P/", " S "]);
+
+        assert_eq!(trimmed(&cells, 3), (2, grid(&items, &["P/", "S "])));
+        assert_eq!(trimmed(&[ItemType::NONE; 9], 3), (0, Vec::new()));
+    }
+
+    #[test]
+    fn matches_shaped_recipe_anywhere_in_grid() {
+        let (recipes, items) = registries();
+        let axe = items.by_name("wooden_axe");
+
+        let left = grid(&items, &["PP ", "P/ ", " / "]);
+        let right = grid(&items, &[" PP", " P/", "  /"]);
+        assert_eq!(result(&recipes, &left, 3), axe);
+        assert_eq!(result(&recipes, &right, 3), axe);
+        assert_eq!(result(&recipes, &grid(&items, &["PP", "P/"]), 2), None);
+    }
+
+    #[test]
+    fn matches_mirrored_shaped_recipe() {
+        let (recipes, items) = registries();
+
+        let mirrored = grid(&items, &["PP ", "/P ", "/  "]);
+        let upside_down = grid(&items, &[" / ", "P/ ", "PP "]);
+        assert_eq!(result(&recipes, &mirrored, 3), items.by_name("wooden_axe"));
+        assert_eq!(result(&recipes, &upside_down, 3), None);
+    }
+
+    #[test]
+    fn matches_shapeless_recipe_in_any_order() {
+        let (recipes, items) = registries();
+        let torch = items.by_name("torch");
+
+        let arrangements = [
+            grid(&items, &["SP/", "   ", "   "]),
+            grid(&items, &["/  ", " S ", "  P"]),
+            grid(&items, &["P/", "S "]),
+        ];
+        assert_eq!(result(&recipes, &arrangements[0], 3), torch);
+        assert_eq!(result(&recipes, &arrangements[1], 3), torch);
+        assert_eq!(result(&recipes, &arrangements[2], 2), torch);
+        assert_eq!(result(&recipes, &grid(&items, &["P/", "SS"]), 2), None);
+    }
+
+    #[test]
+    fn three_by_three_recipe_does_not_fit_small_grid() {
+        let (recipes, items) = registries();
+        let furnace = recipes
+            .iter()
+            .find(|recipe| Some(recipe.result) == items.by_name("furnace"))
+            .unwrap();
+
+        assert!(furnace.fits(3));
+        assert!(!furnace.fits(2));
+        let ring = grid(&items, &["SSS", "S S", "SSS"]);
+        assert_eq!(result(&recipes, &ring, 3), items.by_name("furnace"));
+    }
+}
@@ -0,0 +1,240 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::player::settings_menu::Settings;
+use crate::world::VoxelWorld;
+use crate::world::blocks::BlockRegistry;
+use crate::world::components::{CHUNK_SIZE, Chunk, ChunkPosition};
+use crate::world::systems::world_chunk_y_range;
+
+/// Face directions in the order used by the connectivity bitset:
+/// +X, -X, +Y, -Y, +Z, -Z.
+const FACE_OFFSETS: [IVec3; 6] = [
+    IVec3::new(1, 0, 0),
+    IVec3::new(-1, 0, 0),
+    IVec3::new(0, 1, 0),
+    IVec3::new(0, -1, 0),
+    IVec3::new(0, 0, 1),
+    IVec3::new(0, 0, -1),
+];
+
+fn opposite_face(face: usize) -> usize {
+    face ^ 1
+}
+
+/// Which pairs of a section's six faces can see each other through
+/// non-opaque voxels. Computed whenever the section is remeshed.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SectionConnectivity(pub u64);
+
+impl SectionConnectivity {
+    pub const ALL: Self = Self(u64::MAX);
+
+    pub fn connects(&self, from: usize, to: usize) -> bool {
+        self.0 & (1 << (from * 6 + to)) != 0
+    }
+
+    pub fn compute(chunk: &Chunk, registry: &BlockRegistry) -> Self {
+        let size = CHUNK_SIZE as i32;
+        let index = |pos: IVec3| {
+            (pos.x as usize * CHUNK_SIZE + pos.y as usize) * CHUNK_SIZE + pos.z as usize
+        };
+        let mut visited = vec![false; CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE];
+        let mut bits = 0u64;
+        let mut queue = VecDeque::new();
+
+        for x in 0..size {
+            for y in 0..size {
+                for z in 0..size {
+                    let start = IVec3::new(x, y, z);
+                    if visited[index(start)] || registry.is_opaque(chunk.get_voxel(start)) {
+                        continue;
+                    }
+
+                    // Flood the open region and note which faces it touches
+                    let mut touched = 0u8;
+                    visited[index(start)] = true;
+                    queue.push_back(start);
+                    while let Some(pos) = queue.pop_front() {
+                        touched |= touched_faces(pos);
+                        for offset in FACE_OFFSETS {
+                            let next = pos + offset;
+                            if next.min_element() < 0 || next.max_element() >= size {
+                                continue;
+                            }
+                            if visited[index(next)] || registry.is_opaque(chunk.get_voxel(next)) {
+                                continue;
+                            }
+                            visited[index(next)] = true;
+                            queue.push_back(next);
+                        }
+                    }
+
+                    for from in 0..6 {
+                        if touched & (1 << from) == 0 {
+                            continue;
+                        }
+                        for to in 0..6 {
+                            if touched & (1 << to) != 0 {
+                                bits |= 1 << (from * 6 + to);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Self(bits)
+    }
+}
+
+fn touched_faces(pos: IVec3) -> u8 {
+    let max = CHUNK_SIZE as i32 - 1;
+    let mut faces = 0u8;
+    if pos.x == max {
+        faces |= 1 << 0;
+    }
+    if pos.x == 0 {
+        faces |= 1 << 1;
+    }
+    if pos.y == max {
+        faces |= 1 << 2;
+    }
+    if pos.y == 0 {
+        faces |= 1 << 3;
+    }
+    if pos.z == max {
+        faces |= 1 << 4;
+    }
+    if pos.z == 0 {
+        faces |= 1 << 5;
+    }
+    faces
+}
+
+/// Walks outward from the camera's section, only passing through a section
+/// between faces that are connected inside it and never doubling back, then
+/// hides every section the walk could not reach.
+pub fn cull_hidden_sections(
+    settings: Res<Settings>,
+    camera_query: Query<&GlobalTransform, With<Camera3d>>,
+    mut chunk_query: Query<
+        (
+            &ChunkPosition,
+            Option<&SectionConnectivity>,
+            &mut Visibility,
+        ),
+        With<Chunk>,
+    >,
+) {
+    let Ok(camera_transform) = camera_query.single() else {
+        return;
+    };
+
+    let camera_chunk = VoxelWorld::world_to_chunk_pos(camera_transform.translation());
+    let view_distance = settings.render_distance;
+    let (min_chunk_y, max_chunk_y) = world_chunk_y_range();
+
+    let connectivity: HashMap<IVec3, SectionConnectivity> = chunk_query
+        .iter()
+        .map(|(pos, conn, _)| (pos.0, conn.copied().unwrap_or(SectionConnectivity::ALL)))
+        .collect();
+
+    // Sections outside the loaded volume (open sky above the world or the
+    // camera below it) are treated as fully open so the walk can cross them.
+    let in_bounds = |pos: IVec3| {
+        (pos.x - camera_chunk.x).abs() <= view_distance
+            && (pos.z - camera_chunk.z).abs() <= view_distance
+            && pos.y >= min_chunk_y.min(camera_chunk.y)
+            && pos.y <= (max_chunk_y + 1).max(camera_chunk.y)
+    };
+
+    let mut visible: HashSet<IVec3> = HashSet::new();
+    let mut queue: VecDeque<(IVec3, Option<usize>, u8)> = VecDeque::new();
+    visible.insert(camera_chunk);
+    queue.push_back((camera_chunk, None, 0));
+
+    while let Some((pos, entered_from, directions)) = queue.pop_front() {
+        let section = connectivity
+            .get(&pos)
+            .copied()
+            .unwrap_or(SectionConnectivity::ALL);
+
+        for (face, offset) in FACE_OFFSETS.iter().enumerate() {
+            // Never step back toward the camera
+            if directions & (1 << opposite_face(face)) != 0 {
+                continue;
+            }
+            if let Some(from) = entered_from
+                && !section.connects(from, face)
+            {
+                continue;
+            }
+            let next = pos + *offset;
+            if !in_bounds(next) || !visible.insert(next) {
+                continue;
+            }
+            queue.push_back((next, Some(opposite_face(face)), directions | (1 << face)));
+        }
+    }
+
+    for (pos, _, mut visibility) in chunk_query.iter_mut() {
+        let target = if visible.contains(&pos.0) {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+        visibility.set_if_neq(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::camera::visibility::VisibilityPlugin;
+    use bevy::transform::TransformPlugin;
+
+    fn spawn_section(app: &mut App, pos: IVec3, connectivity: SectionConnectivity) -> Entity {
+        app.world_mut()
+            .spawn((
+                Chunk::empty(),
+                ChunkPosition(pos),
+                connectivity,
+                Transform::default(),
+                Visibility::Visible,
+            ))
+            .with_child((Transform::default(), Visibility::Inherited))
+            .id()
+    }
+
+    fn child_visible(app: &App, section: Entity) -> bool {
+        let world = app.world();
+        let child = world.get::<Children>(section).unwrap()[0];
+        world.get::<InheritedVisibility>(child).unwrap().get()
+    }
+
+    #[test]
+    fn hides_mesh_children_of_sections_behind_a_sealed_one() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            VisibilityPlugin,
+        ))
+        .init_asset::<Mesh>()
+        .init_resource::<Settings>()
+        .add_systems(Update, cull_hidden_sections);
+        app.world_mut()
+            .spawn((Camera3d::default(), Transform::default()));
+
+        let camera_section = spawn_section(&mut app, IVec3::ZERO, SectionConnectivity::ALL);
+        let sealed = spawn_section(&mut app, IVec3::X, SectionConnectivity(0));
+        let behind = spawn_section(&mut app, IVec3::X * 2, SectionConnectivity::ALL);
+        app.update();
+
+        assert!(child_visible(&app, camera_section));
+        assert!(child_visible(&app, sealed));
+        assert!(!child_visible(&app, behind));
+    }
+}
@@ -0,0 +1,243 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+use std::collections::HashSet;
+
+use crate::mob::components::Mob;
+use crate::player::components::{DamageCause, Health};
+use crate::player::resources::SoundAssets;
+use crate::player::settings_menu::Settings;
+use crate::player::systems::{KNOCKBACK_SECONDS, play_sound, spawn_drop_item};
+use crate::world::blocks::{BlockRegistry, FACE_SIDE};
+use crate::world::components::{BlockChanged, Chunk, InGameEntity, VoxelType};
+use crate::world::interact::break_block;
+use crate::world::loot::LootContext;
+use crate::world::resources::VoxelWorld;
+use crate::world::systems::{BlockAssets, RemeshBatch};
+
+/// Seconds between lighting an explosive block and its blast.
+pub const LIT_FUSE_SECONDS: f32 = 4.0;
+/// Fuse range for explosives set off by another blast, so chains ripple
+/// rather than going off all at once.
+const CHAINED_FUSE_SECONDS: std::ops::Range<f32> = 0.5..1.5;
+/// Rays are cast toward every cell on the surface of a cube this many cells
+/// across.
+const RAY_GRID: i32 = 16;
+const RAY_STEP: f32 = 0.3;
+/// Strength a ray loses per step through air.
+const AIR_FALLOFF: f32 = 0.225;
+/// Entities within `power * DAMAGE_RADIUS_PER_POWER` are hurt.
+const DAMAGE_RADIUS_PER_POWER: f32 = 2.0;
+const KNOCKBACK_SPEED: f32 = 12.0;
+
+/// A blast to apply on the next update. Removes blocks along rays from the
+/// center, then hurts and pushes nearby `Health` entities.
+#[derive(Message, Clone, Copy, Debug)]
+pub struct Explosion {
+    pub center: Vec3,
+    pub power: f32,
+    /// What set the blast off; it isn't hurt by its own blast.
+    pub source: Option<Entity>,
+    /// Chance each destroyed block drops its items.
+    pub drop_yield: f64,
+}
+
+impl Explosion {
+    /// A blast whose drop yield shrinks as it grows, the way TNT drops
+    /// a quarter of what it destroys.
+    pub fn new(center: Vec3, power: f32, source: Option<Entity>) -> Self {
+        Self {
+            center,
+            power,
+            source,
+            drop_yield: (1.0 / power.max(1.0)) as f64,
+        }
+    }
+}
+
+/// A lit explosive block, waiting out its fuse.
+#[derive(Component)]
+pub struct PrimedExplosive {
+    pub fuse: f32,
+    pub power: f32,
+}
+
+pub fn spawn_primed_explosive(
+    commands: &mut Commands,
+    block_assets: &BlockAssets,
+    registry: &BlockRegistry,
+    voxel: VoxelType,
+    pos: IVec3,
+    fuse: f32,
+) {
+    let block = registry.get(voxel);
+    let Some(power) = block.explosion_power else {
+        return;
+    };
+    let material = block_assets.block_materials[block.face_materials[FACE_SIDE]].clone();
+    commands.spawn((
+        PrimedExplosive { fuse, power },
+        Mesh3d(block_assets.mesh.clone()),
+        MeshMaterial3d(material),
+        Transform::from_translation(pos.as_vec3() + Vec3::splat(0.5)),
+        RigidBody::Dynamic,
+        Collider::cuboid(0.49, 0.49, 0.49),
+        Velocity::linear(Vec3::Y * 2.0),
+        LockedAxes::ROTATION_LOCKED,
+        InGameEntity,
+    ));
+}
+
+pub fn tick_primed_explosives(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut primed_query: Query<(Entity, &Transform, &mut PrimedExplosive)>,
+    mut explosions: MessageWriter<Explosion>,
+) {
+    for (entity, transform, mut primed) in primed_query.iter_mut() {
+        primed.fuse -= time.delta_secs();
+        if primed.fuse <= 0.0 {
+            commands.entity(entity).despawn();
+            explosions.write(Explosion::new(
+                transform.translation,
+                primed.power,
+                Some(entity),
+            ));
+        }
+    }
+}
+
+/// Unit directions from the center of a cube to each cell on its surface.
+fn ray_directions() -> impl Iterator<Item = Vec3> {
+    let max = RAY_GRID - 1;
+    (0..RAY_GRID).flat_map(move |x| {
+        (0..RAY_GRID).flat_map(move |y| {
+            (0..RAY_GRID).filter_map(move |z| {
+                let on_surface = [x, y, z].iter().any(|&c| c == 0 || c == max);
+                on_surface.then(|| {
+                    (Vec3::new(x as f32, y as f32, z as f32) / max as f32 * 2.0 - 1.0).normalize()
+                })
+            })
+        })
+    })
+}
+
+/// Entities a blast can hurt, and push if they move.
+type BlastTargetQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static mut Health,
+        Option<&'static mut Velocity>,
+        Option<&'static mut Mob>,
+    ),
+>;
+
+#[allow(clippy::too_many_arguments)]
+pub fn apply_explosions(
+    mut commands: Commands,
+    mut explosions: MessageReader<Explosion>,
+    voxel_world: Res<VoxelWorld>,
+    mut chunk_query: Query<&mut Chunk>,
+    registry: Res<BlockRegistry>,
+    block_assets: Res<BlockAssets>,
+    mut health_query: BlastTargetQuery,
+    mut block_changes: MessageWriter<BlockChanged>,
+    sound_assets: Res<SoundAssets>,
+    settings: Res<Settings>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for explosion in explosions.read() {
+        let mut destroyed = HashSet::new();
+        for direction in ray_directions() {
+            let mut strength = explosion.power * rng.gen_range(0.7..1.3);
+            let mut point = explosion.center;
+            while strength > 0.0 {
+                let pos = point.floor().as_ivec3();
+                let chunk_pos = VoxelWorld::world_to_chunk_pos(point);
+                let Some(chunk) = voxel_world
+                    .chunks
+                    .get(&chunk_pos)
+                    .and_then(|entity| chunk_query.get(*entity).ok())
+                else {
+                    break;
+                };
+                let voxel = chunk.get_voxel(VoxelWorld::voxel_to_local_pos(pos));
+                if voxel != VoxelType::AIR {
+                    strength -= (registry.get(voxel).blast_resistance() + 0.3) * RAY_STEP;
+                    if strength > 0.0 {
+                        destroyed.insert(pos);
+                    }
+                }
+                strength -= AIR_FALLOFF;
+                point += direction * RAY_STEP;
+            }
+        }
+
+        let mut batch = RemeshBatch::default();
+        for &pos in &destroyed {
+            let Some((voxel, state)) = break_block(
+                &mut commands,
+                &voxel_world,
+                &mut chunk_query,
+                &registry,
+                &block_assets,
+                &mut block_changes,
+                &mut batch,
+                pos,
+            ) else {
+                continue;
+            };
+            // The other half of a door may already be gone with its partner
+            if voxel == VoxelType::AIR {
+                continue;
+            }
+
+            let block = registry.get(voxel);
+            if block.explosion_power.is_some() {
+                let fuse = rng.gen_range(CHAINED_FUSE_SECONDS);
+                spawn_primed_explosive(&mut commands, &block_assets, &registry, voxel, pos, fuse);
+                continue;
+            }
+            let context = LootContext {
+                yield_scale: explosion.drop_yield,
+                ..default()
+            };
+            for item in block.drops_for(state).roll(&context, &mut rng) {
+                spawn_drop_item(&mut commands, &block_assets, pos, item);
+            }
+        }
+        batch.flush(&mut commands, &voxel_world);
+
+        let radius = explosion.power * DAMAGE_RADIUS_PER_POWER;
+        for (entity, transform, mut health, velocity, mob) in health_query.iter_mut() {
+            if explosion.source == Some(entity) {
+                continue;
+            }
+            let offset = transform.translation - explosion.center;
+            let distance = offset.length();
+            if distance >= radius {
+                continue;
+            }
+
+            let impact = 1.0 - distance / radius;
+            let damage = ((impact * impact + impact) / 2.0 * 7.0 * explosion.power + 1.0) as i32;
+            health.damage(damage, DamageCause::Explosion);
+            if let Some(mut velocity) = velocity {
+                velocity.linvel += offset.normalize_or(Vec3::Y) * impact * KNOCKBACK_SPEED;
+            }
+            // Mobs steer over their velocity unless they're being knocked back
+            if let Some(mut mob) = mob {
+                mob.knockback_timer = KNOCKBACK_SECONDS;
+            }
+        }
+
+        if !sound_assets.explode.is_empty() {
+            let sound = sound_assets.explode[rng.gen_range(0..sound_assets.explode.len())].clone();
+            play_sound(&mut commands, sound, settings.master_volume);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! Gravity blocks like sand and gravel. One that loses its support turns
+//! into a physics body, together with everything stacked on it, and becomes
+//! a voxel again where it lands, or drops as an item if it can't settle
+//! there.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::player::systems::spawn_drop_item;
+use crate::world::blocks::{BlockModel, BlockRegistry, FACE_SIDE};
+use crate::world::components::{BlockChanged, CHUNK_SIZE, Chunk, InGameEntity, VoxelType};
+use crate::world::loot::LootContext;
+use crate::world::resources::VoxelWorld;
+use crate::world::systems::{BlockAssets, block_at, set_block_at, world_chunk_y_range};
+
+/// Seconds a falling block must have existed before it can settle, so it
+/// isn't placed back the frame it detaches.
+const MIN_FALL_TIME: f32 = 0.1;
+/// Vertical speed below which a falling block counts as landed.
+const LANDED_SPEED: f32 = 0.01;
+
+/// A gravity block that lost its support, simulated as a rigid body until
+/// it lands.
+#[derive(Component)]
+pub struct FallingBlock {
+    pub voxel: VoxelType,
+    pub state: u8,
+    pub age: f32,
+}
+
+/// Whether a gravity block resting on `below` stays put. Unloaded chunks
+/// count as support so blocks don't drop out of the world at its edge.
+fn supports(registry: &BlockRegistry, below: Option<(VoxelType, u8)>) -> bool {
+    below.is_none_or(|(voxel, _)| registry.get(voxel).collision)
+}
+
+/// Detaches unsupported gravity blocks at and above every changed position.
+pub fn start_falling_blocks(
+    mut commands: Commands,
+    mut block_changes: MessageReader<BlockChanged>,
+    voxel_world: Res<VoxelWorld>,
+    mut chunk_query: Query<&mut Chunk>,
+    registry: Res<BlockRegistry>,
+    block_assets: Res<BlockAssets>,
+) {
+    for change in block_changes.read() {
+        let mut pos = change.pos;
+        let is_gravity_block = |block: Option<(VoxelType, u8)>| {
+            block.is_some_and(|(voxel, _)| registry.get(voxel).gravity)
+        };
+        if !is_gravity_block(block_at(&voxel_world, &chunk_query, pos)) {
+            pos += IVec3::Y;
+        }
+
+        // Removing the bottom of a column drops everything stacked on it
+        while let Some((voxel, state)) = block_at(&voxel_world, &chunk_query, pos)
+            && registry.get(voxel).gravity
+            && !supports(
+                &registry,
+                block_at(&voxel_world, &chunk_query, pos - IVec3::Y),
+            )
+        {
+            set_block_at(
+                &mut commands,
+                &voxel_world,
+                &mut chunk_query,
+                pos,
+                VoxelType::AIR,
+                0,
+            );
+
+            let block = registry.get(voxel);
+            let material = block_assets.block_materials[block.face_materials[FACE_SIDE]].clone();
+            commands.spawn((
+                FallingBlock {
+                    voxel,
+                    state,
+                    age: 0.0,
+                },
+                Mesh3d(block_assets.mesh.clone()),
+                MeshMaterial3d(material),
+                Transform::from_translation(pos.as_vec3() + Vec3::splat(0.5)),
+                RigidBody::Dynamic,
+                Collider::cuboid(0.49, 0.49, 0.49),
+                Velocity::default(),
+                Ccd::enabled(),
+                LockedAxes::ROTATION_LOCKED,
+                InGameEntity,
+            ));
+            pos += IVec3::Y;
+        }
+    }
+}
+
+/// Turns falling blocks that came to rest back into voxels, or into drops
+/// when they landed on something that isn't a full block. Blocks landing
+/// together settle from the bottom up, so a column that stops in one frame
+/// stacks back up instead of resting on air.
+#[allow(clippy::too_many_arguments)]
+pub fn land_falling_blocks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut falling_query: Query<(Entity, &Transform, &Velocity, &mut FallingBlock)>,
+    voxel_world: Res<VoxelWorld>,
+    mut chunk_query: Query<&mut Chunk>,
+    registry: Res<BlockRegistry>,
+    block_assets: Res<BlockAssets>,
+    mut block_changes: MessageWriter<BlockChanged>,
+) {
+    let (min_chunk_y, _) = world_chunk_y_range();
+    let world_bottom = (min_chunk_y * CHUNK_SIZE as i32) as f32;
+    let mut rng = rand::thread_rng();
+
+    let mut landed = Vec::new();
+    for (entity, transform, velocity, mut falling) in falling_query.iter_mut() {
+        falling.age += time.delta_secs();
+        if transform.translation.y < world_bottom {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if falling.age < MIN_FALL_TIME || velocity.linvel.y.abs() > LANDED_SPEED {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        let pos = transform.translation.floor().as_ivec3();
+        landed.push((pos, falling.voxel, falling.state));
+    }
+    landed.sort_by_key(|(pos, _, _)| pos.y);
+
+    for (pos, falling_voxel, falling_state) in landed {
+        let cell = block_at(&voxel_world, &chunk_query, pos);
+        let below = block_at(&voxel_world, &chunk_query, pos - IVec3::Y);
+        let fits = cell.is_some_and(|(voxel, _)| !registry.get(voxel).collision)
+            && below.is_some_and(|(voxel, _)| {
+                let block = registry.get(voxel);
+                block.model == BlockModel::Cube && block.collision
+            });
+
+        if fits
+            && set_block_at(
+                &mut commands,
+                &voxel_world,
+                &mut chunk_query,
+                pos,
+                falling_voxel,
+                falling_state,
+            )
+        {
+            block_changes.write(BlockChanged { pos });
+        } else {
+            let drops = registry.get(falling_voxel).drops_for(falling_state);
+            for item in drops.roll(&LootContext::default(), &mut rng) {
+                spawn_drop_item(&mut commands, &block_assets, pos, item);
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Tilled soil and the crops planted on it. Random ticks pick a few cells of
+//! every loaded chunk at a time, so crops grow and soil dries out here and
+//! there over minutes rather than all at once.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+use crate::mob::components::Mob;
+use crate::player::components::Player;
+use crate::player::systems::spawn_drop_item;
+use crate::world::block_state::{age, with_age};
+use crate::world::blocks::{BlockRegistry, Soil};
+use crate::world::components::{BlockChanged, CHUNK_SIZE, Chunk, VoxelType};
+use crate::world::loot::LootContext;
+use crate::world::piston::feet_cell;
+use crate::world::resources::VoxelWorld;
+use crate::world::systems::{BlockAssets, block_at, set_block_at};
+
+/// Seconds between random ticks.
+pub const RANDOM_TICK_SECONDS: f32 = 0.05;
+/// Cells of each chunk a random tick picks.
+const RANDOM_TICKS_PER_CHUNK: usize = 3;
+/// Chance a crop picked by a random tick grows a stage, on moist and on dry
+/// soil.
+const MOIST_GROWTH_CHANCE: f64 = 1.0 / 3.0;
+const DRY_GROWTH_CHANCE: f64 = 1.0 / 8.0;
+/// Blocks away, horizontally, that water keeps farmland moist from.
+const WATER_REACH: i32 = 4;
+/// Falling speed past which landing on farmland tramples it. Landing from a
+/// jump or a one block drop is faster than this; walking is not.
+const TRAMPLE_SPEED: f32 = 6.0;
+
+/// Players and mobs, with how fast they're moving.
+type TramplerQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Transform, &'static Collider, &'static Velocity),
+    Or<(With<Player>, With<Mob>)>,
+>;
+
+fn water_near(voxel_world: &VoxelWorld, chunk_query: &Query<&mut Chunk>, pos: IVec3) -> bool {
+    (-WATER_REACH..=WATER_REACH).any(|x| {
+        (-WATER_REACH..=WATER_REACH).any(|z| {
+            (0..=1).any(|y| {
+                block_at(voxel_world, chunk_query, pos + IVec3::new(x, y, z))
+                    .is_some_and(|(voxel, _)| voxel == VoxelType::WATER)
+            })
+        })
+    })
+}
+
+/// Turns the block at `pos` into what a hoe makes of it, if it can be tilled
+/// and nothing covers it. Returns the tilled block.
+pub fn till(
+    commands: &mut Commands,
+    voxel_world: &VoxelWorld,
+    chunk_query: &mut Query<&mut Chunk>,
+    registry: &BlockRegistry,
+    block_changes: &mut MessageWriter<BlockChanged>,
+    pos: IVec3,
+) -> Option<VoxelType> {
+    let (voxel, _) = block_at(voxel_world, chunk_query, pos)?;
+    let tilled = registry.get(voxel).tills_to?;
+    let covered = block_at(voxel_world, chunk_query, pos + IVec3::Y)
+        .is_none_or(|(above, _)| above != VoxelType::AIR);
+    if covered || !set_block_at(commands, voxel_world, chunk_query, pos, tilled, 0) {
+        return None;
+    }
+    block_changes.write(BlockChanged { pos });
+    Some(tilled)
+}
+
+/// Grows crops, and moistens, dries out or reverts farmland, at a few random
+/// cells of every loaded chunk.
+pub fn random_tick_farmland(
+    mut commands: Commands,
+    voxel_world: Res<VoxelWorld>,
+    mut chunk_query: Query<&mut Chunk>,
+    registry: Res<BlockRegistry>,
+    mut block_changes: MessageWriter<BlockChanged>,
+) {
+    let mut rng = rand::thread_rng();
+    let size = CHUNK_SIZE as i32;
+    let mut picked = Vec::new();
+    for chunk_pos in voxel_world.chunks.keys() {
+        for _ in 0..RANDOM_TICKS_PER_CHUNK {
+            let local = IVec3::new(
+                rng.gen_range(0..size),
+                rng.gen_range(0..size),
+                rng.gen_range(0..size),
+            );
+            picked.push(*chunk_pos * size + local);
+        }
+    }
+
+    for pos in picked {
+        let Some((voxel, state)) = block_at(&voxel_world, &chunk_query, pos) else {
+            continue;
+        };
+        let block = registry.get(voxel);
+
+        if let Some(crop) = &block.crop {
+            let chance = match block_at(&voxel_world, &chunk_query, pos - IVec3::Y)
+                .and_then(|(below, _)| registry.get(below).soil)
+            {
+                Some(Soil::Moist) => MOIST_GROWTH_CHANCE,
+                Some(Soil::Dry) => DRY_GROWTH_CHANCE,
+                None => continue,
+            };
+            if age(state) < crop.last_stage() && rng.gen_bool(chance) {
+                let grown = with_age(state, age(state) + 1);
+                set_block_at(
+                    &mut commands,
+                    &voxel_world,
+                    &mut chunk_query,
+                    pos,
+                    voxel,
+                    grown,
+                );
+            }
+            continue;
+        }
+
+        let Some(soil) = block.soil else {
+            continue;
+        };
+        let wet = water_near(&voxel_world, &chunk_query, pos);
+        let planted = block_at(&voxel_world, &chunk_query, pos + IVec3::Y)
+            .is_some_and(|(above, _)| registry.get(above).crop.is_some());
+        let replacement = match (soil, wet) {
+            (Soil::Dry, true) | (Soil::Moist, false) => block.variant,
+            (Soil::Dry, false) if !planted => Some(VoxelType::DIRT),
+            _ => None,
+        };
+        if let Some(replacement) = replacement
+            && set_block_at(
+                &mut commands,
+                &voxel_world,
+                &mut chunk_query,
+                pos,
+                replacement,
+                0,
+            )
+        {
+            block_changes.write(BlockChanged { pos });
+        }
+    }
+}
+
+/// Turns farmland back into dirt under players and mobs that land on it
+/// hard.
+pub fn trample_farmland(
+    mut commands: Commands,
+    voxel_world: Res<VoxelWorld>,
+    mut chunk_query: Query<&mut Chunk>,
+    registry: Res<BlockRegistry>,
+    tramplers: TramplerQuery,
+    mut block_changes: MessageWriter<BlockChanged>,
+) {
+    for (transform, collider, velocity) in tramplers.iter() {
+        if velocity.linvel.y > -TRAMPLE_SPEED {
+            continue;
+        }
+        // Checked just before landing, since landing stops the fall
+        let feet = feet_cell(transform, collider);
+        for pos in [feet, feet - IVec3::Y] {
+            let is_soil = block_at(&voxel_world, &chunk_query, pos)
+                .is_some_and(|(voxel, _)| registry.get(voxel).soil.is_some());
+            if is_soil
+                && set_block_at(
+                    &mut commands,
+                    &voxel_world,
+                    &mut chunk_query,
+                    pos,
+                    VoxelType::DIRT,
+                    0,
+                )
+            {
+                block_changes.write(BlockChanged { pos });
+                break;
+            }
+        }
+    }
+}
+
+/// Pops crops off blocks that stopped being soil, dropping what breaking
+/// them would.
+pub fn uproot_crops(
+    mut commands: Commands,
+    mut block_changes: MessageReader<BlockChanged>,
+    voxel_world: Res<VoxelWorld>,
+    mut chunk_query: Query<&mut Chunk>,
+    registry: Res<BlockRegistry>,
+    block_assets: Res<BlockAssets>,
+) {
+    let mut rng = rand::thread_rng();
+    for change in block_changes.read() {
+        let pos = change.pos + IVec3::Y;
+        let Some((voxel, state)) = block_at(&voxel_world, &chunk_query, pos) else {
+            continue;
+        };
+        let block = registry.get(voxel);
+        let supported = block_at(&voxel_world, &chunk_query, change.pos)
+            .is_some_and(|(below, _)| registry.get(below).soil.is_some());
+        if block.crop.is_none() || supported {
+            continue;
+        }
+
+        set_block_at(
+            &mut commands,
+            &voxel_world,
+            &mut chunk_query,
+            pos,
+            VoxelType::AIR,
+            0,
+        );
+        for item in block
+            .drops_for(state)
+            .roll(&LootContext::default(), &mut rng)
+        {
+            spawn_drop_item(&mut commands, &block_assets, pos, item);
+        }
+    }
+}
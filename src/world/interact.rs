@@ -0,0 +1,170 @@
+//! Blocks that do something when right-clicked, instead of being built on.
+
+use bevy::prelude::*;
+
+use crate::world::block_entity::{ensure_block_entity, spill_contents};
+use crate::world::block_state::{UPPER, is_open, with_open};
+use crate::world::blocks::{BlockDef, BlockEntityKind, BlockModel, BlockRegistry, UseBehavior};
+use crate::world::components::{BlockChanged, Chunk, VoxelType};
+use crate::world::piston::break_piston_part;
+use crate::world::resources::VoxelWorld;
+use crate::world::systems::{BlockAssets, RemeshBatch, block_at, set_block_at};
+
+/// What using a block did, for the caller to follow up on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UseOutcome {
+    /// A door, trapdoor or gate swung open or shut.
+    Swung { open: bool },
+    /// A container at the used position is ready to be shown.
+    OpenedContainer(BlockEntityKind),
+    /// A crafting table was used.
+    OpenedCraftingTable,
+}
+
+/// Where the other half of a two-block-tall block at `pos` would be.
+pub fn other_half(block: &BlockDef, pos: IVec3, state: u8) -> Option<IVec3> {
+    if block.model != BlockModel::Door {
+        return None;
+    }
+    Some(if state & UPPER != 0 {
+        pos - IVec3::Y
+    } else {
+        pos + IVec3::Y
+    })
+}
+
+/// The other half of the door at `pos`, if it's still there.
+fn find_other_half(
+    voxel_world: &VoxelWorld,
+    chunk_query: &Query<&mut Chunk>,
+    block: &BlockDef,
+    pos: IVec3,
+    state: u8,
+) -> Option<(IVec3, VoxelType, u8)> {
+    let other_pos = other_half(block, pos, state)?;
+    let (voxel, other_state) = block_at(voxel_world, chunk_query, other_pos)?;
+    (Some(voxel) == block.variant).then_some((other_pos, voxel, other_state))
+}
+
+/// Runs the `use` behavior of the block at `pos`. `None` when it has no
+/// behavior and the click should place a block instead.
+pub fn use_block(
+    commands: &mut Commands,
+    voxel_world: &VoxelWorld,
+    chunk_query: &mut Query<&mut Chunk>,
+    registry: &BlockRegistry,
+    block_changes: &mut MessageWriter<BlockChanged>,
+    pos: IVec3,
+) -> Option<UseOutcome> {
+    let (voxel, state) = block_at(voxel_world, chunk_query, pos)?;
+    let block = registry.get(voxel);
+    match block.use_behavior? {
+        UseBehavior::Open => {
+            let open = !is_open(state);
+            let other = find_other_half(voxel_world, chunk_query, block, pos, state);
+            set_block_at(
+                commands,
+                voxel_world,
+                chunk_query,
+                pos,
+                voxel,
+                with_open(state, open),
+            );
+            block_changes.write(BlockChanged { pos });
+            if let Some((other_pos, other_voxel, other_state)) = other {
+                set_block_at(
+                    commands,
+                    voxel_world,
+                    chunk_query,
+                    other_pos,
+                    other_voxel,
+                    with_open(other_state, open),
+                );
+                block_changes.write(BlockChanged { pos: other_pos });
+            }
+            Some(UseOutcome::Swung { open })
+        }
+        UseBehavior::Container => {
+            let kind = block.block_entity?;
+            ensure_block_entity(voxel_world, chunk_query, registry, pos)
+                .then_some(UseOutcome::OpenedContainer(kind))
+        }
+        UseBehavior::Crafting => Some(UseOutcome::OpenedCraftingTable),
+    }
+}
+
+/// Removes what's left of a door after one half at `pos` was broken. The
+/// broken half drops the item, so this one drops nothing.
+pub fn break_other_half(
+    commands: &mut Commands,
+    voxel_world: &VoxelWorld,
+    chunk_query: &mut Query<&mut Chunk>,
+    block_changes: &mut MessageWriter<BlockChanged>,
+    block: &BlockDef,
+    pos: IVec3,
+    state: u8,
+) {
+    if let Some((other_pos, _, _)) = find_other_half(voxel_world, chunk_query, block, pos, state) {
+        set_block_at(
+            commands,
+            voxel_world,
+            chunk_query,
+            other_pos,
+            VoxelType::AIR,
+            0,
+        );
+        block_changes.write(BlockChanged { pos: other_pos });
+    }
+}
+
+/// Removes the block at `pos`, as mining or a blast does: spills whatever it
+/// held and takes the rest of a door or piston with it. Returns the voxel and
+/// state it had, for the caller to roll its drops.
+#[allow(clippy::too_many_arguments)]
+pub fn break_block(
+    commands: &mut Commands,
+    voxel_world: &VoxelWorld,
+    chunk_query: &mut Query<&mut Chunk>,
+    registry: &BlockRegistry,
+    block_assets: &BlockAssets,
+    block_changes: &mut MessageWriter<BlockChanged>,
+    batch: &mut RemeshBatch,
+    pos: IVec3,
+) -> Option<(VoxelType, u8)> {
+    let chunk_pos = VoxelWorld::world_to_chunk_pos(pos.as_vec3());
+    let mut chunk = voxel_world
+        .chunks
+        .get(&chunk_pos)
+        .and_then(|entity| chunk_query.get_mut(*entity).ok())?;
+    let local_pos = VoxelWorld::voxel_to_local_pos(pos);
+    let voxel = chunk.get_voxel(local_pos);
+    let state = chunk.get_state(local_pos);
+    if let Some(contents) = chunk.block_entities.remove(&local_pos) {
+        spill_contents(commands, block_assets, pos, &contents);
+    }
+    chunk.set_voxel(local_pos, VoxelType::AIR);
+    batch.mark(pos);
+    block_changes.write(BlockChanged { pos });
+
+    let block = registry.get(voxel);
+    break_other_half(
+        commands,
+        voxel_world,
+        chunk_query,
+        block_changes,
+        block,
+        pos,
+        state,
+    );
+    break_piston_part(
+        commands,
+        voxel_world,
+        chunk_query,
+        registry,
+        block_changes,
+        voxel,
+        pos,
+        state,
+    );
+    Some((voxel, state))
+}
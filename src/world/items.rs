@@ -0,0 +1,317 @@
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+
+use crate::world::blocks::BlockRegistry;
+use crate::world::components::{ItemType, VoxelType};
+
+/// Item definitions, relative to the assets directory.
+pub const ITEMS_FILE: &str = "data/items.json";
+
+fn default_max_stack() -> u32 {
+    64
+}
+
+/// Which blocks an item mines faster, and what it does when used on a
+/// block besides placing one.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolKind {
+    Pickaxe,
+    Axe,
+    Shovel,
+    Sword,
+    Shears,
+    /// Also tills the top of a block into its `tills_to` block. See
+    /// `farming`.
+    Hoe,
+}
+
+impl ToolKind {
+    /// Damage a wooden one deals, and seconds it takes to swing again.
+    fn base_attack(self) -> Attack {
+        let (damage, cooldown) = match self {
+            ToolKind::Sword => (4, 0.625),
+            ToolKind::Axe => (6, 1.0),
+            ToolKind::Pickaxe => (2, 0.85),
+            ToolKind::Shovel => (2, 1.0),
+            ToolKind::Hoe | ToolKind::Shears => (1, 0.5),
+        };
+        Attack { damage, cooldown }
+    }
+}
+
+/// How hard an item hits mobs, and how long until it can hit again.
+#[derive(Clone, Copy, Debug)]
+pub struct Attack {
+    pub damage: i32,
+    pub cooldown: f32,
+}
+
+/// Attack of an empty hand, or any item that isn't a tool.
+pub const HAND_ATTACK: Attack = Attack {
+    damage: 1,
+    cooldown: 0.25,
+};
+
+/// What a tool is made of, setting how fast it mines, how many uses it
+/// lasts and which blocks it can harvest.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolTier {
+    Wood,
+    Stone,
+    Iron,
+    Gold,
+    Diamond,
+}
+
+impl ToolTier {
+    /// Compared against a block's `harvest_tier`. Gold mines fastest but
+    /// harvests no more than wood.
+    pub fn harvest_level(self) -> u8 {
+        match self {
+            ToolTier::Wood | ToolTier::Gold => 0,
+            ToolTier::Stone => 1,
+            ToolTier::Iron => 2,
+            ToolTier::Diamond => 3,
+        }
+    }
+
+    /// How many times faster than a hand it mines the blocks it's for.
+    pub fn mining_speed(self) -> f32 {
+        match self {
+            ToolTier::Wood => 2.0,
+            ToolTier::Stone => 4.0,
+            ToolTier::Iron => 6.0,
+            ToolTier::Gold => 12.0,
+            ToolTier::Diamond => 8.0,
+        }
+    }
+
+    /// Damage added to the tool's attack.
+    pub fn attack_bonus(self) -> i32 {
+        match self {
+            ToolTier::Wood | ToolTier::Gold => 0,
+            ToolTier::Stone => 1,
+            ToolTier::Iron => 2,
+            ToolTier::Diamond => 3,
+        }
+    }
+
+    /// Uses before the tool breaks.
+    pub fn durability(self) -> u32 {
+        match self {
+            ToolTier::Wood => 59,
+            ToolTier::Stone => 131,
+            ToolTier::Iron => 250,
+            ToolTier::Gold => 32,
+            ToolTier::Diamond => 1561,
+        }
+    }
+}
+
+/// What eating an item restores.
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct Food {
+    pub hunger: i32,
+    pub saturation: f32,
+}
+
+#[derive(Deserialize)]
+struct ItemDefFile {
+    id: u16,
+    name: String,
+    display_name: String,
+    icon: String,
+    #[serde(default = "default_max_stack")]
+    max_stack: u32,
+    block: Option<String>,
+    tool: Option<ToolKind>,
+    tier: Option<ToolTier>,
+    #[serde(default)]
+    silk_touch: bool,
+    food: Option<Food>,
+}
+
+#[derive(Deserialize)]
+struct ItemsFile {
+    items: Vec<ItemDefFile>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ItemDef {
+    pub display_name: String,
+    /// Texture shown in inventory slots and on dropped items.
+    pub icon: String,
+    pub max_stack: u32,
+    /// Block placed when the item is used on a block face.
+    pub block: Option<VoxelType>,
+    /// A tool and what it's made of; tools don't stack.
+    pub tool: Option<(ToolKind, ToolTier)>,
+    /// Mines blocks whole, picking their silk touch drops. See `loot`.
+    pub silk_touch: bool,
+    pub food: Option<Food>,
+    block_name: Option<String>,
+}
+
+#[derive(Resource)]
+pub struct ItemRegistry {
+    items: Vec<Option<ItemDef>>,
+    by_name: HashMap<String, ItemType>,
+}
+
+impl ItemRegistry {
+    pub fn load() -> Self {
+        let path = FileAssetReader::get_base_path()
+            .join("assets")
+            .join(ITEMS_FILE);
+        let data = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+        Self::from_json(&data).unwrap_or_else(|e| panic!("Invalid {}: {}", ITEMS_FILE, e))
+    }
+
+    pub fn from_json(data: &str) -> Result<Self, String> {
+        let file: ItemsFile = serde_json::from_str(data).map_err(|e| e.to_string())?;
+
+        let mut items: Vec<Option<ItemDef>> = vec![None];
+        let mut by_name = HashMap::new();
+        for def in file.items {
+            if def.id == ItemType::NONE.0 {
+                return Err(format!(
+                    "item {}: id 0 is reserved for empty slots",
+                    def.name
+                ));
+            }
+            if by_name.insert(def.name.clone(), ItemType(def.id)).is_some() {
+                return Err(format!("duplicate item name {}", def.name));
+            }
+
+            let index = def.id as usize;
+            if items.len() <= index {
+                items.resize(index + 1, None);
+            }
+            if items[index].is_some() {
+                return Err(format!("duplicate item id {}", def.id));
+            }
+            let tool = match (def.tool, def.tier) {
+                (Some(kind), Some(tier)) => Some((kind, tier)),
+                (None, None) => None,
+                _ => {
+                    return Err(format!(
+                        "item {}: a tool needs both a kind and a tier",
+                        def.name
+                    ));
+                }
+            };
+            items[index] = Some(ItemDef {
+                display_name: def.display_name,
+                icon: def.icon,
+                max_stack: if tool.is_some() {
+                    1
+                } else {
+                    def.max_stack.max(1)
+                },
+                block: None,
+                tool,
+                silk_touch: def.silk_touch,
+                food: def.food,
+                block_name: def.block,
+            });
+        }
+
+        Ok(Self { items, by_name })
+    }
+
+    /// Resolves each item's placed block by name. Blocks and items refer to
+    /// each other, so this runs once both registries are loaded.
+    pub fn link_blocks(&mut self, blocks: &BlockRegistry) -> Result<(), String> {
+        for def in self.items.iter_mut().flatten() {
+            if let Some(name) = &def.block_name {
+                def.block =
+                    Some(blocks.by_name(name).ok_or_else(|| {
+                        format!("item {}: unknown block {}", def.display_name, name)
+                    })?);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, item: ItemType) -> Option<&ItemDef> {
+        self.items.get(item.0 as usize).and_then(Option::as_ref)
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<ItemType> {
+        self.by_name.get(name).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ItemType, &ItemDef)> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(id, def)| def.as_ref().map(|def| (ItemType(id as u16), def)))
+    }
+
+    /// Number of ids, including gaps, so per-item tables can be indexed by id.
+    pub fn id_count(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn max_stack(&self, item: ItemType) -> u32 {
+        self.get(item).map_or(0, |def| def.max_stack)
+    }
+
+    pub fn block_for(&self, item: ItemType) -> Option<VoxelType> {
+        self.get(item).and_then(|def| def.block)
+    }
+
+    pub fn tool_for(&self, item: ItemType) -> Option<(ToolKind, ToolTier)> {
+        self.get(item).and_then(|def| def.tool)
+    }
+
+    pub fn attack(&self, item: ItemType) -> Attack {
+        self.tool_for(item).map_or(HAND_ATTACK, |(kind, tier)| {
+            let attack = kind.base_attack();
+            Attack {
+                damage: attack.damage + tier.attack_bonus(),
+                ..attack
+            }
+        })
+    }
+
+    pub fn food_for(&self, item: ItemType) -> Option<Food> {
+        self.get(item).and_then(|def| def.food)
+    }
+
+    /// Uses a tool lasts, or `None` for items that don't wear out.
+    pub fn durability(&self, item: ItemType) -> Option<u32> {
+        self.tool_for(item).map(|(_, tier)| tier.durability())
+    }
+}
+
+/// A registry of plain items named `names`, numbered from 1 in that order,
+/// for tests that only need the items to exist.
+#[cfg(test)]
+pub fn test_items(names: &[&str]) -> ItemRegistry {
+    let mut registry = ItemRegistry {
+        items: vec![None],
+        by_name: HashMap::new(),
+    };
+    for name in names {
+        let item = ItemType(registry.items.len() as u16);
+        registry.items.push(Some(ItemDef {
+            display_name: name.to_string(),
+            icon: format!("{}.png", name),
+            max_stack: default_max_stack(),
+            block: None,
+            tool: None,
+            silk_touch: false,
+            food: None,
+            block_name: None,
+        }));
+        registry.by_name.insert(name.to_string(), item);
+    }
+    registry
+}
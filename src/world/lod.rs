@@ -0,0 +1,275 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::mesh::Indices;
+use bevy::prelude::*;
+use bevy::render::render_resource::PrimitiveTopology;
+use noise::Perlin;
+use std::collections::HashMap;
+
+use crate::main_menu::WorldSettings;
+use crate::player::settings_menu::Settings;
+use crate::world::VoxelWorld;
+use crate::world::components::{CHUNK_SIZE, InGameEntity};
+use crate::world::systems::{InitialChunkMeshing, terrain_height, world_chunk_y_range};
+
+/// Number of voxels along each axis that one LOD cell covers.
+const LOD_CELL_SIZE: i32 = 4;
+const MAX_LOD_COLUMNS_PER_FRAME: usize = 4;
+
+/// A simplified, collider-less mesh standing in for a whole chunk column
+/// outside of the full-detail render distance.
+#[derive(Component)]
+pub struct LodColumn;
+
+#[derive(Resource, Default)]
+pub struct LodWorld {
+    pub columns: HashMap<IVec2, Entity>,
+}
+
+#[derive(Resource)]
+pub struct LodAssets {
+    pub material: Handle<StandardMaterial>,
+}
+
+pub fn setup_lod(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut lod_world: ResMut<LodWorld>,
+) {
+    lod_world.columns.clear();
+    // Colors come from vertex attributes, so the material stays untextured.
+    let material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        perceptual_roughness: 1.0,
+        ..default()
+    });
+    commands.insert_resource(LodAssets { material });
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_lod_columns(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut lod_world: ResMut<LodWorld>,
+    lod_assets: Res<LodAssets>,
+    player_query: Query<&Transform, With<crate::player::components::Player>>,
+    settings: Res<Settings>,
+    initial_meshing: Res<InitialChunkMeshing>,
+    world_settings: Res<WorldSettings>,
+) {
+    if initial_meshing.0 {
+        return;
+    }
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+
+    let player_chunk_pos = VoxelWorld::world_to_chunk_pos(player_transform.translation);
+    let view_distance = settings.render_distance;
+    let lod_distance = settings.lod_distance.max(view_distance);
+    let perlin = Perlin::new(world_settings.seed as u32);
+
+    // Fill the ring nearest-first so the horizon grows outward evenly.
+    let mut missing = Vec::new();
+    for x in -lod_distance..=lod_distance {
+        for z in -lod_distance..=lod_distance {
+            if x.abs() <= view_distance && z.abs() <= view_distance {
+                continue;
+            }
+            let column = IVec2::new(player_chunk_pos.x + x, player_chunk_pos.z + z);
+            if !lod_world.columns.contains_key(&column) {
+                missing.push((x * x + z * z, column));
+            }
+        }
+    }
+    missing.sort_by_key(|(distance, _)| *distance);
+
+    for (_, column) in missing.into_iter().take(MAX_LOD_COLUMNS_PER_FRAME) {
+        let mesh = build_lod_mesh(column, &perlin);
+        let entity = commands
+            .spawn((
+                Mesh3d(meshes.add(mesh)),
+                MeshMaterial3d(lod_assets.material.clone()),
+                Transform::from_translation(Vec3::new(
+                    (column.x * CHUNK_SIZE as i32) as f32,
+                    0.0,
+                    (column.y * CHUNK_SIZE as i32) as f32,
+                )),
+                Visibility::Visible,
+                LodColumn,
+                InGameEntity,
+            ))
+            .id();
+        lod_world.columns.insert(column, entity);
+    }
+}
+
+/// Drops LOD columns that left the LOD ring, and those whose full-detail
+/// chunks have all been loaded as the player approached.
+pub fn despawn_lod_columns(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut lod_world: ResMut<LodWorld>,
+    voxel_world: Res<VoxelWorld>,
+    lod_query: Query<&Mesh3d, With<LodColumn>>,
+    player_query: Query<&Transform, With<crate::player::components::Player>>,
+    settings: Res<Settings>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+
+    let player_chunk_pos = VoxelWorld::world_to_chunk_pos(player_transform.translation);
+    let view_distance = settings.render_distance;
+    let lod_distance = settings.lod_distance.max(view_distance);
+    let (min_chunk_y, max_chunk_y) = world_chunk_y_range();
+
+    let mut to_remove = Vec::new();
+    for (column, entity) in lod_world.columns.iter() {
+        let delta_x = (column.x - player_chunk_pos.x).abs();
+        let delta_z = (column.y - player_chunk_pos.z).abs();
+
+        let out_of_range = delta_x > lod_distance || delta_z > lod_distance;
+        let promoted = delta_x <= view_distance
+            && delta_z <= view_distance
+            && (min_chunk_y..=max_chunk_y).all(|y| {
+                voxel_world
+                    .chunks
+                    .contains_key(&IVec3::new(column.x, y, column.y))
+            });
+
+        if out_of_range || promoted {
+            if let Ok(mesh) = lod_query.get(*entity) {
+                meshes.remove(mesh.0.id());
+            }
+            commands.entity(*entity).despawn();
+            to_remove.push(*column);
+        }
+    }
+
+    for column in to_remove {
+        lod_world.columns.remove(&column);
+    }
+}
+
+/// Builds a heightmap mesh for one chunk column, sampling the terrain noise
+/// once per `LOD_CELL_SIZE` x `LOD_CELL_SIZE` cell.
+fn build_lod_mesh(column: IVec2, perlin: &Perlin) -> Mesh {
+    let top_color = [0.36, 0.58, 0.27, 1.0];
+    let side_color = [0.47, 0.33, 0.22, 1.0];
+
+    let cells = CHUNK_SIZE as i32 / LOD_CELL_SIZE;
+    let origin = IVec2::new(column.x * CHUNK_SIZE as i32, column.y * CHUNK_SIZE as i32);
+    let cell_height = |cx: i32, cz: i32| -> f32 {
+        let world_x = origin.x + cx * LOD_CELL_SIZE + LOD_CELL_SIZE / 2;
+        let world_z = origin.y + cz * LOD_CELL_SIZE + LOD_CELL_SIZE / 2;
+        // Top face of the surface voxel sits one unit above its height
+        (terrain_height(world_x, world_z, perlin) + 1) as f32
+    };
+
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut colors: Vec<[f32; 4]> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+
+    let mut add_quad = |vertices: [[f32; 3]; 4], normal: [f32; 3], color: [f32; 4]| {
+        let start_idx = positions.len() as u32;
+        positions.extend_from_slice(&vertices);
+        normals.extend_from_slice(&[normal; 4]);
+        colors.extend_from_slice(&[color; 4]);
+        indices.extend_from_slice(&[
+            start_idx,
+            start_idx + 1,
+            start_idx + 2,
+            start_idx,
+            start_idx + 2,
+            start_idx + 3,
+        ]);
+    };
+
+    let size = LOD_CELL_SIZE as f32;
+    for cx in 0..cells {
+        for cz in 0..cells {
+            let height = cell_height(cx, cz);
+            let x0 = cx as f32 * size;
+            let z0 = cz as f32 * size;
+            let x1 = x0 + size;
+            let z1 = z0 + size;
+
+            add_quad(
+                [
+                    [x0, height, z0],
+                    [x0, height, z1],
+                    [x1, height, z1],
+                    [x1, height, z0],
+                ],
+                [0.0, 1.0, 0.0],
+                top_color,
+            );
+
+            // Neighbor cells may lie in the next column, so sample them
+            // straight from the noise instead of from this mesh's cells.
+            let east = cell_height(cx + 1, cz);
+            if east < height {
+                add_quad(
+                    [
+                        [x1, east, z1],
+                        [x1, east, z0],
+                        [x1, height, z0],
+                        [x1, height, z1],
+                    ],
+                    [1.0, 0.0, 0.0],
+                    side_color,
+                );
+            }
+            let west = cell_height(cx - 1, cz);
+            if west < height {
+                add_quad(
+                    [
+                        [x0, west, z0],
+                        [x0, west, z1],
+                        [x0, height, z1],
+                        [x0, height, z0],
+                    ],
+                    [-1.0, 0.0, 0.0],
+                    side_color,
+                );
+            }
+            let south = cell_height(cx, cz + 1);
+            if south < height {
+                add_quad(
+                    [
+                        [x0, south, z1],
+                        [x1, south, z1],
+                        [x1, height, z1],
+                        [x0, height, z1],
+                    ],
+                    [0.0, 0.0, 1.0],
+                    side_color,
+                );
+            }
+            let north = cell_height(cx, cz - 1);
+            if north < height {
+                add_quad(
+                    [
+                        [x1, north, z0],
+                        [x0, north, z0],
+                        [x0, height, z0],
+                        [x1, height, z0],
+                    ],
+                    [0.0, 0.0, -1.0],
+                    side_color,
+                );
+            }
+        }
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+    mesh
+}
@@ -0,0 +1,246 @@
+//! Block loot tables: the `drops` of each block in `assets/data/blocks.json`.
+//! Every entry rolls on its own, so a block can drop several things, and
+//! entries can be limited to a kind of tool. Silk touch entries stand in for
+//! the rest when the block is mined with a silk touch tool, like leaves
+//! sheared off whole.
+
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::world::components::ItemType;
+use crate::world::items::{ItemRegistry, ToolKind};
+
+/// How many of an item an entry drops: a fixed count, or a range from the
+/// first to the second inclusive.
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(untagged)]
+enum CountFile {
+    Exact(u32),
+    Range([u32; 2]),
+}
+
+fn default_count() -> CountFile {
+    CountFile::Exact(1)
+}
+
+fn default_chance() -> f64 {
+    1.0
+}
+
+#[derive(Deserialize)]
+pub(crate) struct LootEntryFile {
+    item: String,
+    #[serde(default = "default_count")]
+    count: CountFile,
+    #[serde(default = "default_chance")]
+    chance: f64,
+    /// Only drops when mined with this kind of tool.
+    tool: Option<ToolKind>,
+    #[serde(default)]
+    silk_touch: bool,
+}
+
+#[derive(Clone, Debug)]
+struct LootEntry {
+    item: ItemType,
+    min_count: u32,
+    max_count: u32,
+    chance: f64,
+    tool: Option<ToolKind>,
+    silk_touch: bool,
+}
+
+/// What broke a block, as far as its loot table cares.
+#[derive(Clone, Copy, Debug)]
+pub struct LootContext {
+    pub tool: Option<ToolKind>,
+    pub silk_touch: bool,
+    /// Scales each entry's chance, for explosions that destroy some drops.
+    pub yield_scale: f64,
+}
+
+impl Default for LootContext {
+    fn default() -> Self {
+        Self {
+            tool: None,
+            silk_touch: false,
+            yield_scale: 1.0,
+        }
+    }
+}
+
+impl LootContext {
+    /// Mined by the player holding `item`.
+    pub fn mined_with(item: ItemType, items: &ItemRegistry) -> Self {
+        Self {
+            tool: items.tool_for(item).map(|(kind, _)| kind),
+            silk_touch: items.get(item).is_some_and(|def| def.silk_touch),
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LootTable {
+    entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    pub(crate) fn from_file(
+        entries: &[LootEntryFile],
+        items: &ItemRegistry,
+    ) -> Result<Self, String> {
+        let entries = entries
+            .iter()
+            .map(|entry| {
+                let item = items
+                    .by_name(&entry.item)
+                    .ok_or_else(|| format!("unknown drop item {}", entry.item))?;
+                let (min_count, max_count) = match entry.count {
+                    CountFile::Exact(count) => (count, count),
+                    CountFile::Range([min, max]) => (min, max),
+                };
+                if min_count > max_count {
+                    return Err(format!("drop {} has an empty count range", entry.item));
+                }
+                Ok(LootEntry {
+                    item,
+                    min_count,
+                    max_count,
+                    chance: entry.chance,
+                    tool: entry.tool,
+                    silk_touch: entry.silk_touch,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { entries })
+    }
+
+    /// Rolls every entry that applies, one item per drop.
+    pub fn roll(&self, context: &LootContext, rng: &mut impl Rng) -> Vec<ItemType> {
+        let silk_touch = context.silk_touch && self.entries.iter().any(|entry| entry.silk_touch);
+        let mut drops = Vec::new();
+        for entry in &self.entries {
+            if entry.silk_touch != silk_touch
+                || entry.tool.is_some_and(|tool| context.tool != Some(tool))
+            {
+                continue;
+            }
+            if !rng.gen_bool((entry.chance * context.yield_scale).clamp(0.0, 1.0)) {
+                continue;
+            }
+            let count = rng.gen_range(entry.min_count..=entry.max_count);
+            drops.extend(std::iter::repeat_n(entry.item, count as usize));
+        }
+        drops
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::items::test_items;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn table(entries: &str) -> (LootTable, ItemRegistry) {
+        let items = test_items(&["oak_leaves", "oak_sapling", "stick", "white_wool", "string"]);
+        let entries: Vec<LootEntryFile> = serde_json::from_str(entries).unwrap();
+        (LootTable::from_file(&entries, &items).unwrap(), items)
+    }
+
+    fn count(drops: &[ItemType], items: &ItemRegistry, name: &str) -> usize {
+        let item = items.by_name(name).unwrap();
+        drops.iter().filter(|drop| **drop == item).count()
+    }
+
+    #[test]
+    fn silk_touch_entries_replace_the_rest() {
+        let (table, items) = table(
+            r#"[
+                { "item": "oak_sapling" },
+                { "item": "stick", "count": 2 },
+                { "item": "oak_leaves", "silk_touch": true }
+            ]"#,
+        );
+        let mut rng = StdRng::seed_from_u64(1);
+        let silk_touch = LootContext {
+            silk_touch: true,
+            ..LootContext::default()
+        };
+
+        let drops = table.roll(&silk_touch, &mut rng);
+        assert_eq!(drops, vec![items.by_name("oak_leaves").unwrap()]);
+
+        let drops = table.roll(&LootContext::default(), &mut rng);
+        assert_eq!(count(&drops, &items, "oak_sapling"), 1);
+        assert_eq!(count(&drops, &items, "stick"), 2);
+        assert_eq!(count(&drops, &items, "oak_leaves"), 0);
+    }
+
+    #[test]
+    fn silk_touch_without_silk_touch_entries_drops_as_usual() {
+        let (table, items) = table(r#"[{ "item": "stick", "count": 2 }]"#);
+        let mut rng = StdRng::seed_from_u64(2);
+        let silk_touch = LootContext {
+            silk_touch: true,
+            ..LootContext::default()
+        };
+
+        let drops = table.roll(&silk_touch, &mut rng);
+        assert_eq!(count(&drops, &items, "stick"), 2);
+    }
+
+    #[test]
+    fn tool_entries_need_that_tool() {
+        let (table, items) = table(
+            r#"[
+                { "item": "white_wool", "tool": "shears" },
+                { "item": "string" }
+            ]"#,
+        );
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for tool in [None, Some(ToolKind::Sword)] {
+            let context = LootContext {
+                tool,
+                ..LootContext::default()
+            };
+            let drops = table.roll(&context, &mut rng);
+            assert_eq!(drops, vec![items.by_name("string").unwrap()]);
+        }
+
+        let shears = LootContext {
+            tool: Some(ToolKind::Shears),
+            ..LootContext::default()
+        };
+        let drops = table.roll(&shears, &mut rng);
+        assert_eq!(count(&drops, &items, "white_wool"), 1);
+        assert_eq!(count(&drops, &items, "string"), 1);
+    }
+
+    #[test]
+    fn counts_stay_within_their_range() {
+        let (table, items) = table(
+            r#"[
+                { "item": "stick", "count": [2, 4] },
+                { "item": "string", "count": [0, 1] }
+            ]"#,
+        );
+        let mut rng = StdRng::seed_from_u64(4);
+
+        let mut sticks_seen = [false; 5];
+        let mut strings_seen = [false; 2];
+        for _ in 0..200 {
+            let drops = table.roll(&LootContext::default(), &mut rng);
+            let sticks = count(&drops, &items, "stick");
+            let strings = count(&drops, &items, "string");
+            assert!((2..=4).contains(&sticks), "dropped {} sticks", sticks);
+            assert!(strings <= 1, "dropped {} strings", strings);
+            sticks_seen[sticks] = true;
+            strings_seen[strings] = true;
+        }
+        assert_eq!(sticks_seen, [false, false, true, true, true]);
+        assert_eq!(strings_seen, [true, true]);
+    }
+}
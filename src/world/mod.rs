@@ -1,25 +1,84 @@
 use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use std::time::Duration;
 
+pub mod animation;
+pub mod block_entity;
+pub mod block_state;
+pub mod blocks;
+pub mod colliders;
 pub mod components;
+pub mod crafting;
+pub mod culling;
+pub mod explosion;
+pub mod falling;
+pub mod farming;
+pub mod interact;
+pub mod items;
+pub mod lod;
+pub mod loot;
+pub mod persistence;
+pub mod piston;
+pub mod redstone;
 pub mod resources;
+pub mod shapes;
+pub mod smelting;
 pub mod systems;
 
+use animation::{BlockAnimations, animate_block_textures};
+use blocks::BlockRegistry;
+use colliders::{update_chunk_colliders, update_physics_range};
+use components::BlockChanged;
+use crafting::RecipeRegistry;
+use culling::cull_hidden_sections;
+use explosion::{Explosion, apply_explosions, tick_primed_explosives};
+use falling::{land_falling_blocks, start_falling_blocks};
+use farming::{RANDOM_TICK_SECONDS, random_tick_farmland, trample_farmland, uproot_crops};
+use items::ItemRegistry;
+use lod::{LodWorld, despawn_lod_columns, setup_lod, spawn_lod_columns};
+use persistence::{mark_modified_chunks, save_modified_chunks};
+use piston::animate_moving_blocks;
+use redstone::{REDSTONE_TICK_SECONDS, RedstoneNetwork, queue_redstone_updates, tick_redstone};
 use resources::{ChunkLoadFrameCounter, VoxelWorld};
+use smelting::{FURNACE_TICK_SECONDS, SmeltingRegistry, advance_play_time, tick_furnaces};
 use systems::{
-    apply_chunk_despawns, despawn_far_chunks, reset_voxel_world, setup_world,
-    spawn_chunks_around_player, update_chunk_mesh, update_game_time,
+    apply_chunk_despawns, despawn_far_chunks, reload_world_textures, reset_voxel_world,
+    setup_world, sort_translucent_faces, spawn_chunks_around_player, update_chunk_mesh,
+    update_game_time,
 };
 
 pub struct WorldPlugin;
 
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<VoxelWorld>()
+        let mut items = ItemRegistry::load();
+        let blocks = BlockRegistry::load(&items);
+        if let Err(e) = items.link_blocks(&blocks) {
+            panic!("Invalid {}: {}", items::ITEMS_FILE, e);
+        }
+
+        let smelting = SmeltingRegistry::load(&items);
+        let recipes = RecipeRegistry::load(&items);
+
+        app.insert_resource(blocks)
+            .insert_resource(items)
+            .insert_resource(smelting)
+            .insert_resource(recipes)
+            .init_resource::<VoxelWorld>()
             .init_resource::<crate::world::components::GameTime>()
             .init_resource::<ChunkLoadFrameCounter>()
+            .init_resource::<LodWorld>()
+            .init_resource::<BlockAnimations>()
+            .init_resource::<RedstoneNetwork>()
+            .add_message::<BlockChanged>()
+            .add_message::<Explosion>()
             .add_systems(
                 OnEnter(crate::main_menu::AppState::InGame),
-                (reset_voxel_world, setup_world).chain(),
+                (reset_voxel_world, setup_world, setup_lod).chain(),
+            )
+            .add_systems(
+                OnExit(crate::main_menu::AppState::InGame),
+                save_modified_chunks,
             )
             .add_systems(
                 Update,
@@ -29,8 +88,55 @@ impl Plugin for WorldPlugin {
                     apply_chunk_despawns,
                     update_chunk_mesh,
                     update_game_time,
+                    spawn_lod_columns,
+                    despawn_lod_columns,
+                    cull_hidden_sections.after(update_chunk_mesh),
+                    sort_translucent_faces.after(update_chunk_mesh),
+                    reload_world_textures,
+                    animate_block_textures,
+                    mark_modified_chunks,
+                    start_falling_blocks,
+                    land_falling_blocks,
+                    tick_primed_explosives,
+                    apply_explosions,
+                    queue_redstone_updates,
+                    animate_moving_blocks,
+                    advance_play_time,
+                )
+                    .run_if(in_state(crate::main_menu::AppState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_physics_range,
+                    update_chunk_colliders
+                        .after(update_physics_range)
+                        .before(update_chunk_mesh),
                 )
                     .run_if(in_state(crate::main_menu::AppState::InGame)),
+            )
+            .add_systems(
+                Update,
+                (trample_farmland, uproot_crops)
+                    .run_if(in_state(crate::main_menu::AppState::InGame)),
+            )
+            .add_systems(
+                FixedUpdate,
+                random_tick_farmland
+                    .run_if(on_timer(Duration::from_secs_f32(RANDOM_TICK_SECONDS)))
+                    .run_if(in_state(crate::main_menu::AppState::InGame)),
+            )
+            .add_systems(
+                FixedUpdate,
+                tick_redstone
+                    .run_if(on_timer(Duration::from_secs_f32(REDSTONE_TICK_SECONDS)))
+                    .run_if(in_state(crate::main_menu::AppState::InGame)),
+            )
+            .add_systems(
+                FixedUpdate,
+                tick_furnaces
+                    .run_if(on_timer(Duration::from_secs_f32(FURNACE_TICK_SECONDS)))
+                    .run_if(in_state(crate::main_menu::AppState::InGame)),
             );
     }
 }
@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::main_menu::{WorldSettings, get_worlds_dir};
+use crate::world::block_entity::BlockEntity;
+use crate::world::components::{CHUNK_SIZE, Chunk, ChunkPosition, VoxelType};
+
+const CHUNK_VOLUME: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
+
+/// Marks a chunk the player has edited since it was generated or loaded, so
+/// it is written back to disk when it unloads.
+#[derive(Component)]
+pub struct ChunkModified;
+
+/// A saved chunk as runs of `(block id, state, length)` in x, y, z order,
+/// plus its block entities by local position.
+#[derive(Serialize, Deserialize)]
+struct ChunkFile {
+    runs: Vec<(u16, u8, u16)>,
+    #[serde(default)]
+    block_entities: Vec<([i32; 3], BlockEntity)>,
+}
+
+fn chunk_path(world_name: &str, chunk_key: IVec3) -> PathBuf {
+    let mut path = get_worlds_dir();
+    path.push(world_name);
+    path.push("chunks");
+    path.push(format!(
+        "{}_{}_{}.json",
+        chunk_key.x, chunk_key.y, chunk_key.z
+    ));
+    path
+}
+
+/// Reads a chunk saved by `save_chunk`. `None` if it was never saved, in
+/// which case the caller generates it instead.
+pub fn load_chunk(world_name: &str, chunk_key: IVec3) -> Option<Chunk> {
+    let path = chunk_path(world_name, chunk_key);
+    let data = fs::read_to_string(&path).ok()?;
+    let file: ChunkFile = match serde_json::from_str(&data) {
+        Ok(file) => file,
+        Err(e) => {
+            println!("[World] Invalid {}: {}", path.display(), e);
+            return None;
+        }
+    };
+
+    let total: usize = file.runs.iter().map(|&(_, _, run)| run as usize).sum();
+    if total != CHUNK_VOLUME {
+        println!(
+            "[World] Invalid {}: {} voxels instead of {}",
+            path.display(),
+            total,
+            CHUNK_VOLUME
+        );
+        return None;
+    }
+
+    let mut chunk = Chunk::empty();
+    let cells = file
+        .runs
+        .iter()
+        .flat_map(|&(id, state, run)| std::iter::repeat_n((id, state), run as usize));
+    for (index, (id, state)) in cells.enumerate() {
+        let (x, y, z) = (
+            index / (CHUNK_SIZE * CHUNK_SIZE),
+            index / CHUNK_SIZE % CHUNK_SIZE,
+            index % CHUNK_SIZE,
+        );
+        chunk.voxels[x][y][z] = VoxelType(id);
+        chunk.states[x][y][z] = state;
+    }
+    for (pos, block_entity) in file.block_entities {
+        chunk
+            .block_entities
+            .insert(IVec3::from_array(pos), block_entity);
+    }
+    Some(chunk)
+}
+
+pub fn save_chunk(world_name: &str, chunk_key: IVec3, chunk: &Chunk) {
+    let mut runs: Vec<(u16, u8, u16)> = Vec::new();
+    let cells = chunk
+        .voxels
+        .iter()
+        .flatten()
+        .flatten()
+        .zip(chunk.states.iter().flatten().flatten());
+    for (voxel, &state) in cells {
+        match runs.last_mut() {
+            Some((id, last_state, run)) if *id == voxel.0 && *last_state == state => *run += 1,
+            _ => runs.push((voxel.0, state, 1)),
+        }
+    }
+
+    let path = chunk_path(world_name, chunk_key);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).ok();
+    }
+    let block_entities = chunk
+        .block_entities
+        .iter()
+        .map(|(pos, block_entity)| (pos.to_array(), block_entity.clone()))
+        .collect();
+    match serde_json::to_string(&ChunkFile {
+        runs,
+        block_entities,
+    }) {
+        Ok(data) => {
+            if let Err(e) = fs::write(&path, data) {
+                println!("[World] Failed to save {}: {}", path.display(), e);
+            }
+        }
+        Err(e) => println!("[World] Failed to encode chunk {}: {}", chunk_key, e),
+    }
+}
+
+pub fn mark_modified_chunks(
+    mut commands: Commands,
+    chunks: Query<(Entity, Ref<Chunk>), Without<ChunkModified>>,
+) {
+    for (entity, chunk) in chunks.iter() {
+        // A freshly spawned chunk matches what is already on disk or what
+        // the generator would make again
+        if chunk.is_changed() && !chunk.is_added() {
+            commands.entity(entity).insert(ChunkModified);
+        }
+    }
+}
+
+/// Writes every edited chunk still loaded when leaving the game.
+pub fn save_modified_chunks(
+    world_settings: Res<WorldSettings>,
+    chunks: Query<(&Chunk, &ChunkPosition), With<ChunkModified>>,
+) {
+    for (chunk, position) in chunks.iter() {
+        save_chunk(&world_settings.name, position.0, chunk);
+    }
+}
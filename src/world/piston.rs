@@ -0,0 +1,433 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::HashSet;
+
+use crate::mob::components::Mob;
+use crate::player::components::Player;
+use crate::player::systems::spawn_drop_item;
+use crate::world::block_state::{DIRECTIONS, direction, is_open, with_open};
+use crate::world::blocks::{BlockDef, BlockModel, BlockRegistry, FACE_SIDE, RedstoneKind};
+use crate::world::components::{BlockChanged, Chunk, InGameEntity, VoxelType};
+use crate::world::loot::LootContext;
+use crate::world::resources::VoxelWorld;
+use crate::world::systems::{BlockAssets, RemeshBatch, block_at, set_block_at};
+
+/// Most blocks one piston can push.
+pub const PUSH_LIMIT: usize = 12;
+/// Seconds a pushed block takes to slide into its new cell.
+const MOVE_SECONDS: f32 = 0.1;
+
+/// Players and mobs, which press plates they stand on and ride pistons.
+pub type MoverQuery<'w, 's> =
+    Query<'w, 's, (&'static mut Transform, &'static Collider), Or<(With<Player>, With<Mob>)>>;
+
+/// A block sliding from one cell to the next. Only for show: its voxel is
+/// already in the cell it's moving to.
+#[derive(Component)]
+pub struct MovingBlock {
+    pub from: IVec3,
+    pub to: IVec3,
+    pub elapsed: f32,
+}
+
+/// The cell a standing entity's feet are in.
+pub fn feet_cell(transform: &Transform, collider: &Collider) -> IVec3 {
+    let half_height = collider.as_cuboid().map_or(0.5, |c| c.half_extents().y);
+    (transform.translation - Vec3::Y * (half_height - 0.05))
+        .floor()
+        .as_ivec3()
+}
+
+fn is_movable(block: &BlockDef, state: u8) -> bool {
+    let extended_piston = matches!(
+        block.redstone,
+        Some(RedstoneKind::Piston | RedstoneKind::StickyPiston)
+    ) && is_open(state);
+    // Doors would leave their other half behind, and containers their
+    // contents
+    let fixed_model = matches!(block.model, BlockModel::PistonHead | BlockModel::Door);
+    block.is_breakable() && !fixed_model && !extended_piston && block.block_entity.is_none()
+}
+
+/// Sets a voxel without touching the chunk's remesh state, leaving that to
+/// `batch` so every chunk a move touches remeshes once.
+fn set_cell(
+    voxel_world: &VoxelWorld,
+    chunk_query: &mut Query<&mut Chunk>,
+    batch: &mut RemeshBatch,
+    pos: IVec3,
+    voxel: VoxelType,
+    state: u8,
+) {
+    let chunk_pos = VoxelWorld::world_to_chunk_pos(pos.as_vec3());
+    if let Some(mut chunk) = voxel_world
+        .chunks
+        .get(&chunk_pos)
+        .and_then(|entity| chunk_query.get_mut(*entity).ok())
+    {
+        chunk.set_block(VoxelWorld::voxel_to_local_pos(pos), voxel, state);
+        batch.mark(pos);
+    }
+}
+
+/// The blocks a push starting at `start` would move, nearest first, and the
+/// cell the last of them moves into. `None` when something immovable, an
+/// unloaded chunk or more than `PUSH_LIMIT` blocks are in the way.
+fn push_line(
+    voxel_world: &VoxelWorld,
+    chunk_query: &Query<&mut Chunk>,
+    registry: &BlockRegistry,
+    start: IVec3,
+    direction: IVec3,
+) -> Option<(Vec<IVec3>, IVec3)> {
+    let mut line = Vec::new();
+    let mut pos = start;
+    loop {
+        let (voxel, state) = block_at(voxel_world, chunk_query, pos)?;
+        let block = registry.get(voxel);
+        // Plants, wires and other blocks without collision are crushed
+        if !block.collision {
+            return Some((line, pos));
+        }
+        if !is_movable(block, state) || line.len() == PUSH_LIMIT {
+            return None;
+        }
+        line.push(pos);
+        pos += direction;
+    }
+}
+
+fn spawn_moving_block(
+    commands: &mut Commands,
+    block_assets: &BlockAssets,
+    registry: &BlockRegistry,
+    voxel: VoxelType,
+    from: IVec3,
+    to: IVec3,
+) {
+    let block = registry.get(voxel);
+    let material = block_assets.block_materials[block.face_materials[FACE_SIDE]].clone();
+    commands.spawn((
+        MovingBlock {
+            from,
+            to,
+            elapsed: 0.0,
+        },
+        Mesh3d(block_assets.mesh.clone()),
+        MeshMaterial3d(material),
+        Transform::from_translation(from.as_vec3() + Vec3::splat(0.5)),
+        InGameEntity,
+    ));
+}
+
+/// How far, up to a block, an entity can be pushed along `dir` before its
+/// collider runs into a solid voxel.
+fn push_distance(
+    voxel_world: &VoxelWorld,
+    chunk_query: &Query<&mut Chunk>,
+    registry: &BlockRegistry,
+    transform: &Transform,
+    collider: &Collider,
+    dir: IVec3,
+) -> f32 {
+    const EPSILON: f32 = 0.001;
+    let half_extents = collider
+        .as_cuboid()
+        .map_or(Vec3::splat(0.5), |c| c.half_extents());
+    let min = (transform.translation - half_extents).to_array();
+    let max = (transform.translation + half_extents).to_array();
+    let dir = dir.to_array();
+    let Some(axis) = dir.iter().position(|&d| d != 0) else {
+        return 0.0;
+    };
+    let forward = dir[axis] > 0;
+
+    // Cells across the collider's face, and the layers it would sweep into
+    let span = |a: usize| (min[a] + EPSILON).floor() as i32..=(max[a] - EPSILON).floor() as i32;
+    let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+    let layers: Vec<i32> = if forward {
+        let first = (max[axis] - EPSILON).floor() as i32 + 1;
+        (first..=(max[axis] + 1.0 - EPSILON).floor() as i32).collect()
+    } else {
+        let first = (min[axis] + EPSILON).floor() as i32 - 1;
+        ((min[axis] - 1.0 + EPSILON).floor() as i32..=first)
+            .rev()
+            .collect()
+    };
+
+    for layer in layers {
+        for a in span(u) {
+            for b in span(v) {
+                let mut cell = [0; 3];
+                cell[axis] = layer;
+                cell[u] = a;
+                cell[v] = b;
+                let solid = block_at(voxel_world, chunk_query, IVec3::from_array(cell))
+                    .is_none_or(|(voxel, _)| registry.get(voxel).collision);
+                if solid {
+                    let gap = if forward {
+                        layer as f32 - max[axis]
+                    } else {
+                        min[axis] - (layer + 1) as f32
+                    };
+                    return gap.clamp(0.0, 1.0);
+                }
+            }
+        }
+    }
+    1.0
+}
+
+/// Pushes the blocks in front of the piston at `pos` and puts its head out.
+/// Returns every position whose block changed, empty if it couldn't extend.
+#[allow(clippy::too_many_arguments)]
+pub fn extend_piston(
+    commands: &mut Commands,
+    voxel_world: &VoxelWorld,
+    chunk_query: &mut Query<&mut Chunk>,
+    registry: &BlockRegistry,
+    block_assets: &BlockAssets,
+    batch: &mut RemeshBatch,
+    movers: &mut MoverQuery,
+    pos: IVec3,
+) -> Vec<IVec3> {
+    let Some((voxel, state)) = block_at(voxel_world, chunk_query, pos) else {
+        return Vec::new();
+    };
+    if is_open(state) {
+        return Vec::new();
+    }
+    let Some(head) = registry.get(voxel).variant else {
+        return Vec::new();
+    };
+    let direction_index = direction(state);
+    let dir = DIRECTIONS[direction_index as usize];
+    let Some((line, end)) = push_line(voxel_world, chunk_query, registry, pos + dir, dir) else {
+        return Vec::new();
+    };
+
+    let mut changed = vec![pos, pos + dir];
+    if let Some((crushed, crushed_state)) = block_at(voxel_world, chunk_query, end)
+        && crushed != VoxelType::AIR
+    {
+        let mut rng = rand::thread_rng();
+        let drops = registry.get(crushed).drops_for(crushed_state);
+        for item in drops.roll(&LootContext::default(), &mut rng) {
+            spawn_drop_item(commands, block_assets, end, item);
+        }
+        set_cell(voxel_world, chunk_query, batch, end, VoxelType::AIR, 0);
+        changed.push(end);
+    }
+
+    // Entities riding the pushed blocks, or standing where they and the head
+    // are going, move along with them until something solid stops them
+    let occupied: HashSet<IVec3> = line
+        .iter()
+        .map(|&cell| cell + dir)
+        .chain([pos + dir])
+        .collect();
+    let carried: HashSet<IVec3> = line.iter().map(|&cell| cell + IVec3::Y).collect();
+    for (mut transform, collider) in movers.iter_mut() {
+        let feet = feet_cell(&transform, collider);
+        let body = transform.translation.floor().as_ivec3();
+        if carried.contains(&feet) || occupied.contains(&feet) || occupied.contains(&body) {
+            let distance = push_distance(
+                voxel_world,
+                chunk_query,
+                registry,
+                &transform,
+                collider,
+                dir,
+            );
+            transform.translation += dir.as_vec3() * distance;
+        }
+    }
+
+    // Farthest first, so no block is cleared after its neighbor moved in.
+    // Each lands in its new cell right away, in the same batch as the cells
+    // it left, so nothing can take the cell while it slides there.
+    for &cell in line.iter().rev() {
+        let Some((moved, moved_state)) = block_at(voxel_world, chunk_query, cell) else {
+            continue;
+        };
+        set_cell(
+            voxel_world,
+            chunk_query,
+            batch,
+            cell + dir,
+            moved,
+            moved_state,
+        );
+        set_cell(voxel_world, chunk_query, batch, cell, VoxelType::AIR, 0);
+        spawn_moving_block(commands, block_assets, registry, moved, cell, cell + dir);
+        changed.push(cell);
+        changed.push(cell + dir);
+    }
+
+    set_cell(
+        voxel_world,
+        chunk_query,
+        batch,
+        pos + dir,
+        head,
+        direction_index,
+    );
+    set_cell(
+        voxel_world,
+        chunk_query,
+        batch,
+        pos,
+        voxel,
+        with_open(state, true),
+    );
+    changed
+}
+
+/// Takes the head of the piston at `pos` back in, pulling the block in front
+/// of it along if the piston is sticky. Returns every position whose block
+/// changed.
+#[allow(clippy::too_many_arguments)]
+pub fn retract_piston(
+    commands: &mut Commands,
+    voxel_world: &VoxelWorld,
+    chunk_query: &mut Query<&mut Chunk>,
+    registry: &BlockRegistry,
+    block_assets: &BlockAssets,
+    batch: &mut RemeshBatch,
+    pos: IVec3,
+) -> Vec<IVec3> {
+    let Some((voxel, state)) = block_at(voxel_world, chunk_query, pos) else {
+        return Vec::new();
+    };
+    if !is_open(state) {
+        return Vec::new();
+    }
+    let block = registry.get(voxel);
+    let dir = DIRECTIONS[direction(state) as usize];
+    let head_pos = pos + dir;
+
+    let mut changed = vec![pos];
+    let head_present = block_at(voxel_world, chunk_query, head_pos)
+        .is_some_and(|(head, _)| Some(head) == block.variant);
+    if head_present {
+        set_cell(voxel_world, chunk_query, batch, head_pos, VoxelType::AIR, 0);
+        changed.push(head_pos);
+
+        let pulled_pos = head_pos + dir;
+        if block.redstone == Some(RedstoneKind::StickyPiston)
+            && let Some((pulled, pulled_state)) = block_at(voxel_world, chunk_query, pulled_pos)
+            && registry.get(pulled).collision
+            && is_movable(registry.get(pulled), pulled_state)
+        {
+            set_cell(
+                voxel_world,
+                chunk_query,
+                batch,
+                head_pos,
+                pulled,
+                pulled_state,
+            );
+            set_cell(
+                voxel_world,
+                chunk_query,
+                batch,
+                pulled_pos,
+                VoxelType::AIR,
+                0,
+            );
+            spawn_moving_block(
+                commands,
+                block_assets,
+                registry,
+                pulled,
+                pulled_pos,
+                head_pos,
+            );
+            changed.push(pulled_pos);
+        }
+    }
+
+    set_cell(
+        voxel_world,
+        chunk_query,
+        batch,
+        pos,
+        voxel,
+        with_open(state, false),
+    );
+    changed
+}
+
+/// Keeps a piston whole after one of its parts, a `voxel` at `pos`, was
+/// broken: a broken extended base takes its head with it, and a broken head
+/// leaves its base retracted.
+#[allow(clippy::too_many_arguments)]
+pub fn break_piston_part(
+    commands: &mut Commands,
+    voxel_world: &VoxelWorld,
+    chunk_query: &mut Query<&mut Chunk>,
+    registry: &BlockRegistry,
+    block_changes: &mut MessageWriter<BlockChanged>,
+    voxel: VoxelType,
+    pos: IVec3,
+    state: u8,
+) {
+    let block = registry.get(voxel);
+    let dir = DIRECTIONS[direction(state) as usize];
+    if block.model == BlockModel::PistonHead {
+        let base_pos = pos - dir;
+        if let Some((base, base_state)) = block_at(voxel_world, chunk_query, base_pos)
+            && registry.get(base).variant == Some(voxel)
+            && is_open(base_state)
+        {
+            let retracted = with_open(base_state, false);
+            set_block_at(
+                commands,
+                voxel_world,
+                chunk_query,
+                base_pos,
+                base,
+                retracted,
+            );
+            block_changes.write(BlockChanged { pos: base_pos });
+        }
+    } else if matches!(
+        block.redstone,
+        Some(RedstoneKind::Piston | RedstoneKind::StickyPiston)
+    ) && is_open(state)
+    {
+        let head_pos = pos + dir;
+        if let Some((head, _)) = block_at(voxel_world, chunk_query, head_pos)
+            && Some(head) == block.variant
+        {
+            set_block_at(
+                commands,
+                voxel_world,
+                chunk_query,
+                head_pos,
+                VoxelType::AIR,
+                0,
+            );
+            block_changes.write(BlockChanged { pos: head_pos });
+        }
+    }
+}
+
+/// Slides moving blocks toward their cells and removes the ones that arrived.
+pub fn animate_moving_blocks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut moving_query: Query<(Entity, &mut Transform, &mut MovingBlock)>,
+) {
+    for (entity, mut transform, mut moving) in moving_query.iter_mut() {
+        moving.elapsed += time.delta_secs();
+        let t = (moving.elapsed / MOVE_SECONDS).min(1.0);
+        let from = moving.from.as_vec3() + Vec3::splat(0.5);
+        transform.translation = from + (moving.to - moving.from).as_vec3() * t;
+        if t < 1.0 {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,359 @@
+//! Power network over voxels. Sources switch on, wires carry their power a
+//! level weaker per block, repeaters pass it on after a delay, lamps light
+//! up while powered, and doors swing open when power reaches them.
+//!
+//! Everything a circuit needs is kept in block state bits, so circuits are
+//! saved with their chunk. The network only remembers which positions need
+//! another look and which updates are waiting on a delay.
+
+use bevy::prelude::*;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use crate::player::resources::SoundAssets;
+use crate::player::settings_menu::Settings;
+use crate::player::systems::play_sound;
+use crate::world::block_state::{
+    DIRECTIONS, HORIZONTAL_FACINGS, UPPER, cycle_delay, delay, direction, facing, is_open,
+    is_powered, power, with_open, with_power, with_powered,
+};
+use crate::world::blocks::{BlockDef, BlockRegistry, RedstoneKind};
+use crate::world::components::{BlockChanged, CHUNK_SIZE, Chunk, ChunkPosition};
+use crate::world::interact::other_half;
+use crate::world::piston::{MoverQuery, extend_piston, feet_cell, retract_piston};
+use crate::world::resources::VoxelWorld;
+use crate::world::systems::{BlockAssets, RemeshBatch, block_at};
+
+/// Seconds per redstone tick.
+pub const REDSTONE_TICK_SECONDS: f32 = 0.1;
+pub const MAX_POWER: u8 = 15;
+/// Redstone ticks a button stays pressed.
+const BUTTON_TICKS: u64 = 10;
+/// Block updates one tick may run before the rest wait for the next, so a
+/// runaway circuit can't stall a frame.
+const MAX_UPDATES_PER_TICK: usize = 4096;
+
+#[derive(Resource, Default)]
+pub struct RedstoneNetwork {
+    tick: u64,
+    /// Positions to re-evaluate on the next tick.
+    dirty: HashSet<IVec3>,
+    /// Positions due at a later tick: repeaters switching and buttons
+    /// springing back.
+    scheduled: BTreeMap<u64, Vec<IVec3>>,
+    /// Plates something stood on last tick.
+    pressed_plates: HashSet<IVec3>,
+}
+
+impl RedstoneNetwork {
+    fn mark_with_neighbors(&mut self, pos: IVec3) {
+        self.dirty.insert(pos);
+        for offset in DIRECTIONS {
+            self.dirty.insert(pos + offset);
+        }
+    }
+
+    fn is_scheduled(&self, pos: IVec3) -> bool {
+        self.scheduled.values().any(|due| due.contains(&pos))
+    }
+
+    fn schedule(&mut self, pos: IVec3, ticks: u64) {
+        self.scheduled
+            .entry(self.tick + ticks)
+            .or_default()
+            .push(pos);
+    }
+}
+
+/// New state for a redstone part the player right-clicked, or `None` if
+/// clicking it does nothing and the click should fall through to placing.
+pub fn use_state(block: &BlockDef, state: u8) -> Option<u8> {
+    match block.redstone? {
+        RedstoneKind::Lever => Some(with_powered(state, !is_powered(state))),
+        RedstoneKind::Button => Some(with_powered(state, true)),
+        RedstoneKind::Repeater => Some(cycle_delay(state)),
+        _ => None,
+    }
+}
+
+/// Power the block at `from` sends into its neighbor at `to`.
+fn emitted_power(block: &BlockDef, state: u8, from: IVec3, to: IVec3) -> u8 {
+    match block.redstone {
+        Some(RedstoneKind::Lever | RedstoneKind::Button | RedstoneKind::PressurePlate)
+            if is_powered(state) =>
+        {
+            MAX_POWER
+        }
+        Some(RedstoneKind::Wire) => power(state),
+        Some(RedstoneKind::Repeater)
+            if is_powered(state) && to == from + repeater_output(state) =>
+        {
+            MAX_POWER
+        }
+        _ => 0,
+    }
+}
+
+/// Strongest power any neighbor sends into `pos`.
+fn received_power(
+    voxel_world: &VoxelWorld,
+    chunk_query: &Query<&mut Chunk>,
+    registry: &BlockRegistry,
+    pos: IVec3,
+) -> u8 {
+    DIRECTIONS
+        .iter()
+        .filter_map(|&offset| {
+            let (voxel, state) = block_at(voxel_world, chunk_query, pos + offset)?;
+            Some(emitted_power(registry.get(voxel), state, pos + offset, pos))
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Repeaters face whoever placed them and take power from that side, so
+/// power flows away from the player.
+fn repeater_output(state: u8) -> IVec3 {
+    -HORIZONTAL_FACINGS[facing(state) as usize]
+}
+
+/// Queues re-evaluation around changed blocks, and of every redstone part in
+/// newly loaded chunks since their saved state may be out of date.
+pub fn queue_redstone_updates(
+    mut block_changes: MessageReader<BlockChanged>,
+    loaded_chunks: Query<(&Chunk, &ChunkPosition), Added<Chunk>>,
+    registry: Res<BlockRegistry>,
+    mut network: ResMut<RedstoneNetwork>,
+) {
+    for change in block_changes.read() {
+        network.mark_with_neighbors(change.pos);
+    }
+
+    for (chunk, position) in loaded_chunks.iter() {
+        let origin = position.0 * CHUNK_SIZE as i32;
+        for x in 0..CHUNK_SIZE {
+            for y in 0..CHUNK_SIZE {
+                for z in 0..CHUNK_SIZE {
+                    if registry.get(chunk.voxels[x][y][z]).redstone.is_some() {
+                        let local = IVec3::new(x as i32, y as i32, z as i32);
+                        network.dirty.insert(origin + local);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Runs one redstone tick: presses and releases plates, fires updates whose
+/// delay is up, then settles every queued position. Positions are visited in
+/// a fixed order so the same circuit always behaves the same way. Pistons
+/// move once the network has settled.
+#[allow(clippy::too_many_arguments)]
+pub fn tick_redstone(
+    mut commands: Commands,
+    mut network: ResMut<RedstoneNetwork>,
+    voxel_world: Res<VoxelWorld>,
+    mut chunk_query: Query<&mut Chunk>,
+    registry: Res<BlockRegistry>,
+    block_assets: Res<BlockAssets>,
+    mut movers: MoverQuery,
+    mut block_changes: MessageWriter<BlockChanged>,
+    sound_assets: Res<SoundAssets>,
+    settings: Res<Settings>,
+) {
+    network.tick += 1;
+
+    let mut pressed = HashSet::new();
+    for (transform, collider) in movers.iter() {
+        let pos = feet_cell(transform, collider);
+        if let Some((voxel, _)) = block_at(&voxel_world, &chunk_query, pos)
+            && registry.get(voxel).redstone == Some(RedstoneKind::PressurePlate)
+        {
+            pressed.insert(pos);
+        }
+    }
+    let toggled: Vec<IVec3> = pressed
+        .symmetric_difference(&network.pressed_plates)
+        .copied()
+        .collect();
+    network.dirty.extend(toggled);
+    network.pressed_plates = pressed;
+
+    let tick = network.tick;
+    let due = network.scheduled.remove(&tick).unwrap_or_default();
+    let mut dirty: Vec<IVec3> = network.dirty.drain().collect();
+    dirty.sort_by_key(|pos| (pos.y, pos.x, pos.z));
+
+    let mut queue: VecDeque<(IVec3, bool)> = due.into_iter().map(|pos| (pos, true)).collect();
+    queue.extend(dirty.into_iter().map(|pos| (pos, false)));
+
+    let mut batch = RemeshBatch::default();
+    let mut piston_moves = Vec::new();
+    let mut door_swings = Vec::new();
+    let mut updates = 0;
+    while let Some((pos, is_due)) = queue.pop_front() {
+        if updates == MAX_UPDATES_PER_TICK {
+            network.dirty.insert(pos);
+            network.dirty.extend(queue.drain(..).map(|(pos, _)| pos));
+            break;
+        }
+        updates += 1;
+
+        let Some((voxel, state)) = block_at(&voxel_world, &chunk_query, pos) else {
+            continue;
+        };
+        let block = registry.get(voxel);
+        let Some(kind) = block.redstone else {
+            continue;
+        };
+
+        let input_from = |offset: IVec3| -> u8 {
+            block_at(&voxel_world, &chunk_query, pos + offset).map_or(0, |(voxel, state)| {
+                emitted_power(registry.get(voxel), state, pos + offset, pos)
+            })
+        };
+        let input = || received_power(&voxel_world, &chunk_query, &registry, pos);
+
+        let (new_voxel, new_state) = match kind {
+            RedstoneKind::Wire => {
+                let level = DIRECTIONS
+                    .iter()
+                    .map(|&offset| {
+                        let level = input_from(offset);
+                        let neighbor = block_at(&voxel_world, &chunk_query, pos + offset);
+                        match neighbor {
+                            Some((voxel, _))
+                                if registry.get(voxel).redstone == Some(RedstoneKind::Wire) =>
+                            {
+                                level.saturating_sub(1)
+                            }
+                            _ => level,
+                        }
+                    })
+                    .max()
+                    .unwrap_or(0);
+                (voxel, with_power(state, level))
+            }
+            RedstoneKind::Repeater => {
+                let powered = input_from(HORIZONTAL_FACINGS[facing(state) as usize]) > 0;
+                if is_due {
+                    // Flip rather than copy the input so pulses shorter than
+                    // the delay still come out the other side
+                    (voxel, with_powered(state, !is_powered(state)))
+                } else {
+                    if powered != is_powered(state) && !network.is_scheduled(pos) {
+                        network.schedule(pos, delay(state));
+                    }
+                    (voxel, state)
+                }
+            }
+            RedstoneKind::Button => {
+                if is_due {
+                    (voxel, with_powered(state, false))
+                } else {
+                    if is_powered(state) && !network.is_scheduled(pos) {
+                        network.schedule(pos, BUTTON_TICKS);
+                    }
+                    (voxel, state)
+                }
+            }
+            RedstoneKind::PressurePlate => {
+                let pressed = network.pressed_plates.contains(&pos);
+                (voxel, with_powered(state, pressed))
+            }
+            RedstoneKind::Lever => (voxel, state),
+            RedstoneKind::Lamp if input() > 0 => (block.variant.unwrap_or(voxel), state),
+            RedstoneKind::LitLamp if input() == 0 => (block.variant.unwrap_or(voxel), state),
+            RedstoneKind::Lamp | RedstoneKind::LitLamp => (voxel, state),
+            RedstoneKind::Piston | RedstoneKind::StickyPiston => {
+                // A piston isn't powered through its own front
+                let front = DIRECTIONS[direction(state) as usize];
+                let powered = DIRECTIONS
+                    .iter()
+                    .any(|&offset| offset != front && input_from(offset) > 0);
+                if powered != is_open(state) {
+                    piston_moves.push((pos, powered));
+                }
+                (voxel, state)
+            }
+            RedstoneKind::Door => {
+                // The powered bit remembers the last power seen, so a door
+                // opened by hand stays open until the power changes
+                let other = other_half(block, pos, state);
+                let powered = input() > 0
+                    || other.is_some_and(|other| {
+                        received_power(&voxel_world, &chunk_query, &registry, other) > 0
+                    });
+                if powered == is_powered(state) {
+                    (voxel, state)
+                } else {
+                    // Both halves swing, but only the lower one is heard
+                    if other.is_none() || state & UPPER == 0 {
+                        door_swings.push(powered);
+                    }
+                    (voxel, with_open(with_powered(state, powered), powered))
+                }
+            }
+        };
+        if (new_voxel, new_state) == (voxel, state) {
+            continue;
+        }
+
+        let chunk_pos = VoxelWorld::world_to_chunk_pos(pos.as_vec3());
+        let Some(mut chunk) = voxel_world
+            .chunks
+            .get(&chunk_pos)
+            .and_then(|entity| chunk_query.get_mut(*entity).ok())
+        else {
+            continue;
+        };
+        chunk.set_block(VoxelWorld::voxel_to_local_pos(pos), new_voxel, new_state);
+        if new_voxel != voxel || block.model.is_shaped_by_state() {
+            batch.mark(pos);
+        }
+        // A repeater that has more switching to do checks itself again
+        if kind == RedstoneKind::Repeater {
+            queue.push_back((pos, false));
+        }
+        for offset in DIRECTIONS {
+            queue.push_back((pos + offset, false));
+        }
+    }
+
+    for open in door_swings {
+        let sound = if open {
+            sound_assets.door_open.clone()
+        } else {
+            sound_assets.door_close.clone()
+        };
+        play_sound(&mut commands, sound, settings.master_volume);
+    }
+
+    for (pos, extend) in piston_moves {
+        let changed = if extend {
+            extend_piston(
+                &mut commands,
+                &voxel_world,
+                &mut chunk_query,
+                &registry,
+                &block_assets,
+                &mut batch,
+                &mut movers,
+                pos,
+            )
+        } else {
+            retract_piston(
+                &mut commands,
+                &voxel_world,
+                &mut chunk_query,
+                &registry,
+                &block_assets,
+                &mut batch,
+                pos,
+            )
+        };
+        for pos in changed {
+            block_changes.write(BlockChanged { pos });
+        }
+    }
+    batch.flush(&mut commands, &voxel_world);
+}
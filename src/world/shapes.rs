@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+
+use crate::world::block_state::{
+    SLAB_DOUBLE, SLAB_MASK, SLAB_TOP, UPPER, direction, facing, is_open, is_powered,
+};
+use crate::world::blocks::BlockModel;
+
+/// Tallest ledge the player walks up without jumping.
+pub const STEP_HEIGHT: f32 = 0.5;
+
+/// Whether a fence or pane joins the neighbor in each horizontal facing.
+pub type Connections = [bool; 4];
+
+/// Axis-aligned box in block space, where a full block spans 0..1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlockBox {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl BlockBox {
+    /// Box from pixel coordinates on the 16x16x16 block grid.
+    const fn px(min: [f32; 3], max: [f32; 3]) -> Self {
+        Self {
+            min: Vec3::new(min[0] / 16.0, min[1] / 16.0, min[2] / 16.0),
+            max: Vec3::new(max[0] / 16.0, max[1] / 16.0, max[2] / 16.0),
+        }
+    }
+
+    /// Rotates a north-facing box clockwise, seen from above, by `facing`
+    /// quarter turns.
+    fn rotated(self, facing: u8) -> Self {
+        let mut rotated = self;
+        for _ in 0..facing % 4 {
+            rotated = Self {
+                min: Vec3::new(1.0 - rotated.max.z, rotated.min.y, rotated.min.x),
+                max: Vec3::new(1.0 - rotated.min.z, rotated.max.y, rotated.max.x),
+            };
+        }
+        rotated
+    }
+
+    /// Turns an upward-pointing box to point along the `direction` index
+    /// into `DIRECTIONS`. Boxes are symmetric enough that mirroring stands in
+    /// for a true rotation.
+    fn pointed(self, direction: u8) -> Self {
+        let map = |p: Vec3| match direction {
+            0 => Vec3::new(p.x, 1.0 - p.y, p.z),
+            2 => Vec3::new(p.x, p.z, 1.0 - p.y),
+            3 => Vec3::new(p.y, p.x, p.z),
+            4 => Vec3::new(p.x, p.z, p.y),
+            5 => Vec3::new(1.0 - p.y, p.x, p.z),
+            _ => p,
+        };
+        let (a, b) = (map(self.min), map(self.max));
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    fn flipped_y(self) -> Self {
+        Self {
+            min: Vec3::new(self.min.x, 1.0 - self.max.y, self.min.z),
+            max: Vec3::new(self.max.x, 1.0 - self.min.y, self.max.z),
+        }
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) / 2.0
+    }
+}
+
+const FULL: BlockBox = BlockBox::px([0.0, 0.0, 0.0], [16.0, 16.0, 16.0]);
+const LOWER_HALF: BlockBox = BlockBox::px([0.0, 0.0, 0.0], [16.0, 8.0, 16.0]);
+const UPPER_HALF: BlockBox = BlockBox::px([0.0, 8.0, 0.0], [16.0, 16.0, 16.0]);
+/// The raised step of north-facing stairs.
+const STAIRS_STEP: BlockBox = BlockBox::px([0.0, 8.0, 0.0], [16.0, 16.0, 8.0]);
+const CARPET: BlockBox = BlockBox::px([0.0, 0.0, 0.0], [16.0, 1.0, 16.0]);
+const FARMLAND: BlockBox = BlockBox::px([0.0, 0.0, 0.0], [16.0, 15.0, 16.0]);
+const FENCE_POST: BlockBox = BlockBox::px([6.0, 0.0, 6.0], [10.0, 16.0, 10.0]);
+/// The two rails joining a fence post to its northern neighbor.
+const FENCE_RAILS: [BlockBox; 2] = [
+    BlockBox::px([7.0, 6.0, 0.0], [9.0, 9.0, 6.0]),
+    BlockBox::px([7.0, 12.0, 0.0], [9.0, 15.0, 6.0]),
+];
+/// Fences are a block and a half tall to collide with, so they can't be
+/// jumped over.
+const FENCE_POST_COLLISION: BlockBox = BlockBox::px([6.0, 0.0, 6.0], [10.0, 24.0, 10.0]);
+const FENCE_ARM_COLLISION: BlockBox = BlockBox::px([6.0, 0.0, 0.0], [10.0, 24.0, 6.0]);
+const PANE_POST: BlockBox = BlockBox::px([7.0, 0.0, 7.0], [9.0, 16.0, 9.0]);
+const PANE_ARM: BlockBox = BlockBox::px([7.0, 0.0, 0.0], [9.0, 16.0, 7.0]);
+const BUTTON: BlockBox = BlockBox::px([5.0, 0.0, 6.0], [11.0, 2.0, 10.0]);
+const BUTTON_PRESSED: BlockBox = BlockBox::px([5.0, 0.0, 6.0], [11.0, 1.0, 10.0]);
+const LEVER_BASE: BlockBox = BlockBox::px([5.0, 0.0, 4.0], [11.0, 3.0, 12.0]);
+/// A north-facing lever's handle, thrown back when off and forward when on.
+const LEVER_HANDLE_OFF: BlockBox = BlockBox::px([7.0, 3.0, 9.0], [9.0, 11.0, 11.0]);
+const LEVER_HANDLE_ON: BlockBox = BlockBox::px([7.0, 3.0, 5.0], [9.0, 11.0, 7.0]);
+/// An upward-pointing piston head: the plate and the arm down to the base.
+const PISTON_PLATE: BlockBox = BlockBox::px([0.0, 12.0, 0.0], [16.0, 16.0, 16.0]);
+const PISTON_ARM: BlockBox = BlockBox::px([6.0, 0.0, 6.0], [10.0, 12.0, 10.0]);
+/// A door or open trapdoor against the north edge of its cell. Doors swing
+/// to the east edge when open.
+const NORTH_PANEL: BlockBox = BlockBox::px([0.0, 0.0, 0.0], [16.0, 16.0, 3.0]);
+const EAST_PANEL: BlockBox = BlockBox::px([13.0, 0.0, 0.0], [16.0, 16.0, 16.0]);
+/// A closed trapdoor in the lower half.
+const TRAPDOOR: BlockBox = BlockBox::px([0.0, 0.0, 0.0], [16.0, 3.0, 16.0]);
+/// A gate running east to west: a post at each end with two rails between
+/// them when shut, or swung north against the posts when open.
+const GATE_POSTS: [BlockBox; 2] = [
+    BlockBox::px([0.0, 5.0, 7.0], [2.0, 16.0, 9.0]),
+    BlockBox::px([14.0, 5.0, 7.0], [16.0, 16.0, 9.0]),
+];
+const GATE_RAILS: [BlockBox; 2] = [
+    BlockBox::px([2.0, 6.0, 7.0], [14.0, 9.0, 9.0]),
+    BlockBox::px([2.0, 12.0, 7.0], [14.0, 15.0, 9.0]),
+];
+const GATE_OPEN_RAILS: [BlockBox; 2] = [
+    BlockBox::px([0.0, 6.0, 1.0], [2.0, 15.0, 7.0]),
+    BlockBox::px([14.0, 6.0, 1.0], [16.0, 15.0, 7.0]),
+];
+/// As tall as a fence to collide with.
+const GATE_COLLISION: BlockBox = BlockBox::px([0.0, 0.0, 6.0], [16.0, 24.0, 10.0]);
+
+/// Boxes the mesher draws for a shaped block.
+pub fn model_boxes(model: BlockModel, state: u8, connections: Connections) -> Vec<BlockBox> {
+    match model {
+        BlockModel::Fence => {
+            let mut boxes = vec![FENCE_POST];
+            for facing in connected_facings(connections) {
+                boxes.extend(FENCE_RAILS.map(|rail| rail.rotated(facing)));
+            }
+            boxes
+        }
+        BlockModel::Button if is_powered(state) => vec![BUTTON_PRESSED.rotated(facing(state))],
+        BlockModel::Button => vec![BUTTON.rotated(facing(state))],
+        BlockModel::Lever => {
+            let handle = if is_powered(state) {
+                LEVER_HANDLE_ON
+            } else {
+                LEVER_HANDLE_OFF
+            };
+            [LEVER_BASE, handle]
+                .map(|b| b.rotated(facing(state)))
+                .to_vec()
+        }
+        BlockModel::Gate => {
+            let rails = if is_open(state) {
+                GATE_OPEN_RAILS
+            } else {
+                GATE_RAILS
+            };
+            GATE_POSTS
+                .iter()
+                .chain(&rails)
+                .map(|b| b.rotated(facing(state)))
+                .collect()
+        }
+        _ => collision_boxes(model, state, connections),
+    }
+}
+
+/// Boxes the chunk collider uses for a shaped block.
+pub fn collision_boxes(model: BlockModel, state: u8, connections: Connections) -> Vec<BlockBox> {
+    match model {
+        BlockModel::Cube => vec![FULL],
+        BlockModel::Slab => match state & SLAB_MASK {
+            SLAB_TOP => vec![UPPER_HALF],
+            SLAB_DOUBLE => vec![FULL],
+            _ => vec![LOWER_HALF],
+        },
+        BlockModel::Stairs => {
+            let boxes = [LOWER_HALF, STAIRS_STEP.rotated(facing(state))];
+            if state & UPPER != 0 {
+                boxes.map(BlockBox::flipped_y).to_vec()
+            } else {
+                boxes.to_vec()
+            }
+        }
+        BlockModel::Fence => {
+            let mut boxes = vec![FENCE_POST_COLLISION];
+            for facing in connected_facings(connections) {
+                boxes.push(FENCE_ARM_COLLISION.rotated(facing));
+            }
+            boxes
+        }
+        BlockModel::Pane => {
+            let mut boxes = vec![PANE_POST];
+            for facing in connected_facings(connections) {
+                boxes.push(PANE_ARM.rotated(facing));
+            }
+            boxes
+        }
+        BlockModel::Carpet => vec![CARPET],
+        BlockModel::Farmland => vec![FARMLAND],
+        BlockModel::PistonHead => [PISTON_PLATE, PISTON_ARM]
+            .map(|b| b.pointed(direction(state)))
+            .to_vec(),
+        BlockModel::Door if is_open(state) => vec![EAST_PANEL.rotated(facing(state))],
+        BlockModel::Door => vec![NORTH_PANEL.rotated(facing(state))],
+        BlockModel::Trapdoor if is_open(state) => vec![NORTH_PANEL.rotated(facing(state))],
+        BlockModel::Trapdoor if state & UPPER != 0 => vec![TRAPDOOR.flipped_y()],
+        BlockModel::Trapdoor => vec![TRAPDOOR],
+        BlockModel::Gate if is_open(state) => Vec::new(),
+        BlockModel::Gate => vec![GATE_COLLISION.rotated(facing(state))],
+        // Small enough to walk through
+        BlockModel::Button | BlockModel::Lever => Vec::new(),
+        BlockModel::Cross | BlockModel::None => Vec::new(),
+    }
+}
+
+fn connected_facings(connections: Connections) -> impl Iterator<Item = u8> {
+    (0..4u8).filter(move |&facing| connections[facing as usize])
+}
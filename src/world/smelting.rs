@@ -0,0 +1,348 @@
+//! Furnaces: what they smelt and burn, from `assets/data/smelting.json`,
+//! and how they progress. Furnaces remember the world play time they were
+//! last updated at, so one in a chunk that was unloaded catches up on the
+//! time it missed once the chunk loads again.
+
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::main_menu::WorldSettings;
+use crate::player::components::InventorySlot;
+use crate::world::block_entity::{BlockEntity, EMPTY_SLOT};
+use crate::world::components::{Chunk, ItemType};
+use crate::world::items::ItemRegistry;
+
+/// Smelting recipes and fuels, relative to the assets directory.
+pub const SMELTING_FILE: &str = "data/smelting.json";
+/// Seconds between furnace updates.
+pub const FURNACE_TICK_SECONDS: f32 = 0.25;
+
+pub const FURNACE_INPUT: usize = 0;
+pub const FURNACE_FUEL: usize = 1;
+pub const FURNACE_OUTPUT: usize = 2;
+
+fn default_cook_seconds() -> f32 {
+    10.0
+}
+
+#[derive(Deserialize)]
+struct RecipeFile {
+    input: String,
+    output: String,
+    #[serde(default = "default_cook_seconds")]
+    seconds: f32,
+}
+
+#[derive(Deserialize)]
+struct FuelFile {
+    item: String,
+    burn_seconds: f32,
+}
+
+#[derive(Deserialize)]
+struct SmeltingFile {
+    recipes: Vec<RecipeFile>,
+    fuels: Vec<FuelFile>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SmeltingRecipe {
+    pub output: ItemType,
+    pub seconds: f32,
+}
+
+#[derive(Resource)]
+pub struct SmeltingRegistry {
+    recipes: HashMap<ItemType, SmeltingRecipe>,
+    /// Seconds one of each fuel item burns for.
+    fuels: HashMap<ItemType, f32>,
+}
+
+impl SmeltingRegistry {
+    pub fn load(items: &ItemRegistry) -> Self {
+        let path = FileAssetReader::get_base_path()
+            .join("assets")
+            .join(SMELTING_FILE);
+        let data = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+        Self::from_json(&data, items).unwrap_or_else(|e| panic!("Invalid {}: {}", SMELTING_FILE, e))
+    }
+
+    pub fn from_json(data: &str, items: &ItemRegistry) -> Result<Self, String> {
+        let file: SmeltingFile = serde_json::from_str(data).map_err(|e| e.to_string())?;
+        let item = |name: &str| {
+            items
+                .by_name(name)
+                .ok_or_else(|| format!("unknown item {}", name))
+        };
+
+        let mut recipes = HashMap::new();
+        for recipe in file.recipes {
+            if recipe.seconds <= 0.0 {
+                return Err(format!(
+                    "recipe for {}: seconds must be positive",
+                    recipe.input
+                ));
+            }
+            let smelted = SmeltingRecipe {
+                output: item(&recipe.output)?,
+                seconds: recipe.seconds,
+            };
+            if recipes.insert(item(&recipe.input)?, smelted).is_some() {
+                return Err(format!("duplicate recipe for {}", recipe.input));
+            }
+        }
+
+        let mut fuels = HashMap::new();
+        for fuel in file.fuels {
+            if fuel.burn_seconds <= 0.0 {
+                return Err(format!("fuel {}: burn_seconds must be positive", fuel.item));
+            }
+            fuels.insert(item(&fuel.item)?, fuel.burn_seconds);
+        }
+
+        Ok(Self { recipes, fuels })
+    }
+
+    pub fn recipe(&self, item: ItemType) -> Option<SmeltingRecipe> {
+        self.recipes.get(&item).copied()
+    }
+
+    pub fn burn_seconds(&self, item: ItemType) -> Option<f32> {
+        self.fuels.get(&item).copied()
+    }
+}
+
+/// A furnace's slots, indexed by the `FURNACE_*` constants, and progress.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Furnace {
+    pub slots: Vec<InventorySlot>,
+    /// Seconds the fuel item burning now has left, out of `burn_total`.
+    pub burn_left: f32,
+    pub burn_total: f32,
+    /// Seconds spent smelting the current input item.
+    pub cook_time: f32,
+    /// `WorldSettings::play_time` the furnace was last brought up to date
+    /// at, or `None` before its first update.
+    #[serde(default)]
+    pub updated_at: Option<f64>,
+}
+
+impl Default for Furnace {
+    fn default() -> Self {
+        Self {
+            slots: vec![EMPTY_SLOT; 3],
+            burn_left: 0.0,
+            burn_total: 0.0,
+            cook_time: 0.0,
+            updated_at: None,
+        }
+    }
+}
+
+fn take_one(slot: &mut InventorySlot) {
+    slot.count = slot.count.saturating_sub(1);
+    if slot.count == 0 {
+        slot.item_type = ItemType::NONE;
+    }
+}
+
+impl Furnace {
+    /// The recipe for the input, if its result fits in the output slot.
+    fn smeltable(
+        &self,
+        smelting: &SmeltingRegistry,
+        items: &ItemRegistry,
+    ) -> Option<SmeltingRecipe> {
+        let recipe = smelting.recipe(self.slots[FURNACE_INPUT].item_type)?;
+        let output = &self.slots[FURNACE_OUTPUT];
+        let fits = output.item_type == ItemType::NONE
+            || (output.item_type == recipe.output && output.count < items.max_stack(recipe.output));
+        fits.then_some(recipe)
+    }
+
+    /// How far the fuel burning now has burned down, from 0 to 1.
+    pub fn burn_fraction(&self) -> f32 {
+        if self.burn_total > 0.0 {
+            (self.burn_left / self.burn_total).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// How far the current input item is through smelting, from 0 to 1.
+    pub fn cook_fraction(&self, smelting: &SmeltingRegistry) -> f32 {
+        smelting
+            .recipe(self.slots[FURNACE_INPUT].item_type)
+            .map_or(0.0, |recipe| {
+                (self.cook_time / recipe.seconds).clamp(0.0, 1.0)
+            })
+    }
+
+    /// Runs the furnace for `seconds`, lighting new fuel whenever the last
+    /// burned out and there is something to smelt.
+    pub fn advance(&mut self, mut seconds: f32, smelting: &SmeltingRegistry, items: &ItemRegistry) {
+        while seconds > 0.0 {
+            let recipe = self.smeltable(smelting, items);
+            if self.burn_left <= 0.0 {
+                let fuel = &mut self.slots[FURNACE_FUEL];
+                match (recipe, smelting.burn_seconds(fuel.item_type)) {
+                    (Some(_), Some(burn_seconds)) => {
+                        take_one(fuel);
+                        self.burn_left = burn_seconds;
+                        self.burn_total = burn_seconds;
+                    }
+                    _ => {
+                        self.cook_time = 0.0;
+                        return;
+                    }
+                }
+            }
+
+            let mut step = seconds.min(self.burn_left);
+            if let Some(recipe) = recipe {
+                step = step.min(recipe.seconds - self.cook_time);
+            }
+            self.burn_left -= step;
+            seconds -= step;
+
+            let Some(recipe) = recipe else {
+                // Fuel burns out with nothing to smelt
+                self.cook_time = 0.0;
+                continue;
+            };
+            self.cook_time += step;
+            if self.cook_time >= recipe.seconds - f32::EPSILON * recipe.seconds {
+                self.cook_time = 0.0;
+                take_one(&mut self.slots[FURNACE_INPUT]);
+                let output = &mut self.slots[FURNACE_OUTPUT];
+                output.item_type = recipe.output;
+                output.count += 1;
+            }
+        }
+    }
+}
+
+/// Counts up the world's play time, which furnaces measure their progress
+/// against.
+pub fn advance_play_time(time: Res<Time>, mut world_settings: ResMut<WorldSettings>) {
+    world_settings.play_time += time.delta_secs_f64();
+}
+
+/// Brings every furnace in a loaded chunk up to the current play time.
+pub fn tick_furnaces(
+    world_settings: Res<WorldSettings>,
+    mut chunk_query: Query<&mut Chunk>,
+    smelting: Res<SmeltingRegistry>,
+    items: Res<ItemRegistry>,
+) {
+    let now = world_settings.play_time;
+    for mut chunk in chunk_query.iter_mut() {
+        if !chunk
+            .block_entities
+            .values()
+            .any(|block_entity| matches!(block_entity, BlockEntity::Furnace(_)))
+        {
+            continue;
+        }
+        for block_entity in chunk.block_entities.values_mut() {
+            let BlockEntity::Furnace(furnace) = block_entity else {
+                continue;
+            };
+            let elapsed = furnace.updated_at.map_or(0.0, |then| (now - then).max(0.0));
+            furnace.updated_at = Some(now);
+            furnace.advance(elapsed as f32, &smelting, &items);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::items::test_items;
+
+    const SMELTING: &str = r#"{
+        "recipes": [{ "input": "iron_ore", "output": "iron_ingot", "seconds": 10 }],
+        "fuels": [
+            { "item": "coal", "burn_seconds": 80 },
+            { "item": "stick", "burn_seconds": 5 }
+        ]
+    }"#;
+
+    fn registries() -> (SmeltingRegistry, ItemRegistry) {
+        let items = test_items(&["iron_ore", "iron_ingot", "coal", "stick"]);
+        let smelting = SmeltingRegistry::from_json(SMELTING, &items).unwrap();
+        (smelting, items)
+    }
+
+    fn furnace(items: &ItemRegistry, input: u32, fuel: &str, fuel_count: u32) -> Furnace {
+        let mut furnace = Furnace::default();
+        furnace.slots[FURNACE_INPUT] = InventorySlot {
+            item_type: items.by_name("iron_ore").unwrap(),
+            count: input,
+            damage: 0,
+        };
+        furnace.slots[FURNACE_FUEL] = InventorySlot {
+            item_type: items.by_name(fuel).unwrap(),
+            count: fuel_count,
+            damage: 0,
+        };
+        furnace
+    }
+
+    #[test]
+    fn catches_up_on_several_items_at_once() {
+        let (smelting, items) = registries();
+        let mut furnace = furnace(&items, 3, "coal", 1);
+
+        furnace.advance(35.0, &smelting, &items);
+
+        let output = &furnace.slots[FURNACE_OUTPUT];
+        assert_eq!(output.item_type, items.by_name("iron_ingot").unwrap());
+        assert_eq!(output.count, 3);
+        assert_eq!(furnace.slots[FURNACE_INPUT].item_type, ItemType::NONE);
+        assert_eq!(furnace.slots[FURNACE_FUEL].count, 0);
+        assert_eq!(furnace.cook_time, 0.0);
+        assert_eq!(furnace.burn_left, 45.0);
+    }
+
+    #[test]
+    fn stops_when_fuel_runs_out() {
+        let (smelting, items) = registries();
+        let mut furnace = furnace(&items, 5, "stick", 3);
+
+        furnace.advance(60.0, &smelting, &items);
+
+        // Three sticks burn for 15 seconds: one item, and half of the next
+        // that is lost when the fire goes out
+        assert_eq!(furnace.slots[FURNACE_OUTPUT].count, 1);
+        assert_eq!(furnace.slots[FURNACE_INPUT].count, 4);
+        assert_eq!(furnace.slots[FURNACE_FUEL].item_type, ItemType::NONE);
+        assert_eq!(furnace.burn_left, 0.0);
+        assert_eq!(furnace.cook_time, 0.0);
+    }
+
+    #[test]
+    fn stops_smelting_when_output_is_full() {
+        let (smelting, items) = registries();
+        let mut furnace = furnace(&items, 10, "coal", 2);
+        furnace.slots[FURNACE_OUTPUT] = InventorySlot {
+            item_type: items.by_name("iron_ingot").unwrap(),
+            count: 62,
+            damage: 0,
+        };
+
+        furnace.advance(200.0, &smelting, &items);
+
+        // Two more fill the stack; the first coal burns out with nothing
+        // left to smelt, and the second is never lit
+        assert_eq!(furnace.slots[FURNACE_OUTPUT].count, 64);
+        assert_eq!(furnace.slots[FURNACE_INPUT].count, 8);
+        assert_eq!(furnace.slots[FURNACE_FUEL].count, 1);
+        assert_eq!(furnace.burn_left, 0.0);
+        assert_eq!(furnace.cook_time, 0.0);
+    }
+}
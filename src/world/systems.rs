@@ -1,17 +1,31 @@
 use bevy::asset::RenderAssetUsages;
-use bevy::image::{ImageLoaderSettings, ImageSampler};
 use bevy::mesh::Indices;
 use bevy::prelude::*;
 use bevy::render::render_resource::PrimitiveTopology;
 use bevy_rapier3d::prelude::*;
 use noise::{NoiseFn, Perlin};
+use std::collections::HashSet;
 
 use crate::main_menu::WorldSettings;
 use crate::player::settings_menu::Settings;
+use crate::resource_pack::{ResourcePacks, ResourcePacksReloaded};
 use crate::world::VoxelWorld;
+use crate::world::animation::BlockAnimations;
+use crate::world::block_state::{AXIS_X, AXIS_Z, HORIZONTAL_FACINGS, age, axis, direction, facing};
+use crate::world::blocks::{
+    BlockDef, BlockMaterialDef, BlockModel, BlockRegistry, FACE_BOTTOM, FACE_FRONT, FACE_SIDE,
+    FACE_TOP, Orientation, RenderLayer,
+};
+use crate::world::colliders::ChunkCollider;
 use crate::world::components::{
-    CHUNK_SIZE, Chunk, ChunkPosition, DespawnChunk, NeedsMeshUpdate, SunLight, VoxelType,
+    CHUNK_SIZE, CelestialBody, Chunk, ChunkPosition, DespawnChunk, NeedsMeshUpdate, SunLight,
+    TranslucentFaces, VoxelType,
 };
+use crate::world::culling::SectionConnectivity;
+use crate::world::items::ItemRegistry;
+use crate::world::persistence::{ChunkModified, load_chunk, save_chunk};
+use crate::world::redstone::RedstoneNetwork;
+use crate::world::shapes::{BlockBox, Connections, model_boxes};
 
 #[derive(Component)]
 #[allow(dead_code)]
@@ -20,17 +34,10 @@ pub struct Block;
 #[derive(Resource)]
 pub struct BlockAssets {
     pub mesh: Handle<Mesh>,
-    pub grass_top_material: Handle<StandardMaterial>,
-    pub grass_side_material: Handle<StandardMaterial>,
-    pub dirt_material: Handle<StandardMaterial>,
-    pub stone_material: Handle<StandardMaterial>,
-    pub coal_ore_material: Handle<StandardMaterial>,
-    pub iron_ore_material: Handle<StandardMaterial>,
-    pub gold_ore_material: Handle<StandardMaterial>,
-    pub diamond_ore_material: Handle<StandardMaterial>,
-    pub bedrock_material: Handle<StandardMaterial>,
-    pub tall_grass_material: Handle<StandardMaterial>,
-    pub wheat_material: Handle<StandardMaterial>,
+    /// One material per entry of `BlockRegistry::materials`, same order.
+    pub block_materials: Vec<Handle<StandardMaterial>>,
+    /// Material for dropped items, indexed by item id.
+    pub item_materials: Vec<Handle<StandardMaterial>>,
     pub destroy_stages: [Handle<StandardMaterial>; 10],
 }
 
@@ -69,10 +76,6 @@ pub fn spawn_chunks_around_player(
     let view_distance = settings.render_distance;
     let (min_chunk_y, max_chunk_y) = world_chunk_y_range();
 
-    let base_height = 14.0;
-    let amplitude = 8.0;
-    let frequency = 0.04;
-
     if initial_meshing.0 {
         return;
     }
@@ -88,8 +91,8 @@ pub fn spawn_chunks_around_player(
                 if let std::collections::hash_map::Entry::Vacant(e) =
                     voxel_world.chunks.entry(chunk_key)
                 {
-                    let chunk_data =
-                        generate_chunk(chunk_key, base_height, amplitude, frequency, &perlin);
+                    let chunk_data = load_chunk(&world_settings.name, chunk_key)
+                        .unwrap_or_else(|| generate_chunk(chunk_key, &perlin));
 
                     let entity = commands
                         .spawn((
@@ -138,11 +141,11 @@ pub fn spawn_chunks_around_player(
                                 let world_x = chunk_key.x as f32 * CHUNK_SIZE as f32 + ox;
                                 let world_z = chunk_key.z as f32 * CHUNK_SIZE as f32 + oz;
 
-                                let noise_val = perlin.get([
-                                    world_x as f64 * frequency as f64,
-                                    world_z as f64 * frequency as f64,
-                                ]);
-                                let height = (base_height + noise_val as f32 * amplitude).round();
+                                let height = terrain_height(
+                                    world_x.floor() as i32,
+                                    world_z.floor() as i32,
+                                    &perlin,
+                                ) as f32;
 
                                 crate::mob::systems::spawn_mob_typed(
                                     &mut commands,
@@ -215,10 +218,22 @@ pub fn despawn_far_chunks(
     }
 }
 
+type DespawningModifiedChunkQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Chunk, &'static ChunkPosition),
+    (With<DespawnChunk>, With<ChunkModified>),
+>;
+
 pub fn apply_chunk_despawns(
     mut commands: Commands,
     chunks: Query<(Entity, Option<&Children>), With<DespawnChunk>>,
+    modified_chunks: DespawningModifiedChunkQuery,
+    world_settings: Res<WorldSettings>,
 ) {
+    for (chunk, position) in modified_chunks.iter() {
+        save_chunk(&world_settings.name, position.0, chunk);
+    }
     for (entity, children) in chunks.iter() {
         if let Some(children) = children {
             for child in children.iter() {
@@ -229,8 +244,388 @@ pub fn apply_chunk_despawns(
     }
 }
 
-pub fn reset_voxel_world(mut voxel_world: ResMut<VoxelWorld>) {
+/// Block id and state at a world voxel position, if its chunk is loaded.
+pub fn block_at(
+    voxel_world: &VoxelWorld,
+    chunk_query: &Query<&mut Chunk>,
+    world_voxel_pos: IVec3,
+) -> Option<(VoxelType, u8)> {
+    let chunk_pos = VoxelWorld::world_to_chunk_pos(world_voxel_pos.as_vec3());
+    let local_pos = VoxelWorld::voxel_to_local_pos(world_voxel_pos);
+    let chunk = voxel_world
+        .chunks
+        .get(&chunk_pos)
+        .and_then(|entity| chunk_query.get(*entity).ok())?;
+    Some((chunk.get_voxel(local_pos), chunk.get_state(local_pos)))
+}
+
+/// Sets a voxel anywhere in the loaded world and queues the remesh. Returns
+/// false when its chunk isn't loaded.
+pub fn set_block_at(
+    commands: &mut Commands,
+    voxel_world: &VoxelWorld,
+    chunk_query: &mut Query<&mut Chunk>,
+    world_voxel_pos: IVec3,
+    voxel: VoxelType,
+    state: u8,
+) -> bool {
+    let chunk_pos = VoxelWorld::world_to_chunk_pos(world_voxel_pos.as_vec3());
+    let local_pos = VoxelWorld::voxel_to_local_pos(world_voxel_pos);
+    let Some(&chunk_entity) = voxel_world.chunks.get(&chunk_pos) else {
+        return false;
+    };
+    let Ok(mut chunk) = chunk_query.get_mut(chunk_entity) else {
+        return false;
+    };
+    chunk.set_block(local_pos, voxel, state);
+    commands.entity(chunk_entity).insert(NeedsMeshUpdate);
+    mark_neighbor_chunks(commands, voxel_world, chunk_pos, local_pos);
+    true
+}
+
+/// Queues remeshing of the chunks bordering `local_pos`, whose faces against
+/// it may have changed.
+pub fn mark_neighbor_chunks(
+    commands: &mut Commands,
+    voxel_world: &VoxelWorld,
+    chunk_pos: IVec3,
+    local_pos: IVec3,
+) {
+    for offset in neighbor_chunk_offsets(local_pos) {
+        if let Some(entity) = voxel_world.chunks.get(&(chunk_pos + offset)) {
+            commands.entity(*entity).insert(NeedsMeshUpdate);
+        }
+    }
+}
+
+/// Offsets of the chunks sharing a face with a voxel on the chunk boundary.
+fn neighbor_chunk_offsets(local_pos: IVec3) -> Vec<IVec3> {
+    let mut neighbors = Vec::new();
+    if local_pos.x == 0 {
+        neighbors.push(IVec3::new(-1, 0, 0));
+    } else if local_pos.x == CHUNK_SIZE as i32 - 1 {
+        neighbors.push(IVec3::new(1, 0, 0));
+    }
+    if local_pos.y == 0 {
+        neighbors.push(IVec3::new(0, -1, 0));
+    } else if local_pos.y == CHUNK_SIZE as i32 - 1 {
+        neighbors.push(IVec3::new(0, 1, 0));
+    }
+    if local_pos.z == 0 {
+        neighbors.push(IVec3::new(0, 0, -1));
+    } else if local_pos.z == CHUNK_SIZE as i32 - 1 {
+        neighbors.push(IVec3::new(0, 0, 1));
+    }
+    neighbors
+}
+
+/// Chunks touched by a burst of voxel edits, such as a blast, so each is
+/// queued for remeshing once instead of once per edited voxel.
+#[derive(Default)]
+pub struct RemeshBatch {
+    chunks: HashSet<IVec3>,
+}
+
+impl RemeshBatch {
+    pub fn mark(&mut self, world_voxel_pos: IVec3) {
+        let chunk_pos = VoxelWorld::world_to_chunk_pos(world_voxel_pos.as_vec3());
+        let local_pos = VoxelWorld::voxel_to_local_pos(world_voxel_pos);
+        self.chunks.insert(chunk_pos);
+        for offset in neighbor_chunk_offsets(local_pos) {
+            self.chunks.insert(chunk_pos + offset);
+        }
+    }
+
+    pub fn flush(self, commands: &mut Commands, voxel_world: &VoxelWorld) {
+        for chunk_pos in self.chunks {
+            if let Some(entity) = voxel_world.chunks.get(&chunk_pos) {
+                commands.entity(*entity).insert(NeedsMeshUpdate);
+            }
+        }
+    }
+}
+
+pub fn reset_voxel_world(
+    mut voxel_world: ResMut<VoxelWorld>,
+    mut redstone: ResMut<RedstoneNetwork>,
+) {
     voxel_world.chunks.clear();
+    *redstone = RedstoneNetwork::default();
+}
+
+#[derive(Default)]
+struct MeshBuffers {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    indices: Vec<u32>,
+}
+
+impl MeshBuffers {
+    fn add_face(&mut self, vertices: [[f32; 3]; 4], normal: [f32; 3]) {
+        self.add_face_with_uvs(
+            vertices,
+            normal,
+            [[1.0, 1.0], [0.0, 1.0], [0.0, 0.0], [1.0, 0.0]],
+        );
+    }
+
+    fn add_face_with_uvs(&mut self, vertices: [[f32; 3]; 4], normal: [f32; 3], uvs: [[f32; 2]; 4]) {
+        let face = (self.positions.len() / 4) as u32;
+        for v in vertices {
+            self.positions.push(v);
+        }
+        self.normals
+            .extend_from_slice(&[normal, normal, normal, normal]);
+        self.uvs.extend_from_slice(&uvs);
+        self.indices.extend_from_slice(&quad_indices(face));
+    }
+
+    fn face_centers(&self) -> Vec<Vec3> {
+        self.positions
+            .chunks_exact(4)
+            .map(|quad| quad.iter().map(|&v| Vec3::from(v)).sum::<Vec3>() / 4.0)
+            .collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    fn into_mesh(self) -> Mesh {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs);
+        mesh.insert_indices(Indices::U32(self.indices));
+        mesh
+    }
+}
+
+/// The two triangles of the `face`th quad in a buffer.
+fn quad_indices(face: u32) -> [u32; 6] {
+    let start = face * 4;
+    [start, start + 1, start + 2, start, start + 2, start + 3]
+}
+
+/// Cube faces in `(neighbor offset, normal, unit-cube vertices)` form. The
+/// first two are the top and bottom faces, the rest are sides.
+const CUBE_FACES: [(IVec3, [f32; 3], [[f32; 3]; 4]); 6] = [
+    (
+        IVec3::new(0, 1, 0),
+        [0.0, 1.0, 0.0],
+        [
+            [0.0, 1.0, 0.0],
+            [0.0, 1.0, 1.0],
+            [1.0, 1.0, 1.0],
+            [1.0, 1.0, 0.0],
+        ],
+    ),
+    (
+        IVec3::new(0, -1, 0),
+        [0.0, -1.0, 0.0],
+        [
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 0.0, 1.0],
+        ],
+    ),
+    (
+        IVec3::new(1, 0, 0),
+        [1.0, 0.0, 0.0],
+        [
+            [1.0, 0.0, 1.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [1.0, 1.0, 1.0],
+        ],
+    ),
+    (
+        IVec3::new(-1, 0, 0),
+        [-1.0, 0.0, 0.0],
+        [
+            [0.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 1.0, 1.0],
+            [0.0, 1.0, 0.0],
+        ],
+    ),
+    (
+        IVec3::new(0, 0, 1),
+        [0.0, 0.0, 1.0],
+        [
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0],
+            [1.0, 1.0, 1.0],
+            [0.0, 1.0, 1.0],
+        ],
+    ),
+    (
+        IVec3::new(0, 0, -1),
+        [0.0, 0.0, -1.0],
+        [
+            [1.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 1.0, 0.0],
+        ],
+    ),
+];
+
+/// The two diagonal planes of a cross model, each as a front and back quad.
+const CROSS_FACES: [[[f32; 3]; 4]; 4] = [
+    [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 1.0],
+        [1.0, 1.0, 1.0],
+        [0.0, 1.0, 0.0],
+    ],
+    [
+        [1.0, 0.0, 1.0],
+        [0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [1.0, 1.0, 1.0],
+    ],
+    [
+        [1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [0.0, 1.0, 1.0],
+        [1.0, 1.0, 0.0],
+    ],
+    [
+        [0.0, 0.0, 1.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [0.0, 1.0, 1.0],
+    ],
+];
+
+fn offset_face(pos: IVec3, vertices: [[f32; 3]; 4]) -> [[f32; 3]; 4] {
+    vertices.map(|v| {
+        [
+            pos.x as f32 + v[0],
+            pos.y as f32 + v[1],
+            pos.z as f32 + v[2],
+        ]
+    })
+}
+
+/// Shrinks a `CUBE_FACES` face onto `block_box`. UVs follow the vertex
+/// positions, so a half-height side shows half of the texture rather than
+/// all of it squashed.
+fn box_face(
+    pos: IVec3,
+    block_box: &BlockBox,
+    face_index: usize,
+    vertices: [[f32; 3]; 4],
+) -> ([[f32; 3]; 4], [[f32; 2]; 4]) {
+    let size = block_box.max - block_box.min;
+    let vertices = vertices.map(|v| block_box.min + Vec3::from(v) * size);
+    // Same mapping as the fixed UVs of a full cube face
+    let uvs = vertices.map(|v| match face_index {
+        0 => [1.0 - v.z, 1.0 - v.x],
+        1 => [v.z, 1.0 - v.x],
+        2 => [v.z, 1.0 - v.y],
+        3 => [1.0 - v.z, 1.0 - v.y],
+        4 => [1.0 - v.x, 1.0 - v.y],
+        _ => [v.x, 1.0 - v.y],
+    });
+    let vertices = vertices.map(|v| (v + pos.as_vec3()).to_array());
+    (vertices, uvs)
+}
+
+/// Whether face `face_index` of `block_box` lies on the block's boundary,
+/// where a neighbor can hide it.
+fn box_face_on_boundary(block_box: &BlockBox, face_index: usize) -> bool {
+    match face_index {
+        0 => block_box.max.y >= 1.0,
+        1 => block_box.min.y <= 0.0,
+        2 => block_box.max.x >= 1.0,
+        3 => block_box.min.x <= 0.0,
+        4 => block_box.max.z >= 1.0,
+        _ => block_box.min.z <= 0.0,
+    }
+}
+
+/// `CUBE_FACES` index of the side each horizontal facing points out of.
+const FACING_FACES: [usize; 4] = [5, 2, 4, 3];
+/// `CUBE_FACES` index of the side each of `DIRECTIONS` points out of.
+const DIRECTION_FACES: [usize; 6] = [1, 0, 5, 2, 4, 3];
+
+/// Material for a cube face given the block's state, and whether the texture
+/// is turned a quarter so the grain of a sideways log runs along it.
+fn face_material(block: &BlockDef, face_index: usize, state: u8) -> (usize, bool) {
+    let slot = match face_index {
+        0 => FACE_TOP,
+        1 => FACE_BOTTOM,
+        _ => FACE_SIDE,
+    };
+    let (slot, rotated) = match block.orientation {
+        Orientation::None => (slot, false),
+        Orientation::Facing if FACING_FACES[facing(state) as usize] == face_index => {
+            (FACE_FRONT, false)
+        }
+        Orientation::Facing => (slot, false),
+        Orientation::Direction => {
+            let front = DIRECTION_FACES[direction(state) as usize];
+            if face_index == front {
+                (FACE_FRONT, false)
+            } else if face_index == front ^ 1 {
+                // Faces come in opposite pairs
+                (FACE_BOTTOM, false)
+            } else {
+                (FACE_SIDE, false)
+            }
+        }
+        Orientation::Axis => match (axis(state), face_index) {
+            (AXIS_X, 2) | (AXIS_Z, 4) => (FACE_TOP, false),
+            (AXIS_X, 3) | (AXIS_Z, 5) => (FACE_BOTTOM, false),
+            (AXIS_X, 4 | 5) | (AXIS_Z, _) => (FACE_SIDE, true),
+            (AXIS_X, _) => (FACE_SIDE, false),
+            _ => (slot, false),
+        },
+    };
+    (block.face_materials[slot], rotated)
+}
+
+/// The voxel at `local`, relative to the chunk at `chunk_pos`, looking into
+/// the neighboring chunk when it's outside. Air when that isn't loaded.
+pub fn chunk_voxel_at(
+    voxel_world: &VoxelWorld,
+    chunk_lookup: &Query<&Chunk>,
+    chunk: &Chunk,
+    chunk_pos: IVec3,
+    local: IVec3,
+) -> VoxelType {
+    let size = CHUNK_SIZE as i32;
+    if local.cmpge(IVec3::ZERO).all() && local.cmplt(IVec3::splat(size)).all() {
+        return chunk.get_voxel(local);
+    }
+    let world_voxel_pos = chunk_pos * size + local;
+    let neighbor_chunk_pos = VoxelWorld::world_to_chunk_pos(world_voxel_pos.as_vec3());
+    voxel_world
+        .chunks
+        .get(&neighbor_chunk_pos)
+        .and_then(|entity| chunk_lookup.get(*entity).ok())
+        .map(|neighbor| neighbor.get_voxel(VoxelWorld::voxel_to_local_pos(world_voxel_pos)))
+        .unwrap_or(VoxelType::AIR)
+}
+
+/// Which horizontal neighbors a fence or pane of `model` at `pos` joins.
+pub fn connections_at(
+    registry: &BlockRegistry,
+    model: BlockModel,
+    pos: IVec3,
+    voxel_at: impl Fn(IVec3) -> VoxelType,
+) -> Connections {
+    HORIZONTAL_FACINGS.map(|offset| {
+        let neighbor = voxel_at(pos + offset);
+        model.connects_to(registry.get(neighbor).model) || registry.is_opaque(neighbor)
+    })
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -238,7 +633,9 @@ pub fn update_chunk_mesh(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     block_assets: Res<BlockAssets>,
+    registry: Res<BlockRegistry>,
     children_query: Query<&Children>,
+    collider_query: Query<(), With<ChunkCollider>>,
     voxel_world: Res<VoxelWorld>,
     chunk_lookup: Query<&Chunk>,
     mut initial_meshing: ResMut<InitialChunkMeshing>,
@@ -261,329 +658,83 @@ pub fn update_chunk_mesh(
         }
         // log::info!("Updating mesh for chunk entity: {:?}", entity);
 
-        #[derive(Default)]
-        struct MeshBuffers {
-            positions: Vec<[f32; 3]>,
-            normals: Vec<[f32; 3]>,
-            uvs: Vec<[f32; 2]>,
-            indices: Vec<u32>,
-        }
-
-        impl MeshBuffers {
-            fn add_face(&mut self, vertices: [[f32; 3]; 4], _normal: [f32; 3]) {
-                let start_idx = self.positions.len() as u32;
-                for v in vertices {
-                    self.positions.push(v);
-                }
-                self.normals
-                    .extend_from_slice(&[_normal, _normal, _normal, _normal]);
-                self.uvs
-                    .extend_from_slice(&[[1.0, 1.0], [0.0, 1.0], [0.0, 0.0], [1.0, 0.0]]);
-                self.indices.extend_from_slice(&[
-                    start_idx,
-                    start_idx + 1,
-                    start_idx + 2,
-                    start_idx,
-                    start_idx + 2,
-                    start_idx + 3,
-                ]);
-            }
-
-            fn is_empty(&self) -> bool {
-                self.positions.is_empty()
-            }
-
-            fn into_mesh(self) -> Mesh {
-                let mut mesh = Mesh::new(
-                    PrimitiveTopology::TriangleList,
-                    RenderAssetUsages::RENDER_WORLD,
-                );
-                mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
-                mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals);
-                mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs);
-                mesh.insert_indices(Indices::U32(self.indices));
-                mesh
-            }
-        }
-
-        let mut combined = MeshBuffers::default();
-        let mut grass_top = MeshBuffers::default();
-        let mut grass_side = MeshBuffers::default();
-        let mut dirt = MeshBuffers::default();
-        let mut stone = MeshBuffers::default();
-        let mut coal_ore = MeshBuffers::default();
-        let mut iron_ore = MeshBuffers::default();
-        let mut gold_ore = MeshBuffers::default();
-        let mut diamond_ore = MeshBuffers::default();
-        let mut bedrock = MeshBuffers::default();
-        let mut tall_grass = MeshBuffers::default();
-        let mut tall_grass_collision = MeshBuffers::default();
+        // One buffer per registry material, so each becomes a single draw
+        let mut buffers: Vec<MeshBuffers> = (0..registry.materials.len())
+            .map(|_| MeshBuffers::default())
+            .collect();
+
+        let voxel_at = |neighbor_pos: IVec3| {
+            chunk_voxel_at(
+                &voxel_world,
+                &chunk_lookup,
+                chunk,
+                chunk_pos.0,
+                neighbor_pos,
+            )
+        };
 
         for x in 0..CHUNK_SIZE {
             for y in 0..CHUNK_SIZE {
                 for z in 0..CHUNK_SIZE {
                     let pos = IVec3::new(x as i32, y as i32, z as i32);
                     let voxel = chunk.get_voxel(pos);
-                    if voxel == VoxelType::Air {
+                    let block = registry.get(voxel);
+                    if block.model == BlockModel::None {
                         continue;
                     }
 
-                    if voxel == VoxelType::TallGrass {
-                        let visual_faces = [
-                            [
-                                [pos.x as f32, pos.y as f32, pos.z as f32],
-                                [pos.x as f32 + 1.0, pos.y as f32, pos.z as f32 + 1.0],
-                                [pos.x as f32 + 1.0, pos.y as f32 + 1.0, pos.z as f32 + 1.0],
-                                [pos.x as f32, pos.y as f32 + 1.0, pos.z as f32],
-                            ],
-                            [
-                                [pos.x as f32 + 1.0, pos.y as f32, pos.z as f32 + 1.0],
-                                [pos.x as f32, pos.y as f32, pos.z as f32],
-                                [pos.x as f32, pos.y as f32 + 1.0, pos.z as f32],
-                                [pos.x as f32 + 1.0, pos.y as f32 + 1.0, pos.z as f32 + 1.0],
-                            ],
-                            [
-                                [pos.x as f32 + 1.0, pos.y as f32, pos.z as f32],
-                                [pos.x as f32, pos.y as f32, pos.z as f32 + 1.0],
-                                [pos.x as f32, pos.y as f32 + 1.0, pos.z as f32 + 1.0],
-                                [pos.x as f32 + 1.0, pos.y as f32 + 1.0, pos.z as f32],
-                            ],
-                            [
-                                [pos.x as f32, pos.y as f32, pos.z as f32 + 1.0],
-                                [pos.x as f32 + 1.0, pos.y as f32, pos.z as f32],
-                                [pos.x as f32 + 1.0, pos.y as f32 + 1.0, pos.z as f32],
-                                [pos.x as f32, pos.y as f32 + 1.0, pos.z as f32 + 1.0],
-                            ],
-                        ];
-                        for face in visual_faces {
-                            tall_grass.add_face(face, [0.0, 0.0, 0.0]);
+                    if block.model == BlockModel::Cross {
+                        let material = match &block.crop {
+                            Some(crop) => {
+                                let stage = age(chunk.get_state(pos)).min(crop.last_stage());
+                                crop.stage_materials[stage as usize]
+                            }
+                            None => block.face_materials[FACE_SIDE],
+                        };
+                        let buffer = &mut buffers[material];
+                        for vertices in CROSS_FACES {
+                            buffer.add_face(offset_face(pos, vertices), [0.0, 0.0, 0.0]);
                         }
+                        continue;
+                    }
 
-                        // Add full cube for collision (Sensor)
-                        let cube_faces = [
-                            (
-                                [0.0, 1.0, 0.0],
-                                [
-                                    [0.0, 1.0, 0.0],
-                                    [0.0, 1.0, 1.0],
-                                    [1.0, 1.0, 1.0],
-                                    [1.0, 1.0, 0.0],
-                                ],
-                            ),
-                            (
-                                [0.0, -1.0, 0.0],
-                                [
-                                    [0.0, 0.0, 0.0],
-                                    [1.0, 0.0, 0.0],
-                                    [1.0, 0.0, 1.0],
-                                    [0.0, 0.0, 1.0],
-                                ],
-                            ),
-                            (
-                                [1.0, 0.0, 0.0],
-                                [
-                                    [1.0, 0.0, 1.0],
-                                    [1.0, 0.0, 0.0],
-                                    [1.0, 1.0, 0.0],
-                                    [1.0, 1.0, 1.0],
-                                ],
-                            ),
-                            (
-                                [-1.0, 0.0, 0.0],
-                                [
-                                    [0.0, 0.0, 0.0],
-                                    [0.0, 0.0, 1.0],
-                                    [0.0, 1.0, 1.0],
-                                    [0.0, 1.0, 0.0],
-                                ],
-                            ),
-                            (
-                                [0.0, 0.0, 1.0],
-                                [
-                                    [0.0, 0.0, 1.0],
-                                    [1.0, 0.0, 1.0],
-                                    [1.0, 1.0, 1.0],
-                                    [0.0, 1.0, 1.0],
-                                ],
-                            ),
-                            (
-                                [0.0, 0.0, -1.0],
-                                [
-                                    [1.0, 0.0, 0.0],
-                                    [0.0, 0.0, 0.0],
-                                    [0.0, 1.0, 0.0],
-                                    [1.0, 1.0, 0.0],
-                                ],
-                            ),
-                        ];
-                        for (normal, vertices) in cube_faces {
-                            let face = [
-                                [
-                                    pos.x as f32 + vertices[0][0],
-                                    pos.y as f32 + vertices[0][1],
-                                    pos.z as f32 + vertices[0][2],
-                                ],
-                                [
-                                    pos.x as f32 + vertices[1][0],
-                                    pos.y as f32 + vertices[1][1],
-                                    pos.z as f32 + vertices[1][2],
-                                ],
-                                [
-                                    pos.x as f32 + vertices[2][0],
-                                    pos.y as f32 + vertices[2][1],
-                                    pos.z as f32 + vertices[2][2],
-                                ],
-                                [
-                                    pos.x as f32 + vertices[3][0],
-                                    pos.y as f32 + vertices[3][1],
-                                    pos.z as f32 + vertices[3][2],
-                                ],
-                            ];
-                            tall_grass_collision.add_face(face, normal);
+                    if block.model.is_shaped() {
+                        let state = chunk.get_state(pos);
+                        let connections = connections_at(&registry, block.model, pos, voxel_at);
+
+                        for block_box in model_boxes(block.model, state, connections) {
+                            for (face_index, (offset, normal, vertices)) in
+                                CUBE_FACES.iter().enumerate()
+                            {
+                                if box_face_on_boundary(&block_box, face_index)
+                                    && registry.is_opaque(voxel_at(pos + *offset))
+                                {
+                                    continue;
+                                }
+                                let (vertices, uvs) =
+                                    box_face(pos, &block_box, face_index, *vertices);
+                                let (material, _) = face_material(block, face_index, state);
+                                buffers[material].add_face_with_uvs(vertices, *normal, uvs);
+                            }
                         }
                         continue;
                     }
 
-                    let faces = [
-                        (
-                            IVec3::new(0, 1, 0),
-                            [0.0, 1.0, 0.0],
-                            [
-                                [0.0, 1.0, 0.0],
-                                [0.0, 1.0, 1.0],
-                                [1.0, 1.0, 1.0],
-                                [1.0, 1.0, 0.0],
-                            ],
-                        ),
-                        (
-                            IVec3::new(0, -1, 0),
-                            [0.0, -1.0, 0.0],
-                            [
-                                [0.0, 0.0, 1.0],
-                                [0.0, 0.0, 0.0],
-                                [1.0, 0.0, 0.0],
-                                [1.0, 0.0, 1.0],
-                            ],
-                        ),
-                        (
-                            IVec3::new(1, 0, 0),
-                            [1.0, 0.0, 0.0],
-                            [
-                                [1.0, 0.0, 1.0],
-                                [1.0, 0.0, 0.0],
-                                [1.0, 1.0, 0.0],
-                                [1.0, 1.0, 1.0],
-                            ],
-                        ),
-                        (
-                            IVec3::new(-1, 0, 0),
-                            [-1.0, 0.0, 0.0],
-                            [
-                                [0.0, 0.0, 0.0],
-                                [0.0, 0.0, 1.0],
-                                [0.0, 1.0, 1.0],
-                                [0.0, 1.0, 0.0],
-                            ],
-                        ),
-                        (
-                            IVec3::new(0, 0, 1),
-                            [0.0, 0.0, 1.0],
-                            [
-                                [0.0, 0.0, 1.0],
-                                [1.0, 0.0, 1.0],
-                                [1.0, 1.0, 1.0],
-                                [0.0, 1.0, 1.0],
-                            ],
-                        ),
-                        (
-                            IVec3::new(0, 0, -1),
-                            [0.0, 0.0, -1.0],
-                            [
-                                [1.0, 0.0, 0.0],
-                                [0.0, 0.0, 0.0],
-                                [0.0, 1.0, 0.0],
-                                [1.0, 1.0, 0.0],
-                            ],
-                        ),
-                    ];
-
-                    for (offset, normal, vertices) in faces {
-                        let neighbor_pos = pos + offset;
-                        let neighbor_voxel = if neighbor_pos.x >= 0
-                            && neighbor_pos.x < CHUNK_SIZE as i32
-                            && neighbor_pos.y >= 0
-                            && neighbor_pos.y < CHUNK_SIZE as i32
-                            && neighbor_pos.z >= 0
-                            && neighbor_pos.z < CHUNK_SIZE as i32
-                        {
-                            chunk.get_voxel(neighbor_pos)
+                    let state = chunk.get_state(pos);
+                    for (face_index, (offset, normal, vertices)) in CUBE_FACES.iter().enumerate() {
+                        if registry.hides_face(voxel, voxel_at(pos + *offset)) {
+                            continue;
+                        }
+                        let (material, rotated) = face_material(block, face_index, state);
+                        let vertices = offset_face(pos, *vertices);
+                        if rotated {
+                            buffers[material].add_face_with_uvs(
+                                vertices,
+                                *normal,
+                                [[0.0, 1.0], [0.0, 0.0], [1.0, 0.0], [1.0, 1.0]],
+                            );
                         } else {
-                            let world_voxel_pos = chunk_pos.0 * CHUNK_SIZE as i32 + neighbor_pos;
-                            let neighbor_chunk_pos =
-                                VoxelWorld::world_to_chunk_pos(world_voxel_pos.as_vec3());
-                            let neighbor_local_pos =
-                                VoxelWorld::voxel_to_local_pos(world_voxel_pos);
-                            voxel_world
-                                .chunks
-                                .get(&neighbor_chunk_pos)
-                                .and_then(|entity| chunk_lookup.get(*entity).ok())
-                                .map(|neighbor_chunk| neighbor_chunk.get_voxel(neighbor_local_pos))
-                                .unwrap_or(VoxelType::Air)
-                        };
-
-                        if neighbor_voxel == VoxelType::Air
-                            || neighbor_voxel == VoxelType::TallGrass
-                        {
-                            let face = [
-                                [
-                                    pos.x as f32 + vertices[0][0],
-                                    pos.y as f32 + vertices[0][1],
-                                    pos.z as f32 + vertices[0][2],
-                                ],
-                                [
-                                    pos.x as f32 + vertices[1][0],
-                                    pos.y as f32 + vertices[1][1],
-                                    pos.z as f32 + vertices[1][2],
-                                ],
-                                [
-                                    pos.x as f32 + vertices[2][0],
-                                    pos.y as f32 + vertices[2][1],
-                                    pos.z as f32 + vertices[2][2],
-                                ],
-                                [
-                                    pos.x as f32 + vertices[3][0],
-                                    pos.y as f32 + vertices[3][1],
-                                    pos.z as f32 + vertices[3][2],
-                                ],
-                            ];
-
-                            combined.add_face(face, normal);
-                            if voxel == VoxelType::Grass {
-                                if normal[1] > 0.5 {
-                                    grass_top.add_face(face, normal);
-                                } else if normal[1] < -0.5 {
-                                    dirt.add_face(face, normal);
-                                } else {
-                                    grass_side.add_face(face, normal);
-                                }
-                            } else {
-                                let buffers = match voxel {
-                                    VoxelType::Dirt => Some(&mut dirt),
-                                    VoxelType::Stone => Some(&mut stone),
-                                    VoxelType::CoalOre => Some(&mut coal_ore),
-                                    VoxelType::IronOre => Some(&mut iron_ore),
-                                    VoxelType::GoldOre => Some(&mut gold_ore),
-                                    VoxelType::DiamondOre => Some(&mut diamond_ore),
-                                    VoxelType::Bedrock => Some(&mut bedrock),
-                                    VoxelType::Grass | VoxelType::Air | VoxelType::TallGrass => {
-                                        None
-                                    }
-                                };
-
-                                if let Some(buffers) = buffers {
-                                    buffers.add_face(face, normal);
-                                }
-                            }
+                            buffers[material].add_face(vertices, *normal);
                         }
                     }
                 }
@@ -591,7 +742,11 @@ pub fn update_chunk_mesh(
         }
 
         if let Ok(children) = children_query.get(entity) {
-            for child in children.iter() {
+            // Colliders are kept up to date separately, by `colliders`
+            for child in children
+                .iter()
+                .filter(|child| !collider_query.contains(*child))
+            {
                 if let Ok(mut child_commands) = commands.get_entity(child) {
                     child_commands.despawn();
                 }
@@ -600,223 +755,51 @@ pub fn update_chunk_mesh(
         if let Ok(mut entity_commands) = commands.get_entity(entity) {
             entity_commands.remove::<Mesh3d>();
             entity_commands.remove::<MeshMaterial3d<StandardMaterial>>();
+            entity_commands.insert(SectionConnectivity::compute(chunk, &registry));
         } else {
             continue;
         }
 
-        if combined.is_empty() && tall_grass_collision.is_empty() {
+        if buffers.iter().all(MeshBuffers::is_empty) {
             if let Some(mesh) = existing_mesh {
                 meshes.remove(mesh.0.id());
             }
             if let Ok(mut entity_commands) = commands.get_entity(entity) {
-                entity_commands.remove::<Collider>();
                 entity_commands.remove::<NeedsMeshUpdate>();
             }
             continue;
         }
 
-        // Use voxel grid collider for perfect block alignment (no edge catching!)
-        let mut solid_voxels = Vec::new();
-        for x in 0..CHUNK_SIZE {
-            for y in 0..CHUNK_SIZE {
-                for z in 0..CHUNK_SIZE {
-                    let voxel = chunk.get_voxel(IVec3::new(x as i32, y as i32, z as i32));
-                    if voxel != VoxelType::Air && voxel != VoxelType::TallGrass {
-                        solid_voxels.push(IVec3::new(x as i32, y as i32, z as i32));
-                    }
-                }
-            }
-        }
-        let collider = if solid_voxels.is_empty() {
-            return;
-        } else {
-            Collider::voxels(Vec3::ONE, &solid_voxels)
-        };
-        if let Ok(mut entity_commands) = commands.get_entity(entity) {
-            entity_commands.insert((collider, Visibility::Visible));
-
-            if !tall_grass_collision.is_empty() {
-                let sensor_mesh = tall_grass_collision.into_mesh();
-                let sensor_collider = Collider::from_bevy_mesh(
-                    &sensor_mesh,
-                    &ComputedColliderShape::TriMesh(TriMeshFlags::default()),
-                )
-                .unwrap();
-                entity_commands.with_children(|parent| {
-                    parent.spawn((
-                        sensor_collider,
-                        Sensor,
-                        Transform::default(),
-                        GlobalTransform::default(),
-                    ));
-                });
-            }
-        } else {
-            continue;
-        }
-
-        let grass_top_mesh = if grass_top.is_empty() {
-            None
-        } else {
-            Some(grass_top.into_mesh())
-        };
-        let grass_side_mesh = if grass_side.is_empty() {
-            None
-        } else {
-            Some(grass_side.into_mesh())
-        };
-        let dirt_mesh = if dirt.is_empty() {
-            None
-        } else {
-            Some(dirt.into_mesh())
-        };
-        let stone_mesh = if stone.is_empty() {
-            None
-        } else {
-            Some(stone.into_mesh())
-        };
-        let coal_ore_mesh = if coal_ore.is_empty() {
-            None
-        } else {
-            Some(coal_ore.into_mesh())
-        };
-        let iron_ore_mesh = if iron_ore.is_empty() {
-            None
-        } else {
-            Some(iron_ore.into_mesh())
-        };
-        let gold_ore_mesh = if gold_ore.is_empty() {
-            None
-        } else {
-            Some(gold_ore.into_mesh())
-        };
-        let diamond_ore_mesh = if diamond_ore.is_empty() {
-            None
-        } else {
-            Some(diamond_ore.into_mesh())
-        };
-        let bedrock_mesh = if bedrock.is_empty() {
-            None
-        } else {
-            Some(bedrock.into_mesh())
-        };
-        let tall_grass_mesh = if tall_grass.is_empty() {
-            None
-        } else {
-            Some(tall_grass.into_mesh())
-        };
-
         if let Ok(mut entity_commands) = commands.get_entity(entity) {
             entity_commands.with_children(|parent| {
-                if let Some(mesh) = grass_top_mesh {
-                    let handle = meshes.add(mesh);
-                    parent.spawn((
-                        Mesh3d(handle),
-                        MeshMaterial3d(block_assets.grass_top_material.clone()),
-                        Transform::default(),
-                        GlobalTransform::default(),
-                        Visibility::Visible,
-                        crate::world::components::InGameEntity,
-                    ));
-                }
-                if let Some(mesh) = grass_side_mesh {
-                    let handle = meshes.add(mesh);
-                    parent.spawn((
-                        Mesh3d(handle),
-                        MeshMaterial3d(block_assets.grass_side_material.clone()),
-                        Transform::default(),
-                        GlobalTransform::default(),
-                        Visibility::Visible,
-                        crate::world::components::InGameEntity,
-                    ));
-                }
-                if let Some(mesh) = dirt_mesh {
-                    let handle = meshes.add(mesh);
-                    parent.spawn((
-                        Mesh3d(handle),
-                        MeshMaterial3d(block_assets.dirt_material.clone()),
-                        Transform::default(),
-                        GlobalTransform::default(),
-                        Visibility::Visible,
-                        crate::world::components::InGameEntity,
-                    ));
-                }
-                if let Some(mesh) = stone_mesh {
-                    let handle = meshes.add(mesh);
-                    parent.spawn((
-                        Mesh3d(handle),
-                        MeshMaterial3d(block_assets.stone_material.clone()),
-                        Transform::default(),
-                        GlobalTransform::default(),
-                        Visibility::Visible,
-                        crate::world::components::InGameEntity,
-                    ));
-                }
-                if let Some(mesh) = coal_ore_mesh {
-                    let handle = meshes.add(mesh);
-                    parent.spawn((
-                        Mesh3d(handle),
-                        MeshMaterial3d(block_assets.coal_ore_material.clone()),
-                        Transform::default(),
-                        GlobalTransform::default(),
-                        Visibility::Visible,
-                        crate::world::components::InGameEntity,
-                    ));
-                }
-                if let Some(mesh) = iron_ore_mesh {
-                    let handle = meshes.add(mesh);
-                    parent.spawn((
-                        Mesh3d(handle),
-                        MeshMaterial3d(block_assets.iron_ore_material.clone()),
-                        Transform::default(),
-                        GlobalTransform::default(),
-                        Visibility::Visible,
-                        crate::world::components::InGameEntity,
-                    ));
-                }
-                if let Some(mesh) = gold_ore_mesh {
-                    let handle = meshes.add(mesh);
-                    parent.spawn((
-                        Mesh3d(handle),
-                        MeshMaterial3d(block_assets.gold_ore_material.clone()),
-                        Transform::default(),
-                        GlobalTransform::default(),
-                        Visibility::Visible,
-                        crate::world::components::InGameEntity,
-                    ));
-                }
-                if let Some(mesh) = diamond_ore_mesh {
-                    let handle = meshes.add(mesh);
-                    parent.spawn((
-                        Mesh3d(handle),
-                        MeshMaterial3d(block_assets.diamond_ore_material.clone()),
-                        Transform::default(),
-                        GlobalTransform::default(),
-                        Visibility::Visible,
-                        crate::world::components::InGameEntity,
-                    ));
-                }
-                if let Some(mesh) = bedrock_mesh {
-                    let handle = meshes.add(mesh);
-                    parent.spawn((
-                        Mesh3d(handle),
-                        MeshMaterial3d(block_assets.bedrock_material.clone()),
+                for (material, buffer) in buffers.into_iter().enumerate() {
+                    if buffer.is_empty() {
+                        continue;
+                    }
+                    let translucent =
+                        registry.materials[material].render_layer == RenderLayer::Translucent;
+                    let centers = translucent.then(|| buffer.face_centers());
+                    let mut mesh = buffer.into_mesh();
+                    if translucent {
+                        // Kept in the main world so sort_translucent_faces can
+                        // reorder its indices
+                        mesh.asset_usage = RenderAssetUsages::default();
+                    }
+                    let mut child = parent.spawn((
+                        Mesh3d(meshes.add(mesh)),
+                        MeshMaterial3d(block_assets.block_materials[material].clone()),
                         Transform::default(),
                         GlobalTransform::default(),
-                        Visibility::Visible,
-                        crate::world::components::InGameEntity,
-                    ));
-                }
-                if let Some(mesh) = tall_grass_mesh {
-                    let handle = meshes.add(mesh);
-                    parent.spawn((
-                        Mesh3d(handle),
-                        MeshMaterial3d(block_assets.tall_grass_material.clone()),
-                        Transform::default(),
-                        GlobalTransform::default(),
-                        Visibility::Visible,
+                        // Inherited, so culling can hide the whole section
+                        Visibility::Inherited,
                         crate::world::components::InGameEntity,
                     ));
+                    if let Some(centers) = centers {
+                        child.insert(TranslucentFaces {
+                            centers,
+                            sorted_from: None,
+                        });
+                    }
                 }
             });
 
@@ -829,6 +812,40 @@ pub fn update_chunk_mesh(
     }
 }
 
+/// Bevy sorts transparent meshes against each other but not the triangles
+/// inside one, so translucent chunk meshes get their quads ordered back to
+/// front whenever the camera enters a new block.
+pub fn sort_translucent_faces(
+    mut meshes: ResMut<Assets<Mesh>>,
+    camera_query: Query<&GlobalTransform, With<Camera3d>>,
+    mut query: Query<(&Mesh3d, &GlobalTransform, &mut TranslucentFaces)>,
+) {
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+
+    for (mesh, transform, mut faces) in query.iter_mut() {
+        let camera_pos = camera.translation() - transform.translation();
+        let camera_block = camera_pos.floor().as_ivec3();
+        if faces.sorted_from == Some(camera_block) {
+            continue;
+        }
+        let Some(mesh) = meshes.get_mut(&mesh.0) else {
+            continue;
+        };
+        faces.sorted_from = Some(camera_block);
+
+        let mut order: Vec<u32> = (0..faces.centers.len() as u32).collect();
+        order.sort_by(|&a, &b| {
+            let distance_a = faces.centers[a as usize].distance_squared(camera_pos);
+            let distance_b = faces.centers[b as usize].distance_squared(camera_pos);
+            distance_b.total_cmp(&distance_a)
+        });
+        let indices = order.into_iter().flat_map(quad_indices).collect();
+        mesh.insert_indices(Indices::U32(indices));
+    }
+}
+
 pub fn update_game_time(
     time: Res<Time>,
     mut game_time: ResMut<crate::world::components::GameTime>,
@@ -890,91 +907,23 @@ pub fn update_game_time(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn setup_world(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     asset_server: Res<AssetServer>,
+    packs: Res<ResourcePacks>,
+    registry: Res<BlockRegistry>,
+    items: Res<ItemRegistry>,
     mut voxel_world: ResMut<VoxelWorld>,
     settings: Res<Settings>,
     world_settings: Res<WorldSettings>,
 ) {
     commands.insert_resource(InitialChunkMeshing(true));
-    let dirt_texture = asset_server.load_with_settings(
-        "textures/block/dirt.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let grass_top_texture = asset_server.load_with_settings(
-        "textures/block/grass_block_top.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let grass_side_texture = asset_server.load_with_settings(
-        "textures/block/grass_block_side.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let stone_texture = asset_server.load_with_settings(
-        "textures/block/stone.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let coal_ore_texture = asset_server.load_with_settings(
-        "textures/block/coal_ore.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let iron_ore_texture = asset_server.load_with_settings(
-        "textures/block/iron_ore.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let gold_ore_texture = asset_server.load_with_settings(
-        "textures/block/gold_ore.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let diamond_ore_texture = asset_server.load_with_settings(
-        "textures/block/diamond_ore.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let bedrock_texture = asset_server.load_with_settings(
-        "textures/block/bedrock.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let tall_grass_texture = asset_server.load_with_settings(
-        "textures/block/short_grass.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-    let wheat_texture = asset_server.load_with_settings(
-        "textures/item/wheat.png",
-        |settings: &mut ImageLoaderSettings| {
-            settings.sampler = ImageSampler::nearest();
-        },
-    );
-
     let mut destroy_stages = Vec::new();
     for i in 0..10 {
-        let texture = asset_server.load_with_settings(
-            format!("textures/block/destroy_stage_{}.png", i),
-            |settings: &mut ImageLoaderSettings| {
-                settings.sampler = ImageSampler::nearest();
-            },
-        );
+        let texture = packs.load_image(&asset_server, &destroy_stage_texture(i));
         destroy_stages.push(materials.add(StandardMaterial {
             base_color_texture: Some(texture),
             alpha_mode: AlphaMode::Blend,
@@ -984,79 +933,41 @@ pub fn setup_world(
     }
 
     let mesh_handle = meshes.add(Cuboid::from_size(Vec3::ONE));
-    let grass_top_material = materials.add(StandardMaterial {
-        base_color_texture: Some(grass_top_texture),
-        base_color: Color::srgb(0.5, 0.8, 0.4),
-        ..default()
-    });
-    let grass_side_material = materials.add(StandardMaterial {
-        base_color_texture: Some(grass_side_texture),
-        base_color: Color::WHITE,
-        ..default()
-    });
-    let dirt_material = materials.add(StandardMaterial {
-        base_color_texture: Some(dirt_texture),
-        base_color: Color::WHITE,
-        ..default()
-    });
-    let stone_material = materials.add(StandardMaterial {
-        base_color_texture: Some(stone_texture),
-        base_color: Color::WHITE,
-        ..default()
-    });
-    let coal_ore_material = materials.add(StandardMaterial {
-        base_color_texture: Some(coal_ore_texture),
-        base_color: Color::WHITE,
-        ..default()
-    });
-    let iron_ore_material = materials.add(StandardMaterial {
-        base_color_texture: Some(iron_ore_texture),
-        base_color: Color::WHITE,
-        ..default()
-    });
-    let gold_ore_material = materials.add(StandardMaterial {
-        base_color_texture: Some(gold_ore_texture),
-        base_color: Color::WHITE,
-        ..default()
-    });
-    let diamond_ore_material = materials.add(StandardMaterial {
-        base_color_texture: Some(diamond_ore_texture),
-        base_color: Color::WHITE,
-        ..default()
-    });
-    let bedrock_material = materials.add(StandardMaterial {
-        base_color_texture: Some(bedrock_texture),
-        base_color: Color::WHITE,
-        ..default()
-    });
-    let tall_grass_material = materials.add(StandardMaterial {
-        base_color_texture: Some(tall_grass_texture),
-        base_color: Color::srgb(0.5, 0.8, 0.4),
-        alpha_mode: AlphaMode::Mask(0.5),
-        cull_mode: None,
-        ..default()
-    });
-    let wheat_material = materials.add(StandardMaterial {
-        base_color_texture: Some(wheat_texture),
-        base_color: Color::WHITE,
-        alpha_mode: AlphaMode::Mask(0.5),
-        cull_mode: None,
-        ..default()
-    });
+    let block_materials: Vec<Handle<StandardMaterial>> = registry
+        .materials
+        .iter()
+        .map(|def| materials.add(block_material(def, &packs, &asset_server)))
+        .collect();
+    commands.insert_resource(BlockAnimations::new(
+        &registry,
+        &block_materials,
+        &materials,
+        &packs,
+    ));
+
+    // Block items look like their block; everything else is a flat cutout
+    // of its icon.
+    let mut item_materials = vec![Handle::default(); items.id_count()];
+    for (item, def) in items.iter() {
+        item_materials[item.0 as usize] = match def.block {
+            Some(block) => {
+                let side = registry.get(block).face_materials[FACE_SIDE];
+                block_materials[side].clone()
+            }
+            None => materials.add(StandardMaterial {
+                base_color_texture: Some(packs.load_image(&asset_server, &def.icon)),
+                base_color: Color::WHITE,
+                alpha_mode: AlphaMode::Mask(0.5),
+                cull_mode: None,
+                ..default()
+            }),
+        };
+    }
 
     commands.insert_resource(BlockAssets {
         mesh: mesh_handle.clone(),
-        grass_top_material,
-        grass_side_material,
-        dirt_material,
-        stone_material,
-        coal_ore_material,
-        iron_ore_material,
-        gold_ore_material,
-        diamond_ore_material,
-        bedrock_material,
-        tall_grass_material,
-        wheat_material,
+        block_materials,
+        item_materials,
         destroy_stages: destroy_stages.try_into().unwrap(),
     });
 
@@ -1073,12 +984,7 @@ pub fn setup_world(
 
     // Spawn sun
     let sun_material = materials.add(StandardMaterial {
-        base_color_texture: Some(asset_server.load_with_settings(
-            "textures/environment/celestial/sun.png",
-            |settings: &mut ImageLoaderSettings| {
-                settings.sampler = ImageSampler::nearest();
-            },
-        )),
+        base_color_texture: Some(packs.load_image(&asset_server, celestial_texture(false))),
         alpha_mode: AlphaMode::Mask(0.5),
         cull_mode: None,
         unlit: true,
@@ -1094,12 +1000,7 @@ pub fn setup_world(
 
     // Spawn moon
     let moon_material = materials.add(StandardMaterial {
-        base_color_texture: Some(asset_server.load_with_settings(
-            "textures/environment/celestial/moon/full_moon.png",
-            |settings: &mut ImageLoaderSettings| {
-                settings.sampler = ImageSampler::nearest();
-            },
-        )),
+        base_color_texture: Some(packs.load_image(&asset_server, celestial_texture(true))),
         alpha_mode: AlphaMode::Mask(0.5),
         cull_mode: None,
         unlit: true,
@@ -1115,9 +1016,6 @@ pub fn setup_world(
 
     let view_distance = settings.render_distance;
     let (min_chunk_y, max_chunk_y) = world_chunk_y_range();
-    let base_height = 14.0;
-    let amplitude = 8.0;
-    let frequency = 0.04;
 
     let perlin = Perlin::new(world_settings.seed as u32);
 
@@ -1125,8 +1023,8 @@ pub fn setup_world(
         for x in -view_distance..=view_distance {
             for z in -view_distance..=view_distance {
                 let chunk_key = IVec3::new(x, y, z);
-                let chunk_data =
-                    generate_chunk(chunk_key, base_height, amplitude, frequency, &perlin);
+                let chunk_data = load_chunk(&world_settings.name, chunk_key)
+                    .unwrap_or_else(|| generate_chunk(chunk_key, &perlin));
                 let entity = commands
                     .spawn((
                         chunk_data,
@@ -1165,12 +1063,9 @@ pub fn setup_world(
                         let world_x = chunk_key.x as f32 * CHUNK_SIZE as f32 + ox;
                         let world_z = chunk_key.z as f32 * CHUNK_SIZE as f32 + oz;
 
-                        // Estimate height (could use generate_chunk but let's keep it simple for now)
-                        let noise_val = perlin.get([
-                            world_x as f64 * frequency as f64,
-                            world_z as f64 * frequency as f64,
-                        ]);
-                        let height = (base_height + noise_val as f32 * amplitude).round();
+                        let height =
+                            terrain_height(world_x.floor() as i32, world_z.floor() as i32, &perlin)
+                                as f32;
 
                         crate::mob::systems::spawn_mob_typed(
                             &mut commands,
@@ -1186,13 +1081,106 @@ pub fn setup_world(
     }
 }
 
-fn generate_chunk(
-    chunk_key: IVec3,
-    base_height: f32,
-    amplitude: f32,
-    frequency: f32,
-    perlin: &Perlin,
-) -> Chunk {
+fn destroy_stage_texture(stage: usize) -> String {
+    format!("textures/block/destroy_stage_{}.png", stage)
+}
+
+fn celestial_texture(is_moon: bool) -> &'static str {
+    if is_moon {
+        "textures/environment/celestial/moon/full_moon.png"
+    } else {
+        "textures/environment/celestial/sun.png"
+    }
+}
+
+pub fn block_material(
+    def: &BlockMaterialDef,
+    packs: &ResourcePacks,
+    asset_server: &AssetServer,
+) -> StandardMaterial {
+    let texture: Option<Handle<Image>> =
+        (!def.texture.is_empty()).then(|| packs.load_image(asset_server, &def.texture));
+    let [r, g, b] = def.tint;
+    let glow = def.light_emission as f32 / 15.0;
+    let mut material = StandardMaterial {
+        base_color_texture: texture.clone(),
+        base_color: Color::srgb(r, g, b),
+        ..default()
+    };
+    if glow > 0.0 {
+        material.emissive = LinearRgba::rgb(glow, glow, glow);
+        material.emissive_texture = texture;
+    }
+    match def.render_layer {
+        RenderLayer::Opaque => {}
+        RenderLayer::Cutout => {
+            material.alpha_mode = AlphaMode::Mask(0.5);
+            material.cull_mode = None;
+        }
+        RenderLayer::Translucent => {
+            material.alpha_mode = AlphaMode::Blend;
+            material.cull_mode = None;
+        }
+    }
+    material
+}
+
+/// Points every world material at the textures of the current resource
+/// packs. Materials are updated in place, so chunk meshes keep their handles.
+#[allow(clippy::too_many_arguments)]
+pub fn reload_world_textures(
+    mut commands: Commands,
+    mut reloaded: MessageReader<ResourcePacksReloaded>,
+    packs: Res<ResourcePacks>,
+    asset_server: Res<AssetServer>,
+    registry: Res<BlockRegistry>,
+    items: Res<ItemRegistry>,
+    block_assets: Res<BlockAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    celestial_query: Query<(&CelestialBody, &MeshMaterial3d<StandardMaterial>)>,
+) {
+    if reloaded.is_empty() {
+        return;
+    }
+    reloaded.clear();
+
+    for (def, handle) in registry.materials.iter().zip(&block_assets.block_materials) {
+        if let Some(material) = materials.get_mut(handle) {
+            *material = block_material(def, &packs, &asset_server);
+        }
+    }
+    // The new textures may have different strips or timing
+    commands.insert_resource(BlockAnimations::new(
+        &registry,
+        &block_assets.block_materials,
+        &materials,
+        &packs,
+    ));
+
+    for (item, def) in items.iter() {
+        if def.block.is_none()
+            && let Some(material) = materials.get_mut(&block_assets.item_materials[item.0 as usize])
+        {
+            material.base_color_texture = Some(packs.load_image(&asset_server, &def.icon));
+        }
+    }
+
+    for (stage, handle) in block_assets.destroy_stages.iter().enumerate() {
+        if let Some(material) = materials.get_mut(handle) {
+            material.base_color_texture =
+                Some(packs.load_image(&asset_server, &destroy_stage_texture(stage)));
+        }
+    }
+
+    for (body, handle) in celestial_query.iter() {
+        if let Some(material) = materials.get_mut(&handle.0) {
+            material.base_color_texture =
+                Some(packs.load_image(&asset_server, celestial_texture(body.is_moon)));
+        }
+    }
+}
+
+fn generate_chunk(chunk_key: IVec3, perlin: &Perlin) -> Chunk {
     let mut chunk_data = Chunk::empty();
     let chunk_world_y = chunk_key.y * CHUNK_SIZE as i32;
 
@@ -1201,17 +1189,7 @@ fn generate_chunk(
             let world_vx = chunk_key.x * CHUNK_SIZE as i32 + vx as i32;
             let world_vz = chunk_key.z * CHUNK_SIZE as i32 + vz as i32;
 
-            let noise_val = perlin.get([
-                world_vx as f64 * frequency as f64,
-                world_vz as f64 * frequency as f64,
-            ]);
-            let mut height = (base_height + noise_val as f32 * amplitude).round() as i32;
-            if height < WORLD_MIN_Y + 1 {
-                height = WORLD_MIN_Y + 1;
-            }
-            if height > WORLD_MAX_Y - 1 {
-                height = WORLD_MAX_Y - 1;
-            }
+            let height = terrain_height(world_vx, world_vz, perlin);
 
             for vy in 0..CHUNK_SIZE {
                 let world_vy = chunk_world_y + vy as i32;
@@ -1222,21 +1200,21 @@ fn generate_chunk(
                 if world_vy == WORLD_MIN_Y {
                     chunk_data.set_voxel(
                         IVec3::new(vx as i32, vy as i32, vz as i32),
-                        VoxelType::Bedrock,
+                        VoxelType::BEDROCK,
                     );
                     continue;
                 }
                 if world_vy < WORLD_MIN_Y {
                     chunk_data.set_voxel(
                         IVec3::new(vx as i32, vy as i32, vz as i32),
-                        VoxelType::Stone,
+                        VoxelType::STONE,
                     );
                     continue;
                 }
 
                 if world_vy <= height {
                     let voxel = if world_vy == height {
-                        VoxelType::Grass
+                        VoxelType::GRASS
                     } else {
                         select_stone_variant(world_vx, world_vy, world_vz)
                     };
@@ -1248,7 +1226,7 @@ fn generate_chunk(
                         if hash % 100 < 5 {
                             chunk_data.set_voxel(
                                 IVec3::new(vx as i32, vy as i32 + 1, vz as i32),
-                                VoxelType::TallGrass,
+                                VoxelType::TALL_GRASS,
                             );
                         }
                     }
@@ -1260,24 +1238,40 @@ fn generate_chunk(
     chunk_data
 }
 
+/// Height terrain noise is centered on, how far it swings either way, and
+/// how quickly it varies across the world.
+pub const TERRAIN_BASE_HEIGHT: f32 = 14.0;
+pub const TERRAIN_AMPLITUDE: f32 = 8.0;
+pub const TERRAIN_FREQUENCY: f32 = 0.04;
+
+/// Height of the surface voxel of the column at `world_vx`, `world_vz`.
+pub fn terrain_height(world_vx: i32, world_vz: i32, perlin: &Perlin) -> i32 {
+    let noise_val = perlin.get([
+        world_vx as f64 * TERRAIN_FREQUENCY as f64,
+        world_vz as f64 * TERRAIN_FREQUENCY as f64,
+    ]);
+    let height = (TERRAIN_BASE_HEIGHT + noise_val as f32 * TERRAIN_AMPLITUDE).round() as i32;
+    height.clamp(WORLD_MIN_Y + 1, WORLD_MAX_Y - 1)
+}
+
 fn select_stone_variant(x: i32, y: i32, z: i32) -> VoxelType {
     let hash = (x as i64 * 734287 + y as i64 * 912931 + z as i64 * 1237).abs();
     let roll = (hash % 100) as i32;
 
     if y < 10 && roll < 2 {
-        VoxelType::DiamondOre
+        VoxelType::DIAMOND_ORE
     } else if y < 20 && roll < 4 {
-        VoxelType::GoldOre
+        VoxelType::GOLD_ORE
     } else if y < 40 && roll < 7 {
-        VoxelType::IronOre
+        VoxelType::IRON_ORE
     } else if roll < 12 {
-        VoxelType::CoalOre
+        VoxelType::COAL_ORE
     } else {
-        VoxelType::Stone
+        VoxelType::STONE
     }
 }
 
-fn world_chunk_y_range() -> (i32, i32) {
+pub fn world_chunk_y_range() -> (i32, i32) {
     let min = (WORLD_MIN_Y as f32 / CHUNK_SIZE as f32).floor() as i32;
     let max = (WORLD_MAX_Y as f32 / CHUNK_SIZE as f32).floor() as i32;
     (min, max)